@@ -14,6 +14,14 @@ pub enum ArgumentKind {
 
     /// Flag with value
     Value,
+
+    /// Flag with value, that can be given several times. Values of all occurrences are
+    /// accumulated into a single `ParsedArgument` in order
+    Repeated,
+
+    /// Flag with optional value, that can only be given in `--key=value` form (e.g.
+    /// `--build-std` or `--build-std=core,alloc`)
+    Optional,
 }
 
 /// Represents argument metadata
@@ -31,6 +39,18 @@ pub enum ArgumentKind {
 ///     &["PROFILE"],
 ///     "Cargo profile to build the project with"
 /// )
+/// args::Argument::new_repeated(
+///     "output",
+///     &["--output", "-o"],
+///     &["OUTPUT"],
+///     "Output tables (can be repeated)"
+/// )
+/// args::Argument::new_optional(
+///     "build-std",
+///     &["--build-std"],
+///     "CRATES",
+///     "Rebuild standard library crates"
+/// )
 /// ```
 pub struct Argument {
     /// Argument name - used after `ArgumentParser::parse()` to distinguish parsed arguments
@@ -47,6 +67,9 @@ pub struct Argument {
 
     /// Description used for `ArgumentParser::print_help()`
     description: String,
+
+    /// If `true` - value can also be given without a key (e.g. `program VALUE`)
+    positional: bool,
 }
 
 impl Argument {
@@ -58,6 +81,7 @@ impl Argument {
             keys: keys.iter().map(|a| a.to_string()).collect(),
             values: values.iter().map(|a| a.to_string()).collect(),
             description: description.to_string(),
+            positional: false,
         }
     }
 
@@ -70,6 +94,29 @@ impl Argument {
     pub fn new_value(name: &str, keys: &[&str], values: &[&str], description: &str) -> Self {
         Self::new(name, ArgumentKind::Value, keys, values, description)
     }
+
+    /// Creates new repeatable argument with value. Omits unnecessary arguments from `new()`
+    pub fn new_repeated(name: &str, keys: &[&str], values: &[&str], description: &str) -> Self {
+        Self::new(name, ArgumentKind::Repeated, keys, values, description)
+    }
+
+    /// Creates new argument with optional value. Omits unnecessary arguments from `new()`
+    pub fn new_optional(name: &str, keys: &[&str], value: &str, description: &str) -> Self {
+        Self::new(name, ArgumentKind::Optional, keys, &[value], description)
+    }
+
+    /// Allows value of the argument to be given without a key. Bare values are assigned to
+    /// positional arguments in order of declaration: `Value` argument takes one, `Repeated` - all
+    /// the rest. Only arguments with a single value can be positional
+    pub fn positional(mut self) -> Self {
+        assert!(
+            matches!(self.kind, ArgumentKind::Value | ArgumentKind::Repeated) && self.values.len() == 1,
+            "Positional argument '{}' must have exactly one value", self.name
+        );
+
+        self.positional = true;
+        self
+    }
 }
 
 /// Represents a parsed argument
@@ -77,7 +124,8 @@ pub struct ParsedArgument {
     /// Argument name from `Argument`
     pub name: String,
 
-    /// Parsed values (empty for Flag)
+    /// Parsed values (empty for Flag & Optional without value, values of all occurrences for
+    /// Repeated)
     pub values: Vec<String>,
 }
 
@@ -88,6 +136,9 @@ pub struct ParsedArguments {
 
     /// Unrecognized arguments
     pub leftover: Vec<String>,
+
+    /// Arguments after `--`, which are not parsed, and are returned as is
+    pub trailing: Vec<String>,
 }
 
 /// Defines policy on encountering unexpected argument
@@ -185,12 +236,22 @@ impl ArgumentParser {
         for name in self.order.iter() {
             let arg = &self.args[name];
 
+            let mut keys = match arg.kind {
+                ArgumentKind::Optional => arg.keys.join(", ") + "[=" + arg.values.join(" ").as_str() + "]",
+                _                      => arg.keys.join(", ") + " " + arg.values.join(" ").as_str(),
+            };
+
+            // Positional form goes first, e.g. `[FILE], --file FILE`
+            if arg.positional {
+                keys = format!("[{}], {}", arg.values.join(" "), keys);
+            }
+
             table.push_row([
                 // 4 spaces for prettiness
                 "    ",
 
                 // Join all argument keys + argument values into single column in this row
-                (arg.keys.join(", ") + " " + arg.values.join(" ").as_str()).as_str(),
+                keys.as_str(),
 
                 // Delimiter between argument keys + values and description
                 " - ",
@@ -203,35 +264,94 @@ impl ArgumentParser {
         table.print();
     }
 
-    /// Handles expected arguments
-    fn handle_expected(&self, result: &mut ParsedArguments, arg: String, args: &mut impl Iterator<Item = String>) {
+    /// Handles expected arguments. `inline` is a value, given in `--key=value` form, it's used
+    /// as the first value of the argument
+    fn handle_expected(
+        &self,
+        result: &mut ParsedArguments,
+        arg: String,
+        mut inline: Option<String>,
+        args: &mut impl Iterator<Item = String>
+    ) {
         // This `.unwrap()` here should panic, as this function is called only when the argument
         // key was already confirmed to be declared and known in this parser
         let arg = self.args.get(&self.keymap[&arg]).unwrap();
 
+        if arg.kind == ArgumentKind::Flag && inline.is_some() {
+            panic!("Argument '{}' doesn't take a value", arg.name);
+        }
+
+        // Optional value is never taken from the next argument, as it can't be told apart from
+        // other arguments
+        let optional = match arg.kind {
+            ArgumentKind::Optional => inline.take().into_iter().collect(),
+            _                      => vec![],
+        };
+
+        let mut args = inline.into_iter().chain(args);
+
         match arg.kind {
-            ArgumentKind::Flag => {
+            ArgumentKind::Flag | ArgumentKind::Optional => {
                 result.args.push(ParsedArgument {
                     name: arg.name.clone(),
-                    values: vec![],
+                    values: optional,
                 });
             }
             ArgumentKind::Value => {
                 result.args.push(ParsedArgument {
                     name: arg.name.clone(),
-                    values: {
-                        let mut values = Vec::new();
+                    values: Self::consume_values(arg, &mut args),
+                });
+            }
+            ArgumentKind::Repeated => {
+                let values = Self::consume_values(arg, &mut args);
+
+                // Accumulate into the first occurrence
+                if let Some(parsed) = result.args.iter_mut().find(|a| a.name == arg.name) {
+                    parsed.values.extend(values);
+                } else {
+                    result.args.push(ParsedArgument { name: arg.name.clone(), values });
+                }
+            }
+        }
+    }
 
-                        // Consume all expected values
-                        for value in arg.values.iter() {
-                            values.push(args.next().expect(format!("Expected value '{}' for argument '{}'", value, arg.name).as_str()));
-                        }
+    /// Consumes all values, expected by `arg`
+    fn consume_values(arg: &Argument, args: &mut impl Iterator<Item = String>) -> Vec<String> {
+        let mut values = Vec::new();
 
-                        values
-                    },
-                });
+        for value in arg.values.iter() {
+            values.push(args.next().unwrap_or_else(|| panic!("Expected value '{}' for argument '{}'", value, arg.name)));
+        }
+
+        values
+    }
+
+    /// Handles bare values, assigning them to positional arguments. Returns `false`, if there
+    /// is no positional argument left to take the value
+    fn handle_positional(&self, result: &mut ParsedArguments, value: &str) -> bool {
+        for name in self.order.iter() {
+            let arg = &self.args[name];
+
+            if !arg.positional {
+                continue;
+            }
+
+            match result.args.iter_mut().find(|a| a.name == arg.name) {
+                Some(parsed) if arg.kind == ArgumentKind::Repeated => {
+                    parsed.values.push(value.to_string());
+                }
+                // `Value` arguments can only be given once
+                Some(_) => continue,
+                None => {
+                    result.args.push(ParsedArgument { name: arg.name.clone(), values: vec![value.to_string()] });
+                }
             }
+
+            return true;
         }
+
+        false
     }
 
     /// Handles unexpected arguments
@@ -252,12 +372,20 @@ impl ArgumentParser {
     /// Performs actual parsing of the arguments.
     /// Arguments are passed using an iterator
     pub fn parse(&self, mut args: impl Iterator<Item = String>) -> ParsedArguments {
-        let mut result = ParsedArguments { args: Vec::new(), leftover: Vec::new() };
+        let mut result = ParsedArguments { args: Vec::new(), leftover: Vec::new(), trailing: Vec::new() };
 
         while let Some(arg) = args.next() {
+            if arg == "--" {
+                result.trailing.extend(args);
+                break;
+            }
+
             if self.keymap.contains_key(&arg) {
-                self.handle_expected(&mut result, arg, &mut args);
-            } else {
+                self.handle_expected(&mut result, arg, None, &mut args);
+            } else if let Some((key, value)) = arg.split_once('=').filter(|(key, _)| self.keymap.contains_key(*key)) {
+                // `--key=value` form
+                self.handle_expected(&mut result, key.to_string(), Some(value.to_string()), &mut args);
+            } else if arg.starts_with('-') || !self.handle_positional(&mut result, &arg) {
                 self.handle_unexpected(&mut result, arg);
             }
         }
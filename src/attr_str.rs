@@ -5,12 +5,14 @@
 //!
 
 
-use std::fmt::{Display, Debug, Formatter};
+use std::fmt::{Display, Debug, Formatter, Write};
+use std::ops::Range;
+use crate::util;
 
 /// Enum for abstracting ANSI color/text manipulation sequences
 /// 
-/// It's not even half complete, and this crate uses maybe 6-10 sequences, but I plan on allowing
-/// users to redefine color scheme sometimes in the future, so it get `allow(dead_code)` for now
+/// This crate uses maybe 6-10 sequences by itself, others are only reachable through user themes
+/// (see `TryFrom<&str>`), so it gets `allow(dead_code)`
 /// 
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -35,6 +37,22 @@ pub enum Attribute {
     ColorFgCyan,
     ColorFgWhite,
     ColorFgDefault,
+
+    /// True color foreground (`#rrggbb` in themes)
+    ColorFgRgb(u8, u8, u8),
+
+    ColorBgBlack,
+    ColorBgRed,
+    ColorBgGreen,
+    ColorBgYellow,
+    ColorBgBlue,
+    ColorBgMagenta,
+    ColorBgCyan,
+    ColorBgWhite,
+    ColorBgDefault,
+
+    /// True color background (`bg-#rrggbb` in themes)
+    ColorBgRgb(u8, u8, u8),
 }
 
 impl Display for Attribute {
@@ -59,10 +77,132 @@ impl Display for Attribute {
             Attribute::ColorFgCyan       => f.write_str("\x1b[36m"),
             Attribute::ColorFgWhite      => f.write_str("\x1b[37m"),
             Attribute::ColorFgDefault    => f.write_str("\x1b[39m"),
+            Attribute::ColorBgBlack      => f.write_str("\x1b[40m"),
+            Attribute::ColorBgRed        => f.write_str("\x1b[41m"),
+            Attribute::ColorBgGreen      => f.write_str("\x1b[42m"),
+            Attribute::ColorBgYellow     => f.write_str("\x1b[43m"),
+            Attribute::ColorBgBlue       => f.write_str("\x1b[44m"),
+            Attribute::ColorBgMagenta    => f.write_str("\x1b[45m"),
+            Attribute::ColorBgCyan       => f.write_str("\x1b[46m"),
+            Attribute::ColorBgWhite      => f.write_str("\x1b[47m"),
+            Attribute::ColorBgDefault    => f.write_str("\x1b[49m"),
+            Attribute::ColorFgRgb(r, g, b) => write!(f, "\x1b[38;2;{};{};{}m", r, g, b),
+            Attribute::ColorBgRgb(r, g, b) => write!(f, "\x1b[48;2;{};{};{}m", r, g, b),
+        }
+    }
+}
+
+/// Parses attribute from its name in user theme: text modifications (`bold`, `dim`, ...),
+/// foreground colors (`red`, `green`, `#rrggbb`, ...) and background colors (`bg-red`,
+/// `bg-green`, `bg-#rrggbb`, ...)
+impl TryFrom<&str> for Attribute {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if let Some(hex) = value.strip_prefix('#') {
+            let (r, g, b) = Self::parse_rgb(hex).ok_or_else(|| format!("Invalid color: '{}' (expected #rrggbb)", value))?;
+
+            return Ok(Attribute::ColorFgRgb(r, g, b));
+        }
+
+        if let Some(hex) = value.strip_prefix("bg-#") {
+            let (r, g, b) = Self::parse_rgb(hex).ok_or_else(|| format!("Invalid color: '{}' (expected bg-#rrggbb)", value))?;
+
+            return Ok(Attribute::ColorBgRgb(r, g, b));
+        }
+
+        match value {
+            "reset"         => Ok(Attribute::TextReset),
+            "bold"          => Ok(Attribute::TextBold),
+            "dim"           => Ok(Attribute::TextDim),
+            "italic"        => Ok(Attribute::TextItalic),
+            "underline"     => Ok(Attribute::TextUnderline),
+            "blink"         => Ok(Attribute::TextBlink),
+            "inverse"       => Ok(Attribute::TextInverse),
+            "hidden"        => Ok(Attribute::TextHidden),
+            "strikethrough" => Ok(Attribute::TextStrikethrough),
+            "black"         => Ok(Attribute::ColorFgBlack),
+            "red"           => Ok(Attribute::ColorFgRed),
+            "green"         => Ok(Attribute::ColorFgGreen),
+            "yellow"        => Ok(Attribute::ColorFgYellow),
+            "blue"          => Ok(Attribute::ColorFgBlue),
+            "magenta"       => Ok(Attribute::ColorFgMagenta),
+            "cyan"          => Ok(Attribute::ColorFgCyan),
+            "white"         => Ok(Attribute::ColorFgWhite),
+            "default"       => Ok(Attribute::ColorFgDefault),
+            "bg-black"      => Ok(Attribute::ColorBgBlack),
+            "bg-red"        => Ok(Attribute::ColorBgRed),
+            "bg-green"      => Ok(Attribute::ColorBgGreen),
+            "bg-yellow"     => Ok(Attribute::ColorBgYellow),
+            "bg-blue"       => Ok(Attribute::ColorBgBlue),
+            "bg-magenta"    => Ok(Attribute::ColorBgMagenta),
+            "bg-cyan"       => Ok(Attribute::ColorBgCyan),
+            "bg-white"      => Ok(Attribute::ColorBgWhite),
+            "bg-default"    => Ok(Attribute::ColorBgDefault),
+            _               => Err(format!(
+                "Unknown attribute: '{}' (possible values: bold, dim, italic, underline, blink, inverse, \
+                 hidden, strikethrough, black, red, green, yellow, blue, magenta, cyan, white, default, \
+                 #rrggbb, and the same colors with 'bg-' prefix for background)",
+                value
+            )),
+        }
+    }
+}
+
+impl Attribute {
+    /// Parses `rrggbb` hex color
+    fn parse_rgb(hex: &str) -> Option<(u8, u8, u8)> {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+
+        Some((channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// Blends two true colors of the same kind (foreground or background), `t` being position
+    /// between `self` (0.0) and `other` (1.0). Returns `None` for other attributes
+    pub fn blend(&self, other: &Attribute, t: f32) -> Option<Attribute> {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+
+        match (self, other) {
+            (Attribute::ColorFgRgb(r1, g1, b1), Attribute::ColorFgRgb(r2, g2, b2)) => {
+                Some(Attribute::ColorFgRgb(mix(*r1, *r2), mix(*g1, *g2), mix(*b1, *b2)))
+            }
+            (Attribute::ColorBgRgb(r1, g1, b1), Attribute::ColorBgRgb(r2, g2, b2)) => {
+                Some(Attribute::ColorBgRgb(mix(*r1, *r2), mix(*g1, *g2), mix(*b1, *b2)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses list of attributes from theme config value - either a single name, or an array
+    /// of names (e.g. `"bg-blue"` or `["bold", "bg-blue"]`)
+    pub fn parse_list(value: &toml::Value) -> Result<Vec<Attribute>, String> {
+        match value {
+            toml::Value::String(name) => Ok(vec![Attribute::try_from(name.as_str())?]),
+            toml::Value::Array(names) => names.iter()
+                .map(|name| name.as_str()
+                    .ok_or_else(|| format!("Attribute must be a string, got '{}'", name))
+                    .and_then(Attribute::try_from))
+                .collect(),
+            _ => Err(format!("Attributes must be a string or an array of strings, got '{}'", value)),
         }
     }
 }
 
+/// Range of `AttributeString`, that has its own attributes
+#[derive(Debug, Clone)]
+struct Span {
+    /// Byte range in the string
+    range: Range<usize>,
+
+    /// Attributes, that replace attributes of the whole string in `range`
+    attrs: Vec<Attribute>,
+}
+
 /// String with attributes
 #[derive(Clone)]
 pub struct AttributeString {
@@ -71,6 +211,12 @@ pub struct AttributeString {
     
     /// List of attributes
     attrs: Vec<Attribute>,
+
+    /// URL of terminal hyperlink (OSC 8), that the string is wrapped into
+    link: Option<String>,
+
+    /// Ranges with their own attributes, sorted and non-overlapping
+    spans: Vec<Span>,
 }
 
 impl AttributeString {
@@ -79,6 +225,8 @@ impl AttributeString {
         AttributeString {
             str: str.to_string(),
             attrs: attrs.to_vec(),
+            link: None,
+            spans: Vec::new(),
         }
     }
 
@@ -87,12 +235,28 @@ impl AttributeString {
         AttributeString {
             str: str.to_string(),
             attrs: Vec::new(),
+            link: None,
+            spans: Vec::new(),
         }
     }
 
-    /// Returns underlying string's value
+    /// Returns display width of underlying string (in terminal columns, not bytes). Escape
+    /// sequences embedded into the string don't count
     pub fn len(&self) -> usize {
-        self.str.len()
+        util::str_width(&self.str)
+    }
+
+    /// Appends `str` (without spans) to the string
+    pub fn push_str(&mut self, str: &str) {
+        self.str.push_str(str);
+    }
+
+    /// Shortens the string to at most `n` terminal columns. Embedded escape sequences are never
+    /// cut in half, and spans are clipped to the remaining part
+    pub fn truncate_display(&mut self, n: usize) {
+        let (head, _) = util::split_at_width(&self.str, n);
+
+        *self = self.slice(0..head.len());
     }
 
     /// Pushes new attribute into attribute list
@@ -100,24 +264,138 @@ impl AttributeString {
         self.attrs.push(attr);
     }
 
+    /// Makes the string a terminal hyperlink to `url`. Terminals without OSC 8 support just
+    /// print the string
+    pub fn set_link(&mut self, url: String) {
+        self.link = Some(url);
+    }
+
+    /// Gives byte `range` of the string its own `attrs`, which replace attributes of the whole
+    /// string there (e.g. dim part of a bold string). Overlapping spans are ignored
+    pub fn push_span(&mut self, range: Range<usize>, attrs: &[Attribute]) {
+        if range.is_empty() || range.end > self.str.len() {
+            return;
+        }
+
+        if self.spans.iter().any(|span| span.range.start < range.end && range.start < span.range.end) {
+            return;
+        }
+
+        let index = self.spans.partition_point(|span| span.range.start < range.start);
+
+        self.spans.insert(index, Span { range, attrs: attrs.to_vec() });
+    }
+
     /// Returns underlying string
     pub fn string(&self) -> &String {
         &self.str
     }
 
-    /// Applies all attributes
-    pub fn attrs_apply(&self) {
-        for attr in &self.attrs {
-            print!("{}", attr);
+    /// Returns a copy, that contains only `parts` (byte ranges in order) of the string, joined
+    /// with `sep`. Attributes, link & spans (clipped to the parts) are kept. Used to shorten or
+    /// split the string, without losing its spans
+    pub fn excerpt(&self, parts: &[Range<usize>], sep: &str) -> AttributeString {
+        let mut excerpt = AttributeString::new("", &self.attrs);
+
+        excerpt.link = self.link.clone();
+
+        for (i, part) in parts.iter().enumerate() {
+            if i > 0 {
+                excerpt.str.push_str(sep);
+            }
+
+            let offset = excerpt.str.len();
+
+            for span in &self.spans {
+                let start = span.range.start.max(part.start);
+                let end = span.range.end.min(part.end);
+
+                if start < end {
+                    excerpt.spans.push(Span {
+                        range: start - part.start + offset..end - part.start + offset,
+                        attrs: span.attrs.clone(),
+                    });
+                }
+            }
+
+            excerpt.str.push_str(&self.str[part.clone()]);
+        }
+
+        excerpt
+    }
+
+    /// Returns a copy, that contains only byte `range` of the string (see `excerpt()`)
+    pub fn slice(&self, range: Range<usize>) -> AttributeString {
+        self.excerpt(std::slice::from_ref(&range), "")
+    }
+
+    /// Writes the string into `w`, switching attributes at span boundaries. Expects attributes
+    /// of the whole string to be already written (see `write_attrs()`)
+    pub fn write_str(&self, w: &mut impl Write) -> std::fmt::Result {
+        self.write_str_within(w, &[])
+    }
+
+    /// Same as `write_str()`, but `outer` attributes (e.g. of table row) are restored along
+    /// with attributes of the string after each span, and kept inside spans
+    pub fn write_str_within(&self, w: &mut impl Write, outer: &[&[Attribute]]) -> std::fmt::Result {
+        let mut pos = 0;
+
+        for span in &self.spans {
+            w.write_str(&self.str[pos..span.range.start])?;
+
+            write!(w, "{}", Attribute::TextReset)?;
+
+            for attrs in outer {
+                write_attrs(w, attrs)?;
+            }
+
+            write_attrs(w, &span.attrs)?;
+
+            w.write_str(&self.str[span.range.clone()])?;
+
+            write!(w, "{}", Attribute::TextReset)?;
+
+            for attrs in outer {
+                write_attrs(w, attrs)?;
+            }
+
+            write_attrs(w, &self.attrs)?;
+
+            pos = span.range.end;
+        }
+
+        w.write_str(&self.str[pos..])
+    }
+
+    /// Writes escape sequences of all attributes (and hyperlink start) into `w`
+    pub fn write_attrs(&self, w: &mut impl Write) -> std::fmt::Result {
+        write_attrs(w, &self.attrs)?;
+
+        if let Some(link) = &self.link {
+            write!(w, "\x1b]8;;{}\x1b\\", link)?;
         }
+
+        Ok(())
     }
 
-    /// Resets all attributes
-    pub fn attrs_reset(&self) {
-        print!("{}", Attribute::TextReset)
+    /// Writes escape sequence, that resets all attributes (and ends hyperlink), into `w`
+    pub fn write_reset(&self, w: &mut impl Write) -> std::fmt::Result {
+        if self.link.is_some() {
+            w.write_str("\x1b]8;;\x1b\\")?;
+        }
+
+        write!(w, "{}", Attribute::TextReset)
     }
 }
 
+/// Writes escape sequences of `attrs` into `w`
+pub fn write_attrs(w: &mut impl Write, attrs: &[Attribute]) -> std::fmt::Result {
+    for attr in attrs {
+        write!(w, "{}", attr)?;
+    }
+
+    Ok(())
+}
 
 /// Creates an attributeless `AttributeString` from `&str`
 impl From<&str> for AttributeString {
@@ -150,18 +428,15 @@ impl<const N: usize> From<(String, &[Attribute; N])> for AttributeString {
 /// Print string along with attributes
 impl Display for AttributeString {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for attr in &self.attrs {
-            write!(f, "{}", attr)?;
-        }
-
-        write!(f, "{}", self.str)?;
-        write!(f, "{}", Attribute::TextReset)
+        self.write_attrs(f)?;
+        self.write_str(f)?;
+        self.write_reset(f)
     }
 }
 
 /// Used for debug
 impl Debug for AttributeString {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "(\"{}\" {:?})", self.str, self.attrs)
+        write!(f, "(\"{}\" {:?} {:?} {:?})", self.str, self.attrs, self.link, self.spans)
     }
 }
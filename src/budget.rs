@@ -0,0 +1,163 @@
+//! # `binsize::budget`
+//!
+//! Implements size budgets - limits on total size, crate/section size, or memory region usage,
+//! which are checked after analysis, failing `binsize` if any of them are exceeded
+//!
+
+use std::fmt::Display;
+
+/// Budget limit
+#[derive(Clone, Copy)]
+pub enum Limit {
+    /// Absolute limit in bytes
+    Bytes(usize),
+
+    /// Limit in percent of memory region size (only valid for regions)
+    Percent(f32),
+}
+
+impl Limit {
+    /// Parses size limit. Possible values:
+    ///  - integer (bytes)
+    ///  - string with a number, suffixed with `B`, `K`/`KiB`, `M`/`MiB` (powers of 1024) or
+    ///    `KB`/`MB` (powers of 1000), e.g. `"150KiB"`, `"1.5MiB"`
+    ///  - string with a number, suffixed with `%` (only for regions), e.g. `"95%"`
+    pub fn parse(value: &toml::Value) -> Result<Self, String> {
+        let str = match value {
+            toml::Value::Integer(val) => return Ok(Limit::Bytes(*val as usize)),
+            toml::Value::String(val)  => val.trim(),
+            _ => return Err(format!("Budget limit must be an integer or a string, got '{}'", value)),
+        };
+
+        if let Some(percent) = str.strip_suffix('%') {
+            return percent.trim().parse::<f32>()
+                .map(Limit::Percent)
+                .map_err(|_| format!("Invalid budget percentage: '{}'", str));
+        }
+
+        // Longer suffixes go first, so `KiB` isn't matched as `B`
+        let units: &[(&str, f64)] = &[
+            ("KiB", 1024.0),
+            ("MiB", 1024.0 * 1024.0),
+            ("GiB", 1024.0 * 1024.0 * 1024.0),
+            ("KB",  1000.0),
+            ("MB",  1000.0 * 1000.0),
+            ("GB",  1000.0 * 1000.0 * 1000.0),
+            ("K",   1024.0),
+            ("M",   1024.0 * 1024.0),
+            ("G",   1024.0 * 1024.0 * 1024.0),
+            ("B",   1.0),
+        ];
+
+        let (number, multiplier) = units.iter()
+            .find_map(|(suffix, multiplier)| Some((str.strip_suffix(suffix)?, *multiplier)))
+            .unwrap_or((str, 1.0));
+
+        number.trim().parse::<f64>()
+            .map(|n| Limit::Bytes((n * multiplier) as usize))
+            .map_err(|_| format!("Invalid budget size: '{}'", str))
+    }
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Limit::Bytes(bytes)     => write!(f, "{}", bytes),
+            Limit::Percent(percent) => write!(f, "{:.02}%", percent),
+        }
+    }
+}
+
+/// What budget limits
+#[derive(Clone)]
+pub enum Target {
+    /// Total size of all symbols
+    Total,
+
+    /// Size of a crate (calculated from symbols)
+    Crate(String),
+
+    /// Size of a section
+    Section(String),
+
+    /// Used space of a memory region from linker script
+    Region(String),
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Target::Total         => write!(f, "total"),
+            Target::Crate(name)   => write!(f, "crate.{}", name),
+            Target::Section(name) => write!(f, "section.{}", name),
+            Target::Region(name)  => write!(f, "region.{}", name),
+        }
+    }
+}
+
+/// Single budget
+#[derive(Clone)]
+pub struct Budget {
+    pub target: Target,
+    pub limit: Limit,
+}
+
+impl Budget {
+    /// Parses budgets from `[binsize.budget]` config section:
+    ///
+    /// ```rust,ignore
+    /// [binsize.budget]
+    /// total = "1MiB"
+    /// crate.serde = "150KiB"
+    /// section.".rodata" = "64K"
+    /// region.FLASH = "95%"
+    /// ```
+    pub fn parse_table(table: &toml::Table) -> Result<Vec<Budget>, String> {
+        let mut budgets = Vec::new();
+
+        for (key, value) in table {
+            match key.as_str() {
+                "total" => {
+                    budgets.push(Budget { target: Target::Total, limit: Limit::parse(value)? });
+                }
+                "crate" | "section" | "region" => {
+                    let inner = value.as_table()
+                        .ok_or_else(|| format!("Budget '{}' must be a table of NAME = LIMIT", key))?;
+
+                    for (name, value) in inner {
+                        let target = match key.as_str() {
+                            "crate"   => Target::Crate(name.clone()),
+                            "section" => Target::Section(name.clone()),
+                            _         => Target::Region(name.clone()),
+                        };
+
+                        budgets.push(Budget { target, limit: Limit::parse(value)? });
+                    }
+                }
+                _ => {
+                    return Err(format!("Unknown budget '{}' (possible values: total, crate, section, region)", key));
+                }
+            }
+        }
+
+        for budget in budgets.iter() {
+            if matches!(budget.limit, Limit::Percent(_)) && !matches!(budget.target, Target::Region(_)) {
+                return Err(format!("Budget '{}': percentage limits are only supported for regions", budget.target));
+            }
+        }
+
+        Ok(budgets)
+    }
+
+    /// Checks budget against `actual` size. `capacity` is the size of a memory region, and is
+    /// required for percentage limits
+    pub fn check(&self, actual: usize, capacity: Option<usize>) -> bool {
+        match self.limit {
+            Limit::Bytes(bytes)     => actual <= bytes,
+            Limit::Percent(percent) => match capacity {
+                Some(capacity) => actual as f64 <= capacity as f64 * percent as f64 / 100.0,
+                None           => false,
+            },
+        }
+    }
+}
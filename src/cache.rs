@@ -0,0 +1,343 @@
+//! # `binsize::cache`
+//!
+//! Implements on-disk cache of parsed executables, so re-running `binsize` with different
+//! options doesn't parse and demangle the whole file again
+//!
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use json::JsonValue;
+use crate::demangle::DemangledSymbolKind;
+use crate::exe::{
+    ArchiveMember,
+    ExecutableInfo,
+    Export,
+    Import,
+    Relocation,
+    Reservation,
+    Section,
+    SectionKind,
+    Segment,
+    SegmentFlags,
+    Symbol,
+    SymbolKind
+};
+
+/// Cache location inside target directory
+pub const CACHE_DIR: &str = "binsize/cache";
+
+/// Version of cache file contents. Bumped whenever cached data changes, so stale files are
+/// not picked up
+const FORMAT_VERSION: u32 = 14;
+
+/// Builds cache file path for executable at `path`. Key is derived from path, size &
+/// modification time of the file, selected architecture (for universal binaries), `binsize`
+/// version (as cached data depends on parser) and cache format version. Cache files are kept
+/// in `dir`
+fn cache_path(path: &Path, arch: Option<&str>, dir: &Path) -> Option<PathBuf> {
+    let meta = std::fs::metadata(path).ok()?;
+
+    let mut hasher = DefaultHasher::new();
+
+    std::fs::canonicalize(path).ok()?.hash(&mut hasher);
+    meta.len().hash(&mut hasher);
+    meta.modified().ok()?.hash(&mut hasher);
+    arch.hash(&mut hasher);
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    FORMAT_VERSION.hash(&mut hasher);
+
+    Some(dir.join(format!("{:016x}.json", hasher.finish())))
+}
+
+/// Parses executable at `path`, using cached result if available. Freshly parsed executables
+/// are saved into cache directory `dir`. Cache errors are not fatal - file is just parsed again.
+/// `arch` selects slice of universal binary (see `exe::parse`)
+pub fn parse(path: &Path, arch: Option<&str>, dir: &Path) -> Result<ExecutableInfo, Box<dyn std::error::Error>> {
+    let cache = cache_path(path, arch, dir);
+
+    if let Some(exe) = cache.as_ref().and_then(|cache| load(cache)) {
+        return Ok(exe);
+    }
+
+    let exe = crate::exe::parse(path, arch)?;
+
+    if let Some(cache) = cache {
+        // Failing to save cache shouldn't fail the analysis
+        let _ = save(&cache, &exe);
+    }
+
+    Ok(exe)
+}
+
+/// Loads executable info from cache file
+fn load(cache: &Path) -> Option<ExecutableInfo> {
+    let root = json::parse(std::fs::read_to_string(cache).ok()?.as_str()).ok()?;
+
+    let symbols = root["symbols"].members()
+        .map(|s| Some(Symbol {
+            name:       s["name"].as_str()?.to_string(),
+            crate_name: s["crate"].as_str()?.to_string(),
+            size:       s["size"].as_usize()?,
+            addr:       s["addr"].as_usize()?,
+            kind:       symbol_kind(s["kind"].as_str()?)?,
+            lang:       symbol_lang(s["lang"].as_str()?)?,
+            section:    s["section"].as_usize(),
+            global:     s["global"].as_bool()?,
+            aliases:    Vec::new(),
+        }))
+        .collect::<Option<Vec<_>>>()?;
+
+    let sections = root["sections"].members()
+        .map(|s| Some(Section {
+            index:  s["index"].as_usize()?,
+            name:   s["name"].as_str()?.to_string(),
+            addr:   s["addr"].as_usize()?,
+            size:   s["size"].as_usize()?,
+            offset: s["offset"].as_usize(),
+            kind:   section_kind(s["kind"].as_str()?)?,
+        }))
+        .collect::<Option<Vec<_>>>()?;
+
+    let segments = root["segments"].members()
+        .map(|s| Some(Segment {
+            kind:      s["kind"].as_str()?.to_string(),
+            load:      s["load"].as_bool()?,
+            addr:      s["addr"].as_usize()?,
+            phys_addr: s["phys_addr"].as_usize()?,
+            size:      s["size"].as_usize()?,
+            file_size: s["file_size"].as_usize()?,
+            flags: SegmentFlags {
+                read:    s["read"].as_bool()?,
+                write:   s["write"].as_bool()?,
+                execute: s["execute"].as_bool()?,
+            },
+        }))
+        .collect::<Option<Vec<_>>>()?;
+
+    let imports = root["imports"].members()
+        .map(|i| Some(Import {
+            name:    i["name"].as_str()?.to_string(),
+            library: i["library"].as_str()?.to_string(),
+        }))
+        .collect::<Option<Vec<_>>>()?;
+
+    let exports = root["exports"].members()
+        .map(|e| Some(Export {
+            name: e["name"].as_str()?.to_string(),
+            addr: e["addr"].as_usize()?,
+            size: e["size"].as_usize()?,
+        }))
+        .collect::<Option<Vec<_>>>()?;
+
+    let libraries = root["libraries"].members()
+        .map(|l| Some(l.as_str()?.to_string()))
+        .collect::<Option<Vec<_>>>()?;
+
+    let reservations = root["reservations"].members()
+        .map(|r| Some(Reservation {
+            name: r["name"].as_str()?.to_string(),
+            addr: r["addr"].as_usize()?,
+            size: r["size"].as_usize()?,
+        }))
+        .collect::<Option<Vec<_>>>()?;
+
+    let relocations = root["relocations"].members()
+        .map(|r| Some(Relocation {
+            addr:     r["addr"].as_usize()?,
+            symbolic: r["symbolic"].as_bool()?,
+        }))
+        .collect::<Option<Vec<_>>>()?;
+
+    let members = root["members"].members()
+        .map(|m| Some(ArchiveMember {
+            name:      m["name"].as_str()?.to_string(),
+            file_size: m["file_size"].as_usize()?,
+            code:      m["code"].as_usize()?,
+            data:      m["data"].as_usize()?,
+            symbols:   m["symbols"].as_usize()?,
+        }))
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(ExecutableInfo {
+        symbols,
+        sections,
+        segments,
+        imports,
+        exports,
+        libraries,
+        reservations,
+        relocations,
+        file_size:   root["file_size"].as_usize()?,
+        file_offset: root["file_offset"].as_usize()?,
+        slices:      root["slices"].members()
+            .map(|s| Some(s.as_str()?.to_string()))
+            .collect::<Option<Vec<_>>>()?,
+        arch:        root["arch"].as_str().map(|arch| arch.to_string()),
+        members,
+        capped_symbols: root["capped_symbols"].as_usize()?,
+    })
+}
+
+/// Saves executable info into cache file
+fn save(cache: &Path, exe: &ExecutableInfo) -> Result<(), Box<dyn std::error::Error>> {
+    let mut root = JsonValue::new_object();
+
+    let mut symbols = JsonValue::new_array();
+
+    for sym in exe.symbols.iter() {
+        let mut obj = JsonValue::new_object();
+
+        obj["name"]    = sym.name.as_str().into();
+        obj["crate"]   = sym.crate_name.as_str().into();
+        obj["size"]    = sym.size.into();
+        obj["addr"]    = sym.addr.into();
+        obj["kind"]    = sym.kind.to_string().trim().into();
+        obj["lang"]    = sym.lang.to_string().into();
+        obj["global"]  = sym.global.into();
+        obj["section"] = sym.section.into();
+
+        symbols.push(obj)?;
+    }
+
+    let mut sections = JsonValue::new_array();
+
+    for section in exe.sections.iter() {
+        let mut obj = JsonValue::new_object();
+
+        obj["index"]  = section.index.into();
+        obj["name"]   = section.name.as_str().into();
+        obj["addr"]   = section.addr.into();
+        obj["size"]   = section.size.into();
+        obj["offset"] = section.offset.into();
+        obj["kind"]   = section.kind.to_string().into();
+
+        sections.push(obj)?;
+    }
+
+    let mut segments = JsonValue::new_array();
+
+    for seg in exe.segments.iter() {
+        let mut obj = JsonValue::new_object();
+
+        obj["kind"]      = seg.kind.as_str().into();
+        obj["load"]      = seg.load.into();
+        obj["addr"]      = seg.addr.into();
+        obj["phys_addr"] = seg.phys_addr.into();
+        obj["size"]      = seg.size.into();
+        obj["file_size"] = seg.file_size.into();
+        obj["read"]      = seg.flags.read.into();
+        obj["write"]     = seg.flags.write.into();
+        obj["execute"]   = seg.flags.execute.into();
+
+        segments.push(obj)?;
+    }
+
+    let mut imports = JsonValue::new_array();
+
+    for import in exe.imports.iter() {
+        let mut obj = JsonValue::new_object();
+
+        obj["name"]    = import.name.as_str().into();
+        obj["library"] = import.library.as_str().into();
+
+        imports.push(obj)?;
+    }
+
+    let mut exports = JsonValue::new_array();
+
+    for export in exe.exports.iter() {
+        let mut obj = JsonValue::new_object();
+
+        obj["name"] = export.name.as_str().into();
+        obj["addr"] = export.addr.into();
+        obj["size"] = export.size.into();
+
+        exports.push(obj)?;
+    }
+
+    let mut reservations = JsonValue::new_array();
+
+    for reservation in exe.reservations.iter() {
+        let mut obj = JsonValue::new_object();
+
+        obj["name"] = reservation.name.as_str().into();
+        obj["addr"] = reservation.addr.into();
+        obj["size"] = reservation.size.into();
+
+        reservations.push(obj)?;
+    }
+
+    let mut relocations = JsonValue::new_array();
+
+    for reloc in exe.relocations.iter() {
+        let mut obj = JsonValue::new_object();
+
+        obj["addr"]     = reloc.addr.into();
+        obj["symbolic"] = reloc.symbolic.into();
+
+        relocations.push(obj)?;
+    }
+
+    let mut members = JsonValue::new_array();
+
+    for member in exe.members.iter() {
+        let mut obj = JsonValue::new_object();
+
+        obj["name"]      = member.name.as_str().into();
+        obj["file_size"] = member.file_size.into();
+        obj["code"]      = member.code.into();
+        obj["data"]      = member.data.into();
+        obj["symbols"]   = member.symbols.into();
+
+        members.push(obj)?;
+    }
+
+    root["symbols"]   = symbols;
+    root["sections"]  = sections;
+    root["segments"]  = segments;
+    root["imports"]   = imports;
+    root["exports"]   = exports;
+    root["libraries"] = exe.libraries.iter().map(|l| l.as_str()).collect::<Vec<_>>().into();
+    root["reservations"] = reservations;
+    root["relocations"] = relocations;
+    root["file_size"] = exe.file_size.into();
+    root["file_offset"] = exe.file_offset.into();
+    root["slices"] = exe.slices.iter().map(|s| s.as_str()).collect::<Vec<_>>().into();
+    root["arch"] = exe.arch.as_deref().into();
+    root["members"] = members;
+    root["capped_symbols"] = exe.capped_symbols.into();
+
+    std::fs::create_dir_all(cache.parent().unwrap())?;
+    std::fs::write(cache, root.dump())?;
+
+    Ok(())
+}
+
+/// Parses symbol kind from its `Display` representation
+fn symbol_kind(value: &str) -> Option<SymbolKind> {
+    [SymbolKind::Unknown, SymbolKind::Function, SymbolKind::Data]
+        .into_iter()
+        .find(|kind| kind.to_string().trim() == value)
+}
+
+/// Parses symbol language from its `Display` representation
+fn symbol_lang(value: &str) -> Option<DemangledSymbolKind> {
+    DemangledSymbolKind::ALL
+        .into_iter()
+        .find(|lang| lang.to_string() == value)
+}
+
+/// Parses section kind from its `Display` representation
+fn section_kind(value: &str) -> Option<SectionKind> {
+    [
+        SectionKind::Code,
+        SectionKind::ReadOnlyData,
+        SectionKind::Data,
+        SectionKind::Bss,
+        SectionKind::Tls,
+        SectionKind::Debug,
+        SectionKind::Other,
+    ]
+        .into_iter()
+        .find(|kind| kind.to_string() == value)
+}
@@ -5,13 +5,16 @@
 //!
 
 use std::fmt::Debug;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 
+/// Location of the list of artifacts, produced by the last build, inside target directory. Used
+/// by `--no-build`
+pub const ARTIFACTS_FILE: &str = "binsize/artifacts.json";
+
 /// Represents build options passed to `cargo build`
-/// TODO: Add an ability to pass any option
 #[derive(Clone)]
 pub struct BuildOptions {
     /// Build profile
@@ -19,12 +22,41 @@ pub struct BuildOptions {
 
     /// Message format for target artifacts parsing
     pub message_format: String,
+
+    /// Build all workspace members
+    pub workspace: bool,
+
+    /// Directory for build artifacts (`--target-dir`). Cargo default, if empty
+    pub target_dir: String,
+
+    /// Number of parallel build jobs (`-j`). Negative values are relative to number of CPUs, cargo
+    /// default, if `None`
+    pub jobs: Option<i64>,
+
+    /// Standard library crates to rebuild with `-Z build-std` (nightly only). `None` disables
+    /// it, empty list lets cargo choose the crates
+    pub build_std: Option<Vec<String>>,
+
+    /// Extra arguments, passed to `cargo build` as is
+    pub extra_args: Vec<String>,
+
+    /// Environment variables, set for `cargo build` (e.g. `CARGO_PROFILE_*` overrides)
+    pub env: Vec<(String, String)>,
 }
 
 impl BuildOptions {
     /// Creates new build options
     pub fn new(profile: String, message_format: String) -> Self {
-        Self { profile, message_format }
+        Self {
+            profile,
+            message_format,
+            workspace: false,
+            target_dir: String::new(),
+            jobs: None,
+            build_std: None,
+            extra_args: Vec::new(),
+            env: Vec::new()
+        }
     }
 
     /// Builds options into vector of command-line arguments to cargo
@@ -40,8 +72,40 @@ impl BuildOptions {
             args.push(format_args!("--message-format={}", self.message_format).to_string());
         }
 
+        if self.workspace {
+            args.push("--workspace".to_string());
+        }
+
+        if !self.target_dir.is_empty() {
+            args.push("--target-dir".to_string());
+            args.push(self.target_dir.clone());
+        }
+
+        if let Some(jobs) = self.jobs {
+            args.push(format!("--jobs={}", jobs));
+        }
+
+        match &self.build_std {
+            Some(crates) if !crates.is_empty() => args.push(format!("-Zbuild-std={}", crates.join(","))),
+            Some(_)                            => args.push("-Zbuild-std".to_string()),
+            None                               => {}
+        }
+
+        args.extend(self.extra_args.iter().cloned());
+
         args
     }
+
+    /// Returns value of cargo option `name` (e.g. `--target`) from extra arguments, given either
+    /// as `--name VALUE` or `--name=VALUE`
+    pub fn extra_arg(&self, name: &str) -> Option<String> {
+        let prefix = format!("{}=", name);
+
+        self.extra_args.iter()
+            .position(|a| a == name)
+            .and_then(|i| self.extra_args.get(i + 1).cloned())
+            .or_else(|| self.extra_args.iter().find_map(|a| a.strip_prefix(prefix.as_str()).map(|v| v.to_string())))
+    }
 }
 
 impl Default for BuildOptions {
@@ -60,6 +124,17 @@ pub enum BuildArtifactKind {
     DynamicLibrary
 }
 
+impl BuildArtifactKind {
+    /// Returns crate type, that converts back into this kind
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Binary         => "bin",
+            Self::Library        => "lib",
+            Self::DynamicLibrary => "dylib",
+        }
+    }
+}
+
 impl TryFrom<&str> for BuildArtifactKind {
     type Error = ();
 
@@ -90,7 +165,7 @@ impl BuildArtifact {
         // Parse symbols only for library artifacts
         if artifact.is_lib() {
             if let Err(e) = artifact.resolve_symbols() {
-                println!("Error: '{}' while parsing '{}' ({:?})", e, artifact.name, artifact.path);
+                eprintln!("Error: '{}' while parsing '{}' ({:?})", e, artifact.name, artifact.path);
             }
         }
 
@@ -135,19 +210,79 @@ impl Debug for BuildArtifact {
     }
 }
 
-/// Run `cargo-build` with given build options
-pub fn build(opt: BuildOptions) -> Result<(), String> {
+/// Compiler message (error, warning, etc.), reported by cargo in JSON output
+pub struct Diagnostic {
+    /// Message level (`error`, `warning`, `note`, etc.)
+    pub level: String,
+
+    /// Message, rendered by compiler in human-readable form (with code snippets). First line is
+    /// a header with level, error code & message itself
+    pub rendered: String,
+}
+
+/// Reasons of failed build
+pub struct BuildError {
+    /// Compiler messages in order of appearance
+    pub diagnostics: Vec<Diagnostic>,
+
+    /// Errors of cargo itself (like invalid manifest or arguments), without progress messages
+    pub stderr: String,
+}
+
+/// Progress statuses, that cargo prints to stderr while building
+const CARGO_STATUSES: &[&str] = &[
+    "Adding",
+    "Blocking",
+    "Building",
+    "Checking",
+    "Compiling",
+    "Documenting",
+    "Downloaded",
+    "Downloading",
+    "Finished",
+    "Fresh",
+    "Locking",
+    "Packaging",
+    "Running",
+    "Updating",
+];
+
+/// Run `cargo-build` with given build options. Output is requested in JSON, so compiler
+/// messages of failed build can be returned in `BuildError`
+pub fn build(mut opt: BuildOptions) -> Result<(), BuildError> {
+    opt.message_format = "json".to_string();
+
     let cargo_build = Command::new("cargo")
         .args(opt.args())
+        .envs(opt.env.iter().cloned())
         .output()
         .expect("cargo build failed");
 
-    // Return cargo error output through Result:Err
-    if !cargo_build.status.success() {
-        return Err(String::from_utf8_lossy(&cargo_build.stderr).to_string().clone());
+    if cargo_build.status.success() {
+        return Ok(());
     }
 
-    Ok(())
+    let diagnostics = String::from_utf8_lossy(&cargo_build.stdout).lines()
+        .filter_map(|line| json::parse(line).ok())
+        .filter(|msg| msg["reason"] == "compiler-message")
+        .filter_map(|msg| Some(Diagnostic {
+            level:    msg["message"]["level"].as_str()?.to_string(),
+            rendered: msg["message"]["rendered"].as_str()?.trim_end().to_string(),
+        }))
+        .collect();
+
+    // Progress messages (`Compiling`, `Finished`, etc.) are indented to line up statuses. Other
+    // indented lines (e.g. causes of an error) are kept
+    let stderr = String::from_utf8_lossy(&cargo_build.stderr).lines()
+        .filter(|line| {
+            let status = line.split_whitespace().next().is_some_and(|word| CARGO_STATUSES.contains(&word));
+
+            !(line.starts_with(' ') && status)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Err(BuildError { diagnostics, stderr })
 }
 
 /// Parse `cargo-build` json output, and produce a list or build artifacts
@@ -157,11 +292,12 @@ pub fn artifacts(mut opt: BuildOptions) -> Vec<BuildArtifact> {
     // Won't actually build the project, because of `--message-format=json` (or at least I think it won't)
     let cargo_build_info = Command::new("cargo")
         .args(opt.args())
+        .envs(opt.env.iter().cloned())
         .output()
         .expect("cargo build failed");
 
     if !cargo_build_info.status.success() {
-        panic!("cargo build failed");
+        panic!("cargo build failed:\n{}", String::from_utf8_lossy(&cargo_build_info.stderr).trim_end());
     }
 
     let mut artifacts = Vec::new();
@@ -170,6 +306,11 @@ pub fn artifacts(mut opt: BuildOptions) -> Vec<BuildArtifact> {
     for line in String::from_utf8_lossy(&cargo_build_info.stdout).lines() {
         let build = json::parse(line).expect("invalid json output from cargo");
 
+        // Build scripts are reported as binaries, but they are not a part of the product
+        if build["target"]["kind"].members().any(|k| k == "custom-build") {
+            continue;
+        }
+
         if let Some(target) = build["target"]["name"].as_str() {
             if !build["filenames"].is_null() {
                 let filenames = build["filenames"].members();
@@ -193,6 +334,334 @@ pub fn artifacts(mut opt: BuildOptions) -> Vec<BuildArtifact> {
     artifacts
 }
 
+/// Returns path of `ARTIFACTS_FILE` in target directory of `opt`
+pub fn artifacts_file(opt: &BuildOptions) -> PathBuf {
+    target_root(opt).join(ARTIFACTS_FILE)
+}
+
+/// Identifies build options in `ARTIFACTS_FILE`: all of them end up either in arguments or in
+/// environment of `cargo build`
+fn options_json(opt: &BuildOptions) -> json::JsonValue {
+    json::object! {
+        args: opt.args(),
+        env:  opt.env.iter().map(|(key, value)| format!("{}={}", key, value)).collect::<Vec<_>>(),
+    }
+}
+
+/// Saves list of `artifacts`, built with `opt`, into `ARTIFACTS_FILE`, so `--no-build` can
+/// reuse it
+pub fn save_artifacts(opt: &BuildOptions, artifacts: &[BuildArtifact]) -> Result<(), Box<dyn Error>> {
+    let mut list = json::JsonValue::new_array();
+
+    for artifact in artifacts {
+        list.push(json::object! {
+            kind: artifact.kind.as_str(),
+            name: artifact.name.as_str(),
+            path: artifact.path.to_string_lossy().to_string(),
+        })?;
+    }
+
+    let file = json::object! {
+        options:   options_json(opt),
+        artifacts: list,
+    };
+
+    let path = artifacts_file(opt);
+
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    std::fs::write(path, file.dump())?;
+
+    Ok(())
+}
+
+/// Returns artifacts of the last build with `opt` without running cargo. Uses the list, saved
+/// by the last build (see `save_artifacts`). If there is none (or it is stale), falls back to
+/// scanning target directory of the profile for binaries & dynamic libraries
+pub fn last_artifacts(opt: &BuildOptions) -> Result<Vec<BuildArtifact>, String> {
+    if let Some(artifacts) = load_artifacts(opt) {
+        return Ok(artifacts);
+    }
+
+    let dir = target_dir(opt);
+
+    let mut files = std::fs::read_dir(&dir)
+        .map_err(|e| format!("Can't read '{}': {}. Build the project first", dir.display(), e))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            let path = entry.path();
+
+            // Binaries have no extension on unix-like systems, dependency info files are `.d`
+            let kind = match path.extension().and_then(|e| e.to_str()) {
+                None | Some("exe")           => BuildArtifactKind::Binary,
+                Some("so" | "dylib" | "dll") => BuildArtifactKind::DynamicLibrary,
+                _                            => return None,
+            };
+
+            let name = path.file_stem()?.to_string_lossy().to_string();
+
+            (meta.is_file() && !name.starts_with('.')).then_some((meta.modified().ok()?, kind, name, path))
+        })
+        .collect::<Vec<_>>();
+
+    if files.is_empty() {
+        return Err(format!("No artifacts found in '{}'. Build the project first", dir.display()));
+    }
+
+    // `select_artifact` picks the last candidate, so it'll be the most recent one
+    files.sort_by_key(|(modified, ..)| *modified);
+
+    Ok(files.into_iter()
+        .map(|(_, kind, name, path)| {
+            // Dynamic libraries are prefixed with `lib` (except on windows)
+            let name = match kind {
+                BuildArtifactKind::Binary => name.as_str(),
+                _                         => name.strip_prefix("lib").unwrap_or(&name),
+            };
+
+            BuildArtifact::new(kind, name.replace("-", "_"), path)
+        })
+        .collect())
+}
+
+/// Loads artifacts list from `ARTIFACTS_FILE`. Returns `None`, if it was saved for different
+/// build options, or some of artifacts no longer exist
+fn load_artifacts(opt: &BuildOptions) -> Option<Vec<BuildArtifact>> {
+    let file = json::parse(&std::fs::read_to_string(artifacts_file(opt)).ok()?).ok()?;
+
+    if file["options"] != options_json(opt) {
+        return None;
+    }
+
+    file["artifacts"].members()
+        .map(|artifact| {
+            let kind = BuildArtifactKind::try_from(artifact["kind"].as_str()?).ok()?;
+            let name = artifact["name"].as_str()?.to_string();
+            let path = PathBuf::from(artifact["path"].as_str()?);
+
+            path.is_file().then(|| BuildArtifact::new(kind, name, path))
+        })
+        .collect()
+}
+
+/// Returns target directory of the build (`--target-dir`, `CARGO_TARGET_DIR` or `target`)
+pub fn target_root(opt: &BuildOptions) -> PathBuf {
+    PathBuf::from(if !opt.target_dir.is_empty() {
+        opt.target_dir.clone()
+    } else {
+        std::env::var("CARGO_TARGET_DIR").unwrap_or("target".to_string())
+    })
+}
+
+/// Returns directory, where cargo puts artifacts of `opt.profile` (respects `--target-dir`,
+/// `CARGO_TARGET_DIR` and `--target` in extra arguments)
+fn target_dir(opt: &BuildOptions) -> PathBuf {
+    let mut dir = target_root(opt);
+
+    if let Some(triple) = opt.extra_arg("--target") {
+        dir.push(triple);
+    }
+
+    // Built-in profiles `dev` & `test` go to `debug`, `bench` to `release`
+    dir.push(match opt.profile.as_str() {
+        "" | "dev" | "test" => "debug",
+        "bench"             => "release",
+        profile             => profile,
+    });
+
+    dir
+}
+
+/// Selects an artifact to analyze. If `name` is given, artifact with this name is selected
+/// (`-` and `_` are interchangeable). Otherwise, binaries & dynamic libraries are considered
+/// (falling back to all artifacts), and the last one is selected. Returns selected artifact and
+/// names of other candidates, so ambiguous choice can be reported
+pub fn select_artifact<'a>(
+    artifacts: &'a [BuildArtifact],
+    name: Option<&str>
+) -> Result<(&'a BuildArtifact, Vec<String>), String> {
+    if let Some(name) = name {
+        let name = name.replace("-", "_");
+
+        // Prefer binaries, as library with the same name is built for the same package
+        let mut matching = artifacts.iter()
+            .filter(|a| a.name == name)
+            .collect::<Vec<_>>();
+
+        matching.sort_by_key(|a| a.kind != BuildArtifactKind::Binary);
+
+        return matching.first()
+            .map(|a| (*a, Vec::new()))
+            .ok_or_else(|| format!(
+                "No artifact named '{}' (available: {})",
+                name,
+                artifacts.iter().map(|a| a.name.as_str()).collect::<Vec<_>>().join(", ")
+            ));
+    }
+
+    let mut candidates = artifacts.iter()
+        .filter(|a| a.kind == BuildArtifactKind::Binary || a.kind == BuildArtifactKind::DynamicLibrary)
+        .collect::<Vec<_>>();
+
+    if candidates.is_empty() {
+        candidates = artifacts.iter().collect();
+    }
+
+    let selected = *candidates.last().ok_or("cargo build produced no artifacts")?;
+
+    let others = candidates.iter()
+        .filter(|a| !std::ptr::eq(**a, selected))
+        .map(|a| a.name.clone())
+        .collect();
+
+    Ok((selected, others))
+}
+
+/// Finds project root, walking up from `dir` like cargo does: nearest directory with
+/// `Cargo.toml` is the package root, but if one of its ancestors has a `Cargo.toml` with
+/// `[workspace]` section, that ancestor is the root. Returns `None` outside of cargo project
+pub fn find_root(dir: &Path) -> Option<PathBuf> {
+    let package = dir.ancestors().find(|d| d.join("Cargo.toml").is_file())?;
+
+    let workspace = package.ancestors().find(|d| {
+        std::fs::read_to_string(d.join("Cargo.toml"))
+            .ok()
+            .and_then(|s| toml::from_str::<toml::Table>(s.as_str()).ok())
+            .is_some_and(|manifest| manifest.contains_key("workspace"))
+    });
+
+    Some(workspace.unwrap_or(package).to_path_buf())
+}
+
+/// Cargo profile setting, that prevents symbol analysis
+pub struct ProfileIssue {
+    /// Setting as written in profile, e.g. `strip = true`
+    pub setting: String,
+
+    /// Where the setting comes from (`Cargo.toml`, cargo config file or environment variable)
+    pub source: String,
+
+    /// Environment variable, that overrides the setting
+    pub env: String,
+
+    /// Value of `env`, that fixes the issue
+    pub fix: String,
+}
+
+/// Returns environment variable, that overrides `key` of `profile`
+pub fn profile_env(profile: &str, key: &str) -> String {
+    format!("CARGO_PROFILE_{}_{}", profile.to_uppercase().replace("-", "_"), key.to_uppercase())
+}
+
+/// Returns `[profile]` tables, that cargo resolves profiles from, along with their file names:
+/// cargo config files (`.cargo/config.toml` in current directory and its parents, then in
+/// `$CARGO_HOME`), followed by `Cargo.toml` of the project. More specific ones go first
+fn profile_sources() -> Vec<(String, toml::Table)> {
+    let dir = std::env::current_dir().unwrap_or_default();
+
+    let cargo_home = std::env::var("CARGO_HOME").map(PathBuf::from).ok()
+        .or_else(|| std::env::var("HOME").ok().map(|home| Path::new(&home).join(".cargo")));
+
+    let configs = dir.ancestors()
+        .map(|d| d.join(".cargo"))
+        .chain(cargo_home)
+        .flat_map(|d| [d.join("config.toml"), d.join("config")]);
+
+    let manifest = find_root(&dir).unwrap_or_default().join("Cargo.toml");
+
+    configs.chain([manifest])
+        .filter_map(|path| {
+            let file = toml::from_str::<toml::Table>(std::fs::read_to_string(&path).ok()?.as_str()).ok()?;
+
+            Some((path.display().to_string(), file.get("profile")?.as_table()?.clone()))
+        })
+        .collect()
+}
+
+/// Checks resolved `profile` for settings, that strip symbols (`strip = true`/`"symbols"`) or
+/// disable debug info (`debug = 0`/`false`/`"none"`). Settings are resolved like cargo does:
+/// `CARGO_PROFILE_*` environment variables, then cargo config files, then `Cargo.toml`, taking
+/// inherited profiles into account
+pub fn profile_issues(profile: &str) -> Vec<ProfileIssue> {
+    let sources = profile_sources();
+
+    let setting = |name: &str, key: &str| sources.iter()
+        .find_map(|(source, profiles)| Some((profiles.get(name)?.get(key)?.clone(), source.clone())));
+
+    // Looks setting up in the profile, and then in the profiles it inherits from
+    let lookup = |key: &str| {
+        let mut name = profile.to_string();
+
+        // Bounded, in case of inheritance cycle
+        for _ in 0..8 {
+            let env = profile_env(name.as_str(), key);
+
+            if let Ok(value) = std::env::var(env.as_str()) {
+                let value = match value.as_str() {
+                    "true"  => toml::Value::Boolean(true),
+                    "false" => toml::Value::Boolean(false),
+                    _       => value.parse::<i64>().map(toml::Value::Integer).unwrap_or(toml::Value::String(value)),
+                };
+
+                return Some((value, env));
+            }
+
+            if let Some(found) = setting(name.as_str(), key) {
+                return Some(found);
+            }
+
+            // Built-in profiles inherit implicitly
+            name = match (setting(name.as_str(), "inherits"), name.as_str()) {
+                (Some((toml::Value::String(parent), _)), _) => parent,
+                (_, "bench")                                => "release".to_string(),
+                (_, "test")                                 => "dev".to_string(),
+                _                                           => return None,
+            };
+        }
+
+        None
+    };
+
+    let mut issues = Vec::new();
+
+    if let Some((strip, source)) = lookup("strip") {
+        let strips_symbols = match &strip {
+            toml::Value::Boolean(val) => *val,
+            toml::Value::String(val)  => val == "symbols",
+            _                         => false,
+        };
+
+        if strips_symbols {
+            issues.push(ProfileIssue {
+                setting: format!("strip = {}", strip),
+                source,
+                env:     profile_env(profile, "strip"),
+                fix:     "none".to_string(),
+            });
+        }
+    }
+
+    if let Some((debug, source)) = lookup("debug") {
+        let no_debug = match &debug {
+            toml::Value::Boolean(val) => !*val,
+            toml::Value::Integer(val) => *val == 0,
+            toml::Value::String(val)  => val == "none",
+            _                         => false,
+        };
+
+        if no_debug {
+            issues.push(ProfileIssue {
+                setting: format!("debug = {}", debug),
+                source,
+                env:     profile_env(profile, "debug"),
+                fix:     "line-tables-only".to_string(),
+            });
+        }
+    }
+
+    issues
+}
+
 /// Try to find crate for symbol in a Vec of artifacts
 pub fn try_find_crate(artifacts: &Vec<BuildArtifact>, symbol: &str) -> Option<String> {
     for artifact in artifacts.iter().filter(|a| a.is_lib()) {
@@ -203,3 +672,294 @@ pub fn try_find_crate(artifacts: &Vec<BuildArtifact>, symbol: &str) -> Option<St
 
     None
 }
+
+/// Crates, that come with the toolchain (`std` and its private dependencies)
+const STD_CRATES: &[&str] = &[
+    "std",
+    "core",
+    "alloc",
+    "compiler_builtins",
+    "panic_unwind",
+    "panic_abort",
+    "unwind",
+    "std_detect",
+    "proc_macro",
+    "test",
+];
+
+/// Returns `true` if crate comes with the toolchain
+pub fn is_std_crate(name: &str) -> bool {
+    STD_CRATES.contains(&name)
+}
+
+/// High-level category of a crate
+#[derive(PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum CrateCategory {
+    /// Crates from current workspace
+    Local,
+
+    /// Third-party crates
+    Dependency,
+
+    /// `std`, `core`, `alloc`, `compiler_builtins` & co
+    Std,
+
+    /// Crate couldn't be classified (unknown crate name, or no cargo metadata available)
+    Unknown,
+}
+
+impl std::fmt::Display for CrateCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            CrateCategory::Local      => "local",
+            CrateCategory::Dependency => "dependencies",
+            CrateCategory::Std        => "std",
+            CrateCategory::Unknown    => "unknown",
+        })
+    }
+}
+
+/// Crate names from `cargo metadata`, used to classify crates into `CrateCategory`
+#[derive(Default)]
+pub struct Metadata {
+    /// Target names of workspace members
+    local: HashSet<String>,
+
+    /// Target names of all other packages
+    dependencies: HashSet<String>,
+}
+
+impl Metadata {
+    /// Classify crate by name. `std` crates are recognized even without metadata
+    pub fn category(&self, name: &str) -> CrateCategory {
+        if self.local.contains(name) {
+            CrateCategory::Local
+        } else if self.dependencies.contains(name) {
+            CrateCategory::Dependency
+        } else if is_std_crate(name) {
+            CrateCategory::Std
+        } else {
+            CrateCategory::Unknown
+        }
+    }
+}
+
+/// Run `cargo metadata` for the package, that is built with `opt` (respects `--manifest-path` in
+/// extra arguments), and parse its output
+fn run_metadata(opt: &BuildOptions) -> Result<json::JsonValue, String> {
+    let mut args = vec!["metadata".to_string(), "--format-version".to_string(), "1".to_string()];
+
+    if let Some(manifest) = opt.extra_arg("--manifest-path") {
+        args.extend(["--manifest-path".to_string(), manifest]);
+    }
+
+    let cargo_metadata = Command::new("cargo")
+        .args(args)
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if !cargo_metadata.status.success() {
+        return Err(String::from_utf8_lossy(&cargo_metadata.stderr).to_string());
+    }
+
+    json::parse(String::from_utf8_lossy(&cargo_metadata.stdout).as_ref())
+        .map_err(|e| e.to_string())
+}
+
+/// Run `cargo metadata` and collect crate names of workspace members & dependencies
+pub fn metadata(opt: &BuildOptions) -> Result<Metadata, String> {
+    let root = run_metadata(opt)?;
+
+    let members = root["workspace_members"].members()
+        .filter_map(|m| m.as_str())
+        .collect::<HashSet<_>>();
+
+    let mut metadata = Metadata::default();
+
+    for package in root["packages"].members() {
+        let is_local = package["id"].as_str().is_some_and(|id| members.contains(id));
+
+        // Symbols contain target (lib/bin) names, which use `_` instead of `-`
+        for target in package["targets"].members() {
+            if let Some(name) = target["name"].as_str() {
+                let name = name.replace("-", "_");
+
+                if is_local {
+                    metadata.local.insert(name);
+                } else {
+                    metadata.dependencies.insert(name);
+                }
+            }
+        }
+    }
+
+    Ok(metadata)
+}
+
+/// Run `cargo metadata` and return features of the root package (feature name -> features &
+/// dependencies it enables). Empty, if there is no root package (virtual workspace)
+pub fn features(opt: &BuildOptions) -> Result<HashMap<String, Vec<String>>, String> {
+    let root = run_metadata(opt)?;
+
+    let Some(id) = root["resolve"]["root"].as_str() else {
+        return Ok(HashMap::new());
+    };
+
+    let Some(package) = root["packages"].members().find(|p| p["id"] == id) else {
+        return Ok(HashMap::new());
+    };
+
+    Ok(package["features"].entries()
+        .map(|(name, enables)| (
+            name.to_string(),
+            enables.members().filter_map(|f| f.as_str()).map(|f| f.to_string()).collect()
+        ))
+        .collect())
+}
+
+/// Returns `features` along with all features, they enable (directly or transitively) according
+/// to `table` (see `features()`). Dependencies (`dep:name`) & features of dependencies
+/// (`name/feature`) aren't followed
+pub fn enabled_features(table: &HashMap<String, Vec<String>>, features: &[String]) -> HashSet<String> {
+    let mut enabled = HashSet::new();
+    let mut stack = features.to_vec();
+
+    while let Some(feature) = stack.pop() {
+        if enabled.insert(feature.clone()) && let Some(enables) = table.get(&feature) {
+            stack.extend(enables.iter().filter(|f| !f.contains([':', '/'])).cloned());
+        }
+    }
+
+    enabled
+}
+
+/// Package in dependency graph
+pub struct Package {
+    /// Package name
+    pub name: String,
+
+    /// Package version
+    pub version: String,
+
+    /// Crate names of package targets (lib/bins), as they appear in symbols
+    pub crates: Vec<String>,
+
+    /// Indices of direct dependencies in `DependencyGraph::packages`
+    pub deps: Vec<usize>,
+}
+
+/// Resolved dependency graph of the workspace from `cargo metadata`. Only normal dependencies
+/// are included, as dev & build dependencies don't end up in the binary
+pub struct DependencyGraph {
+    /// All packages of the graph
+    pub packages: Vec<Package>,
+
+    /// Indices of workspace members in `packages`
+    pub members: Vec<usize>,
+}
+
+impl DependencyGraph {
+    /// Returns indices of `package` and all packages it depends on, directly or transitively
+    pub fn subtree(&self, package: usize) -> HashSet<usize> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![package];
+
+        while let Some(package) = stack.pop() {
+            if visited.insert(package) {
+                stack.extend(self.packages[package].deps.iter().copied());
+            }
+        }
+
+        visited
+    }
+
+    /// Flattens graph into lines of a tree, like `cargo tree` does. Each line is a tree-drawing
+    /// prefix, package index and whether package was already expanded above (its dependencies
+    /// aren't repeated then). Dependencies are ordered by `weight`, heaviest first
+    pub fn lines(&self, roots: &[usize], weight: &[usize]) -> Vec<(String, usize, bool)> {
+        let mut lines = Vec::new();
+        let mut expanded = HashSet::new();
+
+        for root in roots {
+            self.push_lines(*root, "", String::new(), weight, &mut expanded, &mut lines);
+        }
+
+        lines
+    }
+
+    /// Pushes line of `package` & lines of its dependencies (see `lines`). `indent` is a prefix
+    /// of dependency lines, `branch` - of package line itself
+    fn push_lines(
+        &self,
+        package:  usize,
+        indent:   &str,
+        branch:   String,
+        weight:   &[usize],
+        expanded: &mut HashSet<usize>,
+        lines:    &mut Vec<(String, usize, bool)>
+    ) {
+        let repeated = !expanded.insert(package);
+
+        lines.push((branch, package, repeated));
+
+        if repeated {
+            return;
+        }
+
+        let mut deps = self.packages[package].deps.clone();
+        deps.sort_by(|a, b| weight[*b].cmp(&weight[*a]).then_with(|| self.packages[*a].name.cmp(&self.packages[*b].name)));
+        deps.dedup();
+
+        for (i, dep) in deps.iter().enumerate() {
+            let last = i == deps.len() - 1;
+
+            self.push_lines(
+                *dep,
+                &format!("{}{}", indent, if last { "    " } else { "│   " }),
+                format!("{}{}", indent, if last { "└── " } else { "├── " }),
+                weight,
+                expanded,
+                lines
+            );
+        }
+    }
+}
+
+/// Run `cargo metadata` and build dependency graph of the workspace
+pub fn dependency_graph(opt: &BuildOptions) -> Result<DependencyGraph, String> {
+    let root = run_metadata(opt)?;
+
+    let ids = root["packages"].members()
+        .enumerate()
+        .filter_map(|(i, package)| Some((package["id"].as_str()?, i)))
+        .collect::<HashMap<_, _>>();
+
+    let mut packages = root["packages"].members()
+        .map(|package| Package {
+            name:    package["name"].as_str().unwrap_or("?").to_string(),
+            version: package["version"].as_str().unwrap_or("").to_string(),
+            crates:  package["targets"].members()
+                .filter_map(|target| Some(target["name"].as_str()?.replace("-", "_")))
+                .collect(),
+            deps:    Vec::new(),
+        })
+        .collect::<Vec<_>>();
+
+    for node in root["resolve"]["nodes"].members() {
+        let Some(&package) = node["id"].as_str().and_then(|id| ids.get(id)) else {
+            continue;
+        };
+
+        // Kind is `null` for normal dependencies, and `dev` or `build` for others
+        packages[package].deps = node["deps"].members()
+            .filter(|dep| dep["dep_kinds"].members().any(|kind| kind["kind"].is_null()))
+            .filter_map(|dep| dep["pkg"].as_str().and_then(|id| ids.get(id)).copied())
+            .collect();
+    }
+
+    let members = root["workspace_members"].members()
+        .filter_map(|m| m.as_str().and_then(|id| ids.get(id)).copied())
+        .collect();
+
+    Ok(DependencyGraph { packages, members })
+}
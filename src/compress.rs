@@ -0,0 +1,73 @@
+//! # `binsize::compress`
+//!
+//! Implements compressibility estimation of loaded sections. Firmware updates & wasm modules
+//! are usually shipped compressed, so compressed size is closer to what is actually downloaded
+//!
+
+use std::io::Write;
+use std::path::Path;
+use crate::exe::ExecutableInfo;
+
+/// Compressed sizes of a section (or of the whole loaded image)
+pub struct Compression {
+    /// Section name
+    pub name: String,
+
+    /// Uncompressed size
+    pub size: usize,
+
+    /// Size after gzip (default level)
+    pub gzip: usize,
+
+    /// Size after zstd (level 1)
+    pub zstd: usize,
+}
+
+impl Compression {
+    /// Compresses `data` with each of the algorithms
+    fn new(name: &str, data: &[u8]) -> Result<Self, std::io::Error> {
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(data)?;
+
+        let zstd = ruzstd::encoding::compress_to_vec(data, ruzstd::encoding::CompressionLevel::Fastest);
+
+        Ok(Self {
+            name: name.to_string(),
+            size: data.len(),
+            gzip: gzip.finish()?.len(),
+            zstd: zstd.len(),
+        })
+    }
+}
+
+/// Compresses contents of each loaded section of `exe` (parsed from file at `path`). Last
+/// entry is for contents of all sections concatenated, which compresses better than sections
+/// separately, and is closer to an actual update payload
+pub fn compress_sections(path: &Path, exe: &ExecutableInfo) -> Result<Vec<Compression>, Box<dyn std::error::Error>> {
+    let file = std::fs::File::open(path)?;
+    let data = unsafe { memmap2::Mmap::map(&file)? };
+
+    let mut result = Vec::new();
+    let mut image = Vec::new();
+
+    for section in exe.sections.iter().filter(|s| s.is_loaded() && s.size != 0) {
+        let Some(offset) = section.offset else {
+            continue;
+        };
+
+        // Section offsets are relative to the parsed image (slice of universal binary)
+        let offset = exe.file_offset + offset;
+
+        let contents = data.get(offset..offset + section.size)
+            .ok_or_else(|| format!("Section '{}' is out of file bounds", section.name))?;
+
+        result.push(Compression::new(&section.name, contents)?);
+        image.extend_from_slice(contents);
+    }
+
+    if !result.is_empty() {
+        result.push(Compression::new("<image>", &image)?);
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,140 @@
+//! # `binsize::delta`
+//!
+//! Implements tracking of changes between invocations - totals of each symbol & crate are saved
+//! after every run, so the next one can show how much they grew or shrank
+//!
+
+use std::collections::HashMap;
+use std::path::Path;
+use json::JsonValue;
+use crate::demangle;
+use crate::exe::{ExecutableInfo, Symbol};
+
+/// Location of file with totals of the last run inside target directory
+pub const LAST_FILE: &str = "binsize/last.json";
+
+/// Totals of a single analyzed file
+#[derive(Default)]
+pub struct Totals {
+    /// Symbol keys (see `symbol_keys`) and their sizes
+    pub symbols: HashMap<String, usize>,
+
+    /// Crate names and their sizes
+    pub crates: HashMap<String, usize>,
+}
+
+impl Totals {
+    /// Collects totals of `exe`
+    pub fn new(exe: &ExecutableInfo) -> Self {
+        Self {
+            symbols: symbol_keys(exe).into_iter().map(|(sym, key)| (key, sym.size)).collect(),
+            crates:  exe.crate_sizes().into_iter().collect(),
+        }
+    }
+
+    /// Returns changes of symbol sizes since these totals, by symbol address. New symbols
+    /// are counted as grown from zero
+    pub fn symbol_deltas(&self, exe: &ExecutableInfo) -> HashMap<usize, i64> {
+        symbol_keys(exe).into_iter()
+            .map(|(sym, key)| (sym.addr, sym.size as i64 - self.symbols.get(&key).copied().unwrap_or(0) as i64))
+            .collect()
+    }
+
+    /// Returns change of crate size since these totals. New crates are counted as grown from zero
+    pub fn crate_delta(&self, name: &str, size: usize) -> i64 {
+        size as i64 - self.crates.get(name).copied().unwrap_or(0) as i64
+    }
+
+    /// Converts totals into JSON object
+    fn to_json(&self) -> JsonValue {
+        let named = |values: &HashMap<String, usize>| {
+            let mut obj = JsonValue::new_object();
+
+            for (name, size) in values {
+                obj[name.as_str()] = (*size).into();
+            }
+
+            obj
+        };
+
+        let mut obj = JsonValue::new_object();
+
+        obj["symbols"] = named(&self.symbols);
+        obj["crates"]  = named(&self.crates);
+
+        obj
+    }
+
+    /// Parses totals from JSON object
+    fn from_json(obj: &JsonValue) -> Self {
+        let named = |value: &JsonValue| {
+            value.entries()
+                .filter_map(|(name, size)| Some((name.to_string(), size.as_usize()?)))
+                .collect::<HashMap<_, _>>()
+        };
+
+        Self { symbols: named(&obj["symbols"]), crates: named(&obj["crates"]) }
+    }
+}
+
+/// Assigns keys, that identify the same symbol across runs, to symbols of `exe`
+fn symbol_keys(exe: &ExecutableInfo) -> Vec<(&Symbol, String)> {
+    keyed(
+        exe.symbols.iter()
+            .filter(|s| s.size != 0)
+            .map(|s| (s.name.as_str(), s.addr, s))
+            .collect()
+    )
+}
+
+/// Assigns keys, that identify the same symbol across builds, to `(name, addr, value)` entries.
+/// Key is a normalized name (see `demangle::normalize`), so changed hashes don't make a symbol
+/// look new. Symbols sharing a name (e.g. local statics from different modules, or instances
+/// of a generic, that differ only by hashes) are told apart by their order in address space
+pub fn keyed<T>(mut symbols: Vec<(&str, usize, T)>) -> Vec<(T, String)> {
+    symbols.sort_by_key(|(_, addr, _)| *addr);
+
+    let mut seen = HashMap::<String, usize>::new();
+
+    symbols.into_iter()
+        .map(|(name, _, value)| {
+            let name = demangle::normalize(name);
+
+            let count = seen.entry(name.clone()).or_insert(0);
+            *count += 1;
+
+            match *count {
+                1 => (value, name),
+                n => (value, format!("{}#{}", name, n)),
+            }
+        })
+        .collect()
+}
+
+/// Loads totals of all files from the last run. Missing or invalid file yields no totals
+pub fn load(path: &Path) -> HashMap<String, Totals> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| json::parse(&s).ok())
+        .map(|obj| obj.entries().map(|(file, totals)| (file.to_string(), Totals::from_json(totals))).collect())
+        .unwrap_or_default()
+}
+
+/// Saves `totals` of `file` into last run file, keeping totals of other files intact
+pub fn save(path: &Path, file: &str, totals: &Totals) -> Result<(), Box<dyn std::error::Error>> {
+    let mut obj = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| json::parse(&s).ok())
+        .filter(|obj| obj.is_object())
+        .unwrap_or_else(JsonValue::new_object);
+
+    obj[file] = totals.to_json();
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(path, obj.dump())?;
+
+    Ok(())
+}
@@ -2,16 +2,101 @@
 //!
 //!
 
+use std::ops::Range;
 use std::sync::OnceLock;
 
 /// Kind of demangled symbol by language
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Copy, Hash)]
 pub enum DemangledSymbolKind {
     Rust,
     Cpp,
+    /// Not mangled, but a valid C identifier
+    C,
     Other
 }
 
+impl DemangledSymbolKind {
+    /// All languages in the order they are reported
+    pub const ALL: [DemangledSymbolKind; 4] = [
+        DemangledSymbolKind::Rust,
+        DemangledSymbolKind::Cpp,
+        DemangledSymbolKind::C,
+        DemangledSymbolKind::Other,
+    ];
+}
+
+impl TryFrom<&str> for DemangledSymbolKind {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "rust"         => Ok(DemangledSymbolKind::Rust),
+            "cpp" | "c++"  => Ok(DemangledSymbolKind::Cpp),
+            "c"            => Ok(DemangledSymbolKind::C),
+            "other"        => Ok(DemangledSymbolKind::Other),
+            _              => Err(format!("Unknown language '{}' (possible values: rust, cpp, c, other)", value)),
+        }
+    }
+}
+
+impl std::fmt::Display for DemangledSymbolKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DemangledSymbolKind::Rust  => write!(f, "Rust"),
+            DemangledSymbolKind::Cpp   => write!(f, "C++"),
+            DemangledSymbolKind::C     => write!(f, "C"),
+            DemangledSymbolKind::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// Kind of compiler-generated code, that symbol is, judging by its demangled name
+#[derive(PartialEq, Eq, Clone, Copy, Hash)]
+pub enum GeneratedKind {
+    /// Drop glue (`core::ptr::drop_in_place<T>`)
+    DropGlue,
+
+    /// Vtables & their shims (`{{vtable.shim}}`). Most vtables are unnamed, and aren't detected
+    Vtable,
+
+    /// Implementations of formatting traits (`<T as core::fmt::Debug>::fmt`)
+    Fmt,
+
+    /// Iterator adapters (`Map`, `Filter`, `Enumerate`, etc.)
+    IterAdapter,
+
+    /// Closures (`{{closure}}`)
+    Closure,
+
+    /// Not recognized as generated - mostly code, written by hand
+    None,
+}
+
+impl GeneratedKind {
+    /// All kinds in the order they are reported
+    pub const ALL: [GeneratedKind; 6] = [
+        GeneratedKind::DropGlue,
+        GeneratedKind::Vtable,
+        GeneratedKind::Fmt,
+        GeneratedKind::IterAdapter,
+        GeneratedKind::Closure,
+        GeneratedKind::None,
+    ];
+}
+
+impl std::fmt::Display for GeneratedKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GeneratedKind::DropGlue    => write!(f, "drop_in_place"),
+            GeneratedKind::Vtable      => write!(f, "vtables"),
+            GeneratedKind::Fmt         => write!(f, "fmt impls"),
+            GeneratedKind::IterAdapter => write!(f, "iterator adapters"),
+            GeneratedKind::Closure     => write!(f, "closures"),
+            GeneratedKind::None        => write!(f, "other code"),
+        }
+    }
+}
+
 /// Demangled symbol
 pub struct DemangledSymbol {
     pub kind: DemangledSymbolKind,
@@ -50,11 +135,119 @@ pub fn demangle(s: &str) -> DemangledSymbol {
 
     // Return symbol name as-is
     DemangledSymbol {
-        kind: DemangledSymbolKind::Other,
+        kind: if is_c_identifier(s) { DemangledSymbolKind::C } else { DemangledSymbolKind::Other },
         name: s.to_string(),
     }
 }
 
+/// Returns `true` if symbol name is a valid C identifier. Symbol version suffix (`@GLIBC_2.34`),
+/// is ignored. Compiler-generated symbols (like `.L` labels or `$x` mapping symbols) aren't
+/// identifiers
+fn is_c_identifier(s: &str) -> bool {
+    let name = s.split('@').next().unwrap_or(s);
+
+    name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Returns byte ranges of generic arguments in demangled symbol name - `<...>` after a name, like
+/// `<u8>` in `Vec<u8>::push`. Qualified paths (`<T as Trait>::method`) aren't generic arguments,
+/// but may contain them. Nested arguments are part of the outer range
+pub fn generic_args(s: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut depth = 0usize;
+
+    // Depth & position of `<`, that opened current generic arguments
+    let mut open: Option<(usize, usize)> = None;
+    let mut prev = ' ';
+
+    for (i, c) in s.char_indices() {
+        match c {
+            // C++ `operator<` & `operator<<` aren't generics
+            '<' if s[..i].ends_with("operator") || s[..i].ends_with("operator<") => {}
+            '<' => {
+                if open.is_none() && (prev.is_alphanumeric() || prev == '_') {
+                    open = Some((depth, i));
+                }
+
+                depth += 1;
+            }
+            // `->` of function pointer types doesn't close anything
+            '>' if prev != '-' => {
+                depth = depth.saturating_sub(1);
+
+                if let Some((level, start)) = open && level == depth {
+                    ranges.push(start..i + 1);
+                    open = None;
+                }
+            }
+            _ => {}
+        }
+
+        prev = c;
+    }
+
+    ranges
+}
+
+/// Replaces generic arguments of demangled symbol name with `<…>`, so e.g.
+/// `RawTable<(String, i64)>::reserve_rehash` becomes `RawTable<…>::reserve_rehash`
+pub fn fold_generics(s: &str) -> String {
+    let mut name = s.to_string();
+
+    for range in generic_args(s).into_iter().rev() {
+        name.replace_range(range, "<…>");
+    }
+
+    name
+}
+
+/// Classifies demangled symbol name by kind of generated code. Generic arguments are ignored,
+/// so e.g. `Map<I, {{closure}}>::fold` is an iterator adapter, and `drop_in_place<{{closure}}>`
+/// is drop glue, not a closure
+pub fn generated_kind(s: &str) -> GeneratedKind {
+    let mut name = s.to_string();
+
+    for range in generic_args(s).into_iter().rev() {
+        name.replace_range(range, "");
+    }
+
+    if name.contains("::ptr::drop_in_place") {
+        GeneratedKind::DropGlue
+    } else if name.contains("{vtable") {
+        GeneratedKind::Vtable
+    } else if name.contains("::fmt::") && name.ends_with(">::fmt") {
+        GeneratedKind::Fmt
+    } else if name.contains("::iter::adapters::") {
+        GeneratedKind::IterAdapter
+    } else if name.contains("{{closure}}") || name.contains("{closure#") {
+        GeneratedKind::Closure
+    } else {
+        GeneratedKind::None
+    }
+}
+
+/// Compiled regex patterns for parts of symbol name, that change between builds, with their
+/// replacements
+static UNSTABLE_PATTERNS: OnceLock<Vec<(regex::Regex, &str)>> = OnceLock::new();
+
+/// Normalizes demangled symbol name, so the same symbol has the same name across builds. Crate
+/// disambiguators (`core[c1f1a4ba060b9bfa]`) & legacy hashes (`::h0123456789abcdef`) are removed,
+/// which rustc changes when crate metadata (version, features, flags) changes. Generic arguments
+/// are normalized too - lifetimes are erased and spacing around `<`, `>` & `,` is unified
+pub fn normalize(s: &str) -> String {
+    let patterns = UNSTABLE_PATTERNS.get_or_init(|| [
+        (r"\[[0-9a-f]{1,16}\]", ""),
+        (r"::h[0-9a-f]{16}$", ""),
+        (r"'[A-Za-z_][A-Za-z0-9_]*\b", "'_"),
+        (r"\s*,\s*", ", "),
+        (r"\s*>", ">"),
+        (r"<\s*", "<"),
+    ].into_iter().map(|(re, to)| (regex::Regex::new(re).unwrap(), to)).collect());
+
+    patterns.iter().fold(s.to_string(), |name, (re, to)| re.replace_all(&name, *to).into_owned())
+}
+
 /// Compiled regex pattern for roughly guessing crate name from symbol
 static CRATE_PATTERN: OnceLock<regex::Regex> = OnceLock::new();
 
@@ -5,13 +5,15 @@
 //!
 
 use object::{File, Object, ObjectSection, ObjectSegment, ObjectSymbol};
+use object::read::macho::{FatArch, MachOFatFile};
 use std::fmt::{Display, Formatter};
+use std::collections::HashMap;
 use crate::cargo::BuildArtifact;
-use crate::util::SortOrder;
+use crate::util::{SortKey, SortOrder};
 use crate::demangle::{DemangledSymbolKind, demangle, crate_name_from_demangled};
 
 /// Symbol kind
-#[derive(PartialEq)]
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
 pub enum SymbolKind {
     Unknown,
     Function,
@@ -45,17 +47,169 @@ pub struct Symbol {
     /// Symbol kind
     pub kind: SymbolKind,
 
+    /// Source language, detected by demangler
+    pub lang: DemangledSymbolKind,
+
+    /// Index of section (in `ExecutableInfo::sections`), that contains the symbol
+    pub section: Option<usize>,
+
+    /// Symbol is visible outside of its object (global or weak binding)
+    pub global: bool,
+
+    /// Names of other symbols with the same address & size, which were collapsed into this one
+    /// by `ExecutableInfo::collapse_aliases()`
+    pub aliases: Vec<String>,
+
     // TODO: Maybe add definition location (requires dwarf parsing most likely)
 }
 
 impl Symbol {
+    /// Fields that symbols can be sorted by
+    pub const SORT_FIELDS: &'static [&'static str] = &["size", "name", "crate", "kind", "addr"];
+
     pub fn filter(&self, re: &regex::Regex) -> bool {
         matches!(re.captures(&self.name), Some(_)) || matches!(re.captures(&self.crate_name), Some(_))
     }
+
+    /// Compares symbols by `field` (one of `SORT_FIELDS`) in ascending order
+    pub fn compare(&self, other: &Symbol, field: &str) -> std::cmp::Ordering {
+        match field {
+            "size"  => self.size.cmp(&other.size),
+            "name"  => self.name.cmp(&other.name),
+            "crate" => self.crate_name.cmp(&other.crate_name),
+            "kind"  => self.kind.cmp(&other.kind),
+            "addr"  => self.addr.cmp(&other.addr),
+            _       => panic!("Can't sort symbols by '{}'", field),
+        }
+    }
+}
+
+/// Section kind, generalized from `object::SectionKind`
+#[derive(PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SectionKind {
+    /// Executable code
+    Code,
+
+    /// Read-only data (constants, strings)
+    ReadOnlyData,
+
+    /// Initialized writable data
+    Data,
+
+    /// Zero-initialized writable data (doesn't take space in file)
+    Bss,
+
+    /// Thread-local data (both initialized and zero-initialized)
+    Tls,
+
+    /// Debug information
+    Debug,
+
+    /// Anything else (symbol tables, notes, metadata)
+    Other,
+}
+
+impl SectionKind {
+    /// Returns `true` if section of this kind occupies RAM at runtime
+    pub fn is_ram(&self) -> bool {
+        matches!(self, SectionKind::Data | SectionKind::Bss | SectionKind::Tls)
+    }
+}
+
+impl From<object::SectionKind> for SectionKind {
+    fn from(kind: object::SectionKind) -> Self {
+        use object::SectionKind as K;
+
+        match kind {
+            K::Text                                                      => SectionKind::Code,
+            K::ReadOnlyData | K::ReadOnlyDataWithRel | K::ReadOnlyString => SectionKind::ReadOnlyData,
+            K::Data                                                      => SectionKind::Data,
+            K::UninitializedData | K::Common                             => SectionKind::Bss,
+            K::Tls | K::UninitializedTls | K::TlsVariables               => SectionKind::Tls,
+            K::Debug | K::DebugString                                    => SectionKind::Debug,
+            _                                                            => SectionKind::Other,
+        }
+    }
+}
+
+impl Display for SectionKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SectionKind::Code         => write!(f, "CODE"),
+            SectionKind::ReadOnlyData => write!(f, "RODATA"),
+            SectionKind::Data         => write!(f, "DATA"),
+            SectionKind::Bss          => write!(f, "BSS"),
+            SectionKind::Tls          => write!(f, "TLS"),
+            SectionKind::Debug        => write!(f, "DEBUG"),
+            SectionKind::Other        => write!(f, "OTHER"),
+        }
+    }
+}
+
+/// Coarse category of a section, sections are grouped by it in sections summary
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum SectionCategory {
+    /// Executable code
+    Code,
+
+    /// Read-only data
+    ReadOnlyData,
+
+    /// Initialized writable data (including initialized TLS)
+    Data,
+
+    /// Zero-initialized data (including zero-initialized TLS)
+    Bss,
+
+    /// Debug information
+    Debug,
+
+    /// Symbol tables, notes, dynamic linking info & the rest
+    Other,
+}
+
+impl SectionCategory {
+    /// All categories in the order they are reported
+    pub const ALL: [SectionCategory; 6] = [
+        SectionCategory::Code,
+        SectionCategory::ReadOnlyData,
+        SectionCategory::Data,
+        SectionCategory::Bss,
+        SectionCategory::Debug,
+        SectionCategory::Other,
+    ];
+}
+
+impl Display for SectionCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SectionCategory::Code         => write!(f, "code"),
+            SectionCategory::ReadOnlyData => write!(f, "ro-data"),
+            SectionCategory::Data         => write!(f, "rw-data"),
+            SectionCategory::Bss          => write!(f, "bss"),
+            SectionCategory::Debug        => write!(f, "debug"),
+            SectionCategory::Other        => write!(f, "metadata/other"),
+        }
+    }
+}
+
+/// Prefixes of debug information section names (ELF, compressed ELF & Mach-O)
+const DEBUG_SECTION_PREFIXES: &[&str] = &[".debug", ".zdebug", "__debug", "__apple_"];
+
+/// Returns generalized kind of section `s`. `object` only reports debug sections for some
+/// formats (not ELF), so they are also detected by name
+fn section_kind<'a>(s: &impl ObjectSection<'a>) -> SectionKind {
+    match s.name() {
+        Ok(name) if DEBUG_SECTION_PREFIXES.iter().any(|p| name.starts_with(p)) => SectionKind::Debug,
+        _ => s.kind().into(),
+    }
 }
 
 /// Represents a section in an executable (`.text`/`.data`/etc.)
 pub struct Section {
+    /// Section index in the file
+    pub index: usize,
+
     /// Section name
     pub name: String,
 
@@ -64,15 +218,411 @@ pub struct Section {
 
     /// Section size
     pub size: usize,
+
+    /// Offset of section contents in the file. `None` for sections, that occupy no space in
+    /// the file (like `.bss`)
+    pub offset: Option<usize>,
+
+    /// Section kind
+    pub kind: SectionKind,
+}
+
+impl Section {
+    /// Returns category of the section. TLS sections are split into data & bss by name, same
+    /// as in `ExecutableInfo::tls_usage()`
+    pub fn category(&self) -> SectionCategory {
+        match self.kind {
+            SectionKind::Code                             => SectionCategory::Code,
+            SectionKind::ReadOnlyData                     => SectionCategory::ReadOnlyData,
+            SectionKind::Data                             => SectionCategory::Data,
+            SectionKind::Bss                              => SectionCategory::Bss,
+            SectionKind::Tls if self.name.contains("bss") => SectionCategory::Bss,
+            SectionKind::Tls                              => SectionCategory::Data,
+            SectionKind::Debug                            => SectionCategory::Debug,
+            SectionKind::Other                            => SectionCategory::Other,
+        }
+    }
+
+    /// Compares sections by `field` (one of `Symbol::SORT_FIELDS`) in ascending order. Fields,
+    /// that sections don't have (`crate`), don't affect the order
+    pub fn compare(&self, other: &Section, field: &str) -> std::cmp::Ordering {
+        match field {
+            "size" => self.size.cmp(&other.size),
+            "name" => self.name.cmp(&other.name),
+            "kind" => self.kind.to_string().cmp(&other.kind.to_string()),
+            "addr" => self.addr.cmp(&other.addr),
+            _      => std::cmp::Ordering::Equal,
+        }
+    }
+
+    /// Returns `true` if section holds code or data, that symbols can be attributed to (as
+    /// opposed to debug info, symbol tables, notes, etc.)
+    pub fn has_symbols(&self) -> bool {
+        !matches!(self.kind, SectionKind::Debug | SectionKind::Other)
+    }
+
+    /// Returns `true` if section is loaded into memory. Allocation flags are not kept, so
+    /// sections without address are assumed to be non-loaded, unless they hold code or data
+    /// (which can be placed at 0 on bare-metal targets)
+    pub fn is_loaded(&self) -> bool {
+        self.addr != 0 || self.has_symbols()
+    }
+}
+
+/// Segment access permissions
+#[derive(Clone, Copy, Default)]
+pub struct SegmentFlags {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+}
+
+impl SegmentFlags {
+    /// Returns `true` if any access is permitted (segments without permissions, like Mach-O's
+    /// `__PAGEZERO`, only reserve address space)
+    pub fn any(&self) -> bool {
+        self.read || self.write || self.execute
+    }
+}
+
+impl From<object::SegmentFlags> for SegmentFlags {
+    fn from(flags: object::SegmentFlags) -> Self {
+        match flags {
+            object::SegmentFlags::Elf { p_flags } => Self {
+                read:    p_flags & object::elf::PF_R != 0,
+                write:   p_flags & object::elf::PF_W != 0,
+                execute: p_flags & object::elf::PF_X != 0,
+            },
+            object::SegmentFlags::MachO { initprot, .. } => Self {
+                read:    initprot & object::macho::VM_PROT_READ != 0,
+                write:   initprot & object::macho::VM_PROT_WRITE != 0,
+                execute: initprot & object::macho::VM_PROT_EXECUTE != 0,
+            },
+            object::SegmentFlags::Coff { characteristics } => Self {
+                read:    characteristics & object::pe::IMAGE_SCN_MEM_READ != 0,
+                write:   characteristics & object::pe::IMAGE_SCN_MEM_WRITE != 0,
+                execute: characteristics & object::pe::IMAGE_SCN_MEM_EXECUTE != 0,
+            },
+            _ => Self::default(),
+        }
+    }
+}
+
+impl Display for SegmentFlags {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f, "{}{}{}",
+            if self.read { "R" } else { "-" },
+            if self.write { "W" } else { "-" },
+            if self.execute { "X" } else { "-" },
+        )
+    }
 }
 
 /// Represents a Program Header (Segment)
 pub struct Segment {
-    /// Address of segment
+    /// Segment type (`LOAD`/`TLS`/`GNU_STACK`/etc. for ELF, segment name for other formats)
+    pub kind: String,
+
+    /// `true` if segment is loaded into memory (only these take part in memory usage calculation)
+    pub load: bool,
+
+    /// Address of segment (virtual address, where segment resides at run time - VMA)
     pub addr: usize,
 
+    /// Physical address of segment (where segment is loaded from - LMA). Differs from `addr` for
+    /// initialized data on embedded targets, which is stored in flash and copied to RAM on startup
+    pub phys_addr: usize,
+
     /// Size of loaded data
     pub size: usize,
+
+    /// Size of data, stored in file (can be less than `size`, e.g. for `.bss`)
+    pub file_size: usize,
+
+    /// Access permissions
+    pub flags: SegmentFlags,
+}
+
+impl Segment {
+    /// Compares segments by `field` (one of `Symbol::SORT_FIELDS`) in ascending order. `kind`
+    /// is segment type, and fields, that segments don't have (`name`, `crate`), don't affect
+    /// the order
+    pub fn compare(&self, other: &Segment, field: &str) -> std::cmp::Ordering {
+        match field {
+            "size" => self.size.cmp(&other.size),
+            "kind" => self.kind.cmp(&other.kind),
+            "addr" => self.addr.cmp(&other.addr),
+            _      => std::cmp::Ordering::Equal,
+        }
+    }
+}
+
+/// Symbol, imported from a shared library
+pub struct Import {
+    /// Symbol name (demangled)
+    pub name: String,
+
+    /// Library, that provides the symbol (empty, if file doesn't record it)
+    pub library: String,
+}
+
+/// Symbol, exported for dynamic linking
+pub struct Export {
+    /// Symbol name (demangled)
+    pub name: String,
+
+    /// Symbol address
+    pub addr: usize,
+
+    /// Symbol size (0, if unknown)
+    pub size: usize,
+}
+
+/// Shared library dependency and what it costs
+pub struct LibraryUsage {
+    /// Library name (`?` for imports, that file doesn't attribute to a library)
+    pub name: String,
+
+    /// Number of symbols imported from the library. `None`, if it's unknown - ELF attributes
+    /// imports to libraries only through symbol versions, so imports of a library without them
+    /// end up under `?`
+    pub imports: Option<usize>,
+
+    /// Estimated size of import stubs (PLT entries, etc.) for the library. `None`, if number of
+    /// imports is unknown
+    pub stub_size: Option<usize>,
+}
+
+/// Dynamic relocation - a place in file, that is patched by dynamic loader at load time
+pub struct Relocation {
+    /// Address of patched place
+    pub addr: usize,
+
+    /// `true` if relocation refers to a symbol (e.g. an import), `false` if it only adds load
+    /// address (`R_*_RELATIVE`) - the overhead of position independent code
+    pub symbolic: bool,
+}
+
+/// Relocations of a single crate (or relocations outside of symbols, if `crate_name` is empty)
+pub struct CrateRelocations {
+    /// Crate name of symbols, that contain patched places
+    pub crate_name: String,
+
+    /// Number of relocations
+    pub count: usize,
+
+    /// Number of relocations, that refer to a symbol
+    pub symbolic: usize,
+}
+
+/// Prefixes of sections with relocation entries (`.rela.dyn`, `.rel.plt`, `.relr.dyn`), and
+/// of sections with data, that needs relocations (`.data.rel.ro`)
+const RELOCATION_SECTION_PREFIXES: &[&str] = &[".rela.", ".rel.", ".relr.", ".data.rel.ro"];
+
+/// Sections with unwinding tables
+const UNWIND_SECTIONS: &[&str] = &[
+    ".eh_frame", ".eh_frame_hdr", ".gcc_except_table", "__eh_frame", "__unwind_info", ".ARM.exidx", ".ARM.extab"
+];
+
+/// Sections, that contain import stubs
+const STUB_SECTIONS: &[&str] = &[".plt", ".plt.sec", ".plt.got", "__stubs", "__stub_helper"];
+
+/// Sections, that hold interrupt vector table on bare-metal targets (`cortex-m-rt`, CMSIS
+/// startup files, TI & others)
+pub const VECTOR_TABLE_SECTIONS: &[&str] = &[".vector_table", ".isr_vector", ".vectors", ".intvecs"];
+
+/// Symbols, that mark interrupt vector table, if it isn't placed into a dedicated section
+const VECTOR_TABLE_SYMBOLS: &[&str] = &[
+    "__RESET_VECTOR", "__EXCEPTIONS", "__INTERRUPTS", "__isr_vector", "__Vectors", "g_pfnVectors",
+    "__vector_table", "_vector_table",
+];
+
+/// Size of vector table entry (ARM vector table holds 32-bit addresses)
+const VECTOR_TABLE_ENTRY_SIZE: usize = 4;
+
+/// Interrupt vector table of a bare-metal executable
+pub struct VectorTable {
+    /// Address of the table
+    pub addr: usize,
+
+    /// Size of the table
+    pub size: usize,
+}
+
+impl VectorTable {
+    /// Number of entries (including initial stack pointer, which comes first on ARM)
+    pub fn entries(&self) -> usize {
+        self.size / VECTOR_TABLE_ENTRY_SIZE
+    }
+
+    /// Returns `true` if symbol belongs to the table
+    pub fn contains(&self, sym: &Symbol) -> bool {
+        self.addr <= sym.addr && sym.addr < self.addr + self.size
+    }
+}
+
+/// Names of linker-provided symbols, that describe a reservation. First found symbol of each
+/// kind is used
+struct ReservationSymbols {
+    /// Reservation name
+    name: &'static str,
+
+    /// Symbols, that hold lower bound of reserved area
+    lower: &'static [&'static str],
+
+    /// Symbols, that hold upper bound of reserved area
+    upper: &'static [&'static str],
+
+    /// Symbols, that hold size of reserved area
+    size: &'static [&'static str],
+}
+
+/// Linker-provided symbols of stack & heap reservations (`cortex-m-rt`, CMSIS, STM32CubeMX & co)
+const RESERVATION_SYMBOLS: &[ReservationSymbols] = &[
+    ReservationSymbols {
+        name:  "stack",
+        lower: &["__StackLimit", "_stack_end", "__stack_start__", "_sstack"],
+        upper: &["__StackTop", "_stack_start", "_estack", "__stack_end__"],
+        size:  &["__stack_size__", "__stack_size", "_stack_size", "__STACK_SIZE", "_Min_Stack_Size"],
+    },
+    ReservationSymbols {
+        name:  "heap",
+        lower: &["__HeapBase", "_heap_start", "__heap_start__", "__heap_start", "_sheap"],
+        upper: &["__HeapLimit", "_heap_end", "__heap_end__", "__heap_end", "_eheap"],
+        size:  &["_heap_size", "__heap_size__", "__heap_size", "__HEAP_SIZE", "_Min_Heap_Size"],
+    },
+];
+
+/// RAM, reserved by linker script without any section or symbol (stack, heap)
+pub struct Reservation {
+    /// What is reserved (`stack` or `heap`)
+    pub name: String,
+
+    /// Start address of reserved area
+    pub addr: usize,
+
+    /// Size of reserved area
+    pub size: usize,
+}
+
+/// Thread-local storage usage. Every thread gets its own copy of TLS block, so it takes RAM
+/// once per thread
+pub struct TlsUsage {
+    /// Size of initialized thread-local data (`.tdata`), which is stored in file as a template
+    pub data: usize,
+
+    /// Size of zero-initialized thread-local data (`.tbss`)
+    pub bss: usize,
+}
+
+impl TlsUsage {
+    /// Size of TLS block of a single thread
+    pub fn total(&self) -> usize {
+        self.data + self.bss
+    }
+}
+
+/// Sections, that `strip` keeps, even though they are not loaded
+const KEPT_BY_STRIP: &[&str] = &[".shstrtab", ".comment"];
+
+/// Estimated file sizes after stripping
+pub struct StripEstimate {
+    /// File size after `strip --strip-debug` (or `objcopy --strip-debug`) - without debug info
+    pub strip_debug: usize,
+
+    /// File size after `strip` - without debug info, symbol tables & other non-loaded sections
+    pub strip_all: usize,
+}
+
+/// Summary statistics over a set of symbols
+pub struct Stats {
+    /// Number of symbols
+    pub symbols: usize,
+
+    /// Number of functions
+    pub functions: usize,
+
+    /// Number of data symbols
+    pub data: usize,
+
+    /// Total size of symbols
+    pub total: usize,
+
+    /// Mean symbol size
+    pub mean: f64,
+
+    /// Median symbol size
+    pub median: usize,
+
+    /// Largest symbol size
+    pub max: usize,
+
+    /// Number of distinct crates
+    pub crates: usize,
+
+    /// Size of parsed file in bytes
+    pub file_size: usize,
+}
+
+impl Stats {
+    /// Calculates statistics over `symbols` of a file with size `file_size`
+    pub fn new(symbols: &[&Symbol], file_size: usize) -> Self {
+        let mut sizes = symbols.iter().map(|s| s.size).collect::<Vec<_>>();
+        sizes.sort();
+
+        let total = sizes.iter().sum::<usize>();
+
+        let crates = symbols.iter()
+            .map(|s| s.crate_name.as_str())
+            .collect::<std::collections::HashSet<_>>()
+            .len();
+
+        Self {
+            symbols:   symbols.len(),
+            functions: symbols.iter().filter(|s| s.kind == SymbolKind::Function).count(),
+            data:      symbols.iter().filter(|s| s.kind == SymbolKind::Data).count(),
+            total,
+            mean:      if sizes.is_empty() { 0.0 } else { total as f64 / sizes.len() as f64 },
+            median:    sizes.get(sizes.len() / 2).copied().unwrap_or(0),
+            max:       sizes.last().copied().unwrap_or(0),
+            crates,
+            file_size,
+        }
+    }
+
+    /// Returns statistics as `(name, value)` pairs, in the order they are printed
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("symbols",   self.symbols.to_string()),
+            ("functions", self.functions.to_string()),
+            ("data",      self.data.to_string()),
+            ("total",     self.total.to_string()),
+            ("mean",      format!("{:.02}", self.mean)),
+            ("median",    self.median.to_string()),
+            ("max",       self.max.to_string()),
+            ("crates",    self.crates.to_string()),
+            ("file_size", self.file_size.to_string()),
+        ]
+    }
+}
+
+/// Object file inside of an archive (`.rlib`/`.a`) - a codegen unit or a bundled C object
+pub struct ArchiveMember {
+    /// Member name
+    pub name: String,
+
+    /// Size of member in the archive
+    pub file_size: usize,
+
+    /// Size of code sections
+    pub code: usize,
+
+    /// Size of data sections (including read-only data & bss)
+    pub data: usize,
+
+    /// Number of symbols, defined in the member
+    pub symbols: usize,
 }
 
 /// Represents executable information
@@ -80,21 +630,345 @@ pub struct ExecutableInfo {
     pub symbols: Vec<Symbol>,
     pub sections: Vec<Section>,
     pub segments: Vec<Segment>,
+
+    /// Symbols, imported from shared libraries
+    pub imports: Vec<Import>,
+
+    /// Symbols, exported for dynamic linking
+    pub exports: Vec<Export>,
+
+    /// Shared libraries, that executable depends on at run time
+    pub libraries: Vec<String>,
+
+    /// Stack & heap, reserved by linker script outside of any section
+    pub reservations: Vec<Reservation>,
+
+    /// Dynamic relocations (ELF only)
+    pub relocations: Vec<Relocation>,
+
+    /// Size of parsed file in bytes (size of the slice, for universal binaries)
+    pub file_size: usize,
+
+    /// Offset of parsed image in the file. Non-zero for a slice of universal (fat) Mach-O binary
+    pub file_offset: usize,
+
+    /// Architectures of universal (fat) Mach-O binary slices. Empty for other files
+    pub slices: Vec<String>,
+
+    /// Architecture of the parsed slice of universal binary
+    pub arch: Option<String>,
+
+    /// Object files of an archive. Empty for other files
+    pub members: Vec<ArchiveMember>,
+
+    /// Number of symbols, whose sizes reached past the end of their section (or into the next
+    /// symbol) and were capped
+    pub capped_symbols: usize,
 }
 
 impl ExecutableInfo {
     /// Sorts symbols by size, given a `SortOrder`
     pub fn sort_symbols(&mut self, order: SortOrder) {
-        self.symbols.sort_by(|s1, s2|
-            if match order {
-                SortOrder::Ascending => s1.size < s2.size,
-                SortOrder::Descending => s1.size > s2.size
-            } {
-                core::cmp::Ordering::Less
+        // Sort is stable, so symbols of the same size keep their relative order
+        self.symbols.sort_by(|s1, s2| match order {
+            SortOrder::Ascending  => s1.size.cmp(&s2.size),
+            SortOrder::Descending => s1.size.cmp(&s2.size).reverse(),
+        });
+    }
+
+    /// Sorts symbols by a list of keys (e.g. `size:desc,name:asc`). Sort is stable, and symbols,
+    /// that are equal by all keys, keep their relative order
+    pub fn sort_symbols_by(&mut self, keys: &[SortKey]) {
+        self.symbols.sort_by(|s1, s2| SortKey::compare(keys, s1, s2, |field, a, b| a.compare(b, field)));
+    }
+
+    /// Moves symbols out, remapping their section indices to `sections` of another file by
+    /// section name. Used to take symbols from a separate debug info file, whose section list
+    /// can differ from the executable
+    pub fn symbols_for(self, sections: &[Section]) -> Vec<Symbol> {
+        let own = self.sections;
+
+        self.symbols.into_iter()
+            .map(|mut sym| {
+                sym.section = sym.section
+                    .and_then(|i| own.get(i))
+                    .and_then(|sec| sections.iter().position(|s| s.name == sec.name));
+
+                sym
+            })
+            .collect()
+    }
+
+    /// Returns section, that contains the symbol
+    pub fn section_of(&self, sym: &Symbol) -> Option<&Section> {
+        sym.section.map(|i| &self.sections[i])
+    }
+
+    /// Returns total size of unwinding tables (`.eh_frame` & co)
+    pub fn unwind_size(&self) -> usize {
+        self.sections.iter()
+            .filter(|s| UNWIND_SECTIONS.contains(&s.name.as_str()))
+            .fold(0, |r, s| r + s.size)
+    }
+
+    /// Returns sections, that hold relocation entries, or data, that needs relocations
+    pub fn relocation_sections(&self) -> Vec<&Section> {
+        self.sections.iter()
+            .filter(|s| RELOCATION_SECTION_PREFIXES.iter().any(|p| s.name.starts_with(p)))
+            .collect()
+    }
+
+    /// Counts dynamic relocations per crate of the symbol, that contains patched place, sorted
+    /// by count (descending). Relocations outside of any symbol (GOT, `.init_array`, etc.) are
+    /// counted under empty crate name
+    pub fn relocations_by_crate(&self) -> Vec<CrateRelocations> {
+        let mut symbols = self.symbols.iter().filter(|s| s.size != 0).collect::<Vec<_>>();
+        symbols.sort_by_key(|s| s.addr);
+
+        let mut crates: HashMap<&str, CrateRelocations> = HashMap::new();
+
+        for reloc in self.relocations.iter() {
+            // Last symbol, that starts at or before patched place
+            let crate_name = match symbols.partition_point(|s| s.addr <= reloc.addr) {
+                0 => "",
+                i if reloc.addr < symbols[i - 1].addr + symbols[i - 1].size => symbols[i - 1].crate_name.as_str(),
+                _ => "",
+            };
+
+            let entry = crates.entry(crate_name).or_insert_with(|| CrateRelocations {
+                crate_name: crate_name.to_string(),
+                count:      0,
+                symbolic:   0,
+            });
+
+            entry.count += 1;
+            entry.symbolic += reloc.symbolic as usize;
+        }
+
+        let mut crates = crates.into_values().collect::<Vec<_>>();
+        crates.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.crate_name.cmp(&b.crate_name)));
+
+        crates
+    }
+
+    /// Sums up symbol sizes per crate
+    pub fn crate_sizes(&self) -> Vec<(String, usize)> {
+        let mut crates: HashMap<&String, usize> = HashMap::new();
+
+        for sym in self.symbols.iter() {
+            *crates.entry(&sym.crate_name).or_insert(0) += sym.size;
+        }
+
+        crates.into_iter()
+            .map(|(name, size)| (name.clone(), size))
+            .collect()
+    }
+
+    /// Splits size of each crate by destination - flash (code & read-only data) and RAM (writable
+    /// data & bss), using categories of symbol sections. Returns crate name -> (flash, ram).
+    /// Initial values of writable data are stored in flash too, but are only counted towards RAM
+    pub fn crate_memory_sizes(&self) -> HashMap<String, (usize, usize)> {
+        let mut crates: HashMap<String, (usize, usize)> = HashMap::new();
+
+        for sym in self.symbols.iter() {
+            let Some(section) = self.section_of(sym) else {
+                continue;
+            };
+
+            let sizes = crates.entry(sym.crate_name.clone()).or_default();
+
+            match section.category() {
+                SectionCategory::Code | SectionCategory::ReadOnlyData => sizes.0 += sym.size,
+                SectionCategory::Data | SectionCategory::Bss          => sizes.1 += sym.size,
+                _                                                     => {}
+            }
+        }
+
+        crates
+    }
+
+    /// Collapses symbols, that share the same address & size (aliases, `$local` duplicates,
+    /// weak/strong pairs), into one, so their size isn't counted several times. Symbol with known
+    /// crate and without `$local` suffix is kept, names of the rest go into its `aliases`
+    pub fn collapse_aliases(&mut self) {
+        let mut groups: HashMap<(usize, usize, usize), Vec<usize>> = HashMap::new();
+
+        for (i, sym) in self.symbols.iter().enumerate() {
+            // Undefined symbols share address 0 without really being aliases
+            if sym.size != 0 && let Some(section) = sym.section {
+                groups.entry((sym.addr, sym.size, section)).or_default().push(i);
+            }
+        }
+
+        let mut removed = vec![false; self.symbols.len()];
+
+        for group in groups.into_values().filter(|g| g.len() > 1) {
+            let keep = *group.iter()
+                .min_by_key(|i| {
+                    let sym = &self.symbols[**i];
+                    (sym.crate_name == "?", sym.name.ends_with("$local"), **i)
+                })
+                .unwrap();
+
+            let mut aliases = group.iter()
+                .filter(|i| **i != keep)
+                .map(|i| self.symbols[*i].name.clone())
+                .collect::<Vec<_>>();
+
+            for i in group.iter().filter(|i| **i != keep) {
+                removed[*i] = true;
+            }
+
+            self.symbols[keep].global |= group.iter().any(|i| self.symbols[*i].global);
+
+            self.symbols[keep].aliases.append(&mut aliases);
+        }
+
+        let mut removed = removed.into_iter();
+        self.symbols.retain(|_| !removed.next().unwrap());
+    }
+
+    /// Calculates how many bytes of section `index` (in `sections`) are covered by symbols.
+    /// Returns covered size and address ranges (`start..end`), that aren't covered by any symbol
+    pub fn section_coverage(&self, index: usize) -> (usize, Vec<std::ops::Range<usize>>) {
+        let section = &self.sections[index];
+        let end = section.addr + section.size;
+
+        let mut ranges = self.symbols.iter()
+            .filter(|s| s.section == Some(index) && s.size != 0)
+            .map(|s| s.addr.clamp(section.addr, end)..(s.addr + s.size).clamp(section.addr, end))
+            .collect::<Vec<_>>();
+
+        ranges.sort_by_key(|r| r.start);
+
+        let mut covered = 0;
+        let mut gaps = Vec::new();
+        let mut cursor = section.addr;
+
+        // Symbols may overlap (e.g. aliases), so only the part after `cursor` is counted
+        for range in ranges {
+            if range.start > cursor {
+                gaps.push(cursor..range.start);
+            }
+
+            if range.end > cursor {
+                covered += range.end - range.start.max(cursor);
+                cursor = range.end;
+            }
+        }
+
+        if end > cursor {
+            gaps.push(cursor..end);
+        }
+
+        (covered, gaps)
+    }
+
+    /// Returns shared library dependencies with number of imports from each. Total size of stub
+    /// sections is split between libraries proportionally to number of imports, which is only
+    /// an estimate, as data imports don't need stubs. If some imports aren't attributed to any
+    /// library (ELF imports without symbol versions), libraries without attributed imports may
+    /// still provide them, so their numbers are unknown
+    pub fn library_usage(&self) -> Vec<LibraryUsage> {
+        let mut counts = self.libraries.iter()
+            .map(|name| (name.as_str(), 0))
+            .collect::<Vec<_>>();
+
+        for import in self.imports.iter() {
+            let name = if import.library.is_empty() { "?" } else { import.library.as_str() };
+
+            match counts.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, count)) => *count += 1,
+                None             => counts.push((name, 1)),
+            }
+        }
+
+        let unattributed = counts.iter().any(|(name, _)| *name == "?");
+
+        let stubs = self.sections.iter()
+            .filter(|s| STUB_SECTIONS.contains(&s.name.as_str()))
+            .map(|s| s.size)
+            .sum::<usize>();
+
+        counts.into_iter()
+            .map(|(name, count)| {
+                let imports = (count != 0 || !unattributed).then_some(count);
+
+                LibraryUsage {
+                    name:      name.to_string(),
+                    imports,
+                    stub_size: imports.map(|count| (stubs * count).checked_div(self.imports.len()).unwrap_or(0)),
+                }
+            })
+            .collect()
+    }
+
+    /// Estimates file size after stripping, by excluding sizes of sections, that `strip`
+    /// removes. Doesn't account for section headers & alignment, so it's approximate
+    pub fn strip_estimate(&self) -> StripEstimate {
+        let removed = |f: &dyn Fn(&Section) -> bool| self.sections.iter()
+            .filter(|s| !s.is_loaded() && f(s))
+            .fold(0, |r, s| r + s.size);
+
+        let debug = removed(&|s| s.kind == SectionKind::Debug);
+        let all = removed(&|s| !KEPT_BY_STRIP.contains(&s.name.as_str()));
+
+        StripEstimate {
+            strip_debug: self.file_size.saturating_sub(debug),
+            strip_all:   self.file_size.saturating_sub(all),
+        }
+    }
+
+    /// Sums up section sizes per category, skipping sections, for which `excluded` returns `true`.
+    /// Categories without sections are omitted
+    pub fn section_category_sizes(&self, excluded: impl Fn(&Section) -> bool) -> Vec<(SectionCategory, usize)> {
+        SectionCategory::ALL
+            .into_iter()
+            .filter_map(|category| {
+                let sections = self.sections.iter().filter(|s| s.category() == category && !excluded(s));
+
+                sections.clone().next().map(|_| (category, sections.fold(0, |r, s| r + s.size)))
+            })
+            .collect()
+    }
+
+    /// Calculates thread-local storage usage from TLS sections. Zero-initialized sections are
+    /// told apart by name (`.tbss`, `__thread_bss`), as they have the same section kind
+    pub fn tls_usage(&self) -> TlsUsage {
+        let mut usage = TlsUsage { data: 0, bss: 0 };
+
+        for section in self.sections.iter().filter(|s| s.kind == SectionKind::Tls) {
+            if section.name.contains("bss") {
+                usage.bss += section.size;
             } else {
-                core::cmp::Ordering::Greater
+                usage.data += section.size;
             }
-        );
+        }
+
+        usage
+    }
+
+    /// Detects interrupt vector table by its section, or by well-known symbols, if table isn't
+    /// placed into a dedicated section
+    pub fn vector_table(&self) -> Option<VectorTable> {
+        if let Some(section) = self.sections.iter().find(|s| VECTOR_TABLE_SECTIONS.contains(&s.name.as_str()) && s.size != 0) {
+            return Some(VectorTable { addr: section.addr, size: section.size });
+        }
+
+        let symbols = self.symbols.iter()
+            .filter(|s| VECTOR_TABLE_SYMBOLS.contains(&s.name.as_str()) && s.size != 0)
+            .collect::<Vec<_>>();
+
+        let start = symbols.iter().map(|s| s.addr).min()?;
+        let end = symbols.iter().map(|s| s.addr + s.size).max()?;
+
+        Some(VectorTable { addr: start, size: end - start })
+    }
+
+    /// Returns `true` if executable has no symbols, which means that symbol table was stripped
+    /// (and no separate debug file with symbols was found)
+    pub fn is_stripped(&self) -> bool {
+        self.symbols.is_empty()
     }
 }
 
@@ -104,7 +978,73 @@ impl Default for ExecutableInfo {
             symbols: Vec::new(),
             sections: Vec::new(),
             segments: Vec::new(),
+            imports: Vec::new(),
+            exports: Vec::new(),
+            libraries: Vec::new(),
+            reservations: Vec::new(),
+            relocations: Vec::new(),
+            file_size: 0,
+            file_offset: 0,
+            slices: Vec::new(),
+            arch: None,
+            members: Vec::new(),
+            capped_symbols: 0,
+        }
+    }
+}
+
+/// Directory, where distributions install separate debug info files
+const GLOBAL_DEBUG_DIR: &str = "/usr/lib/debug";
+
+/// Locates separate debug info file for a stripped executable at `path`, using `.gnu_debuglink`
+/// (looked up next to the executable, in `.debug` subdirectory, and under `/usr/lib/debug`),
+/// or build-id (`/usr/lib/debug/.build-id/xx/yyyy.debug`)
+fn find_debug_file(path: &std::path::Path, exe: &File) -> Option<std::path::PathBuf> {
+    let path = std::fs::canonicalize(path).ok()?;
+
+    let mut candidates = Vec::new();
+
+    if let Ok(Some((link, _crc))) = exe.gnu_debuglink() {
+        let link = String::from_utf8_lossy(link).to_string();
+
+        let dir = path.parent()?;
+
+        candidates.push(dir.join(&link));
+        candidates.push(dir.join(".debug").join(&link));
+        candidates.push(
+            std::path::Path::new(GLOBAL_DEBUG_DIR)
+                .join(dir.strip_prefix("/").unwrap_or(dir))
+                .join(&link)
+        );
+    }
+
+    if let Ok(Some(build_id)) = exe.build_id() && build_id.len() > 1 {
+        let hex = build_id.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+
+        candidates.push(
+            std::path::Path::new(GLOBAL_DEBUG_DIR)
+                .join(".build-id")
+                .join(&hex[..2])
+                .join(format!("{}.debug", &hex[2..]))
+        );
+    }
+
+    // Debug link can point to the executable itself, which would recurse forever
+    candidates.into_iter()
+        .find(|c| c.is_file() && std::fs::canonicalize(c).is_ok_and(|c| c != path))
+}
+
+/// Returns `true` if `name` is an ARM/AArch64 mapping symbol (`$a`, `$t`, `$d`, `$x`, optionally
+/// followed by `.<suffix>`), that marks the start of ARM code, Thumb code, data or A64 code
+fn is_arm_mapping_symbol(name: &str) -> bool {
+    match name.strip_prefix('$') {
+        Some(rest) => {
+            let mut chars = rest.chars();
+
+            matches!(chars.next(), Some('a' | 't' | 'd' | 'x'))
+                && matches!(chars.next(), None | Some('.'))
         }
+        None => false,
     }
 }
 
@@ -133,96 +1073,564 @@ pub fn parse_archive(path: &std::path::Path) -> Result<ExecutableInfo, Box<dyn s
                     size:       0,
                     addr:       0,
                     kind:       SymbolKind::Unknown,
+                    lang:       demangled.kind,
+                    section:    None,
+                    global:     true,
+                    aliases:    Vec::new(),
                 }
             }
         )
         .collect::<Vec<_>>();
 
-    Ok(ExecutableInfo { symbols, segments: vec![], sections: vec![] })
+    Ok(ExecutableInfo {
+        symbols,
+        segments: vec![],
+        sections: vec![],
+        imports: vec![],
+        exports: vec![],
+        libraries: vec![],
+        reservations: vec![],
+        file_size: data.len(),
+        ..Default::default()
+    })
 
 }
 
-/// Parses an executable
-pub fn parse(path: &std::path::Path) -> Result<ExecutableInfo, Box<dyn std::error::Error>> {
-    let file = std::fs::File::open(&path)?;
-    let data = unsafe { memmap2::Mmap::map(&file)? };
+/// Parses object files of an archive (`.rlib`/`.a`). Members without code or data (like
+/// `lib.rmeta`) are skipped. Sections of object files have no addresses, so they are laid out
+/// one after another, to keep symbol addresses unique
+fn parse_archive_members(data: &[u8]) -> Result<ExecutableInfo, Box<dyn std::error::Error>> {
+    let archive = object::read::archive::ArchiveFile::parse(data)?;
 
-    let exe = File::parse(&*data)?;
+    let mut sections = Vec::new();
+    let mut symbols = Vec::new();
+    let mut members = Vec::new();
 
-    let segments = exe.segments()
-        .map(
-            |s| Segment {
-                size: s.size() as usize,
-                addr: s.address() as usize,
-            }
-        )
-        .collect();
+    let mut next_addr = 0;
 
-    let sections = exe.sections()
-        .map(
-            |s| Section {
-                // TODO: Should add section type (`PROGBITS`/`NOBITS`/etc.) to filter later on
-                name: s.name().unwrap_or("?").to_string(),
-                addr: s.address() as usize,
-                size: s.size() as usize,
-            }
-        )
-        .collect();
+    for member in archive.members() {
+        let member = member?;
 
-    let mut symbols = exe.symbols()
-        .map(
-            |s| {
-                let demangled = demangle(s.name().unwrap_or("?"));
+        let Ok(obj) = File::parse(member.data(data)?) else {
+            continue;
+        };
 
-                // Try to guess crate, only if symbol is from rust
-                let extracted_crate = if demangled.kind == DemangledSymbolKind::Rust {
-                    crate_name_from_demangled(demangled.name.as_str())
-                } else {
-                    "?".to_string()
-                };
+        let (member_offset, member_size) = member.file_range();
 
-                Symbol {
-                    name:       demangled.name,
-                    crate_name: extracted_crate,
-                    size:       s.size() as usize,
-                    addr:       s.address() as usize,
-                    kind: match s.kind() {
-                        object::SymbolKind::Text => SymbolKind::Function,
-                        object::SymbolKind::Data => SymbolKind::Data,
-                        _                        => SymbolKind::Unknown,
-                    },
+        // Maps indices of sections in the object file to indices in `sections`
+        let mut indices = HashMap::new();
+
+        let mut code = 0;
+        let mut size = 0;
+
+        for s in obj.sections() {
+            let mut section = Section {
+                index:  sections.len(),
+                name:   s.name().unwrap_or("?").to_string(),
+                addr:   0,
+                size:   s.size() as usize,
+                offset: s.file_range().map(|(offset, _)| (member_offset + offset) as usize),
+                kind:   section_kind(&s),
+            };
+
+            if section.has_symbols() {
+                section.addr = next_addr;
+                next_addr += section.size.max(1);
+
+                size += section.size;
+
+                if section.kind == SectionKind::Code {
+                    code += section.size;
                 }
             }
-        )
-        .filter(|s| s.kind != SymbolKind::Unknown)
-        .collect::<Vec<_>>();
 
+            indices.insert(s.index().0, (sections.len(), section.addr, s.address() as usize));
+            sections.push(section);
+        }
+
+        // Metadata can be wrapped into an object file too (`lib.rmeta`), but it holds no code
+        if size == 0 {
+            continue;
+        }
+
+        let count = symbols.len();
+
+        symbols.extend(
+            obj.symbols()
+                .filter(|s| s.is_definition() && !is_arm_mapping_symbol(s.name().unwrap_or("")))
+                .filter_map(|s| {
+                    let (section, base, addr) = *indices.get(&s.section_index()?.0)?;
+
+                    new_symbol(
+                        s.name().unwrap_or("?"),
+                        s.size() as usize,
+                        base + (s.address() as usize).saturating_sub(addr),
+                        s.kind(),
+                        Some(section),
+                        s.is_global(),
+                    )
+                })
+        );
+
+        members.push(ArchiveMember {
+            name:      String::from_utf8_lossy(member.name()).to_string(),
+            file_size: member_size as usize,
+            code,
+            data:      size - code,
+            symbols:   symbols.len() - count,
+        });
+    }
+
+    let capped_symbols = fix_symbol_sizes(&mut symbols, &sections);
+
+    Ok(ExecutableInfo {
+        symbols, sections, members, capped_symbols,
+        file_size: data.len(),
+        ..Default::default()
+    })
+}
+
+/// Builds a symbol from its raw (mangled) name, guessing crate name for rust symbols. Returns
+/// `None` for symbols, that are neither functions nor data (files, sections, etc.)
+fn new_symbol(name: &str, size: usize, addr: usize, kind: object::SymbolKind, section: Option<usize>, global: bool) -> Option<Symbol> {
+    let kind = match kind {
+        object::SymbolKind::Text => SymbolKind::Function,
+        object::SymbolKind::Data => SymbolKind::Data,
+        object::SymbolKind::Tls  => SymbolKind::Data,
+        _                        => return None,
+    };
+
+    let demangled = demangle(name);
+
+    // Try to guess crate, only if symbol is from rust
+    let crate_name = if demangled.kind == DemangledSymbolKind::Rust {
+        crate_name_from_demangled(demangled.name.as_str())
+    } else {
+        "?".to_string()
+    };
+
+    Some(Symbol {
+        name: demangled.name,
+        crate_name,
+        size,
+        addr,
+        kind,
+        lang: demangled.kind,
+        section,
+        global,
+        aliases: Vec::new(),
+    })
+}
+
+/// Sorts symbols by address, calculates sizes of symbols, that don't have one, and caps sizes,
+/// that reach past the end of symbol's section or into the next symbol of the section. Returns
+/// number of capped symbols
+fn fix_symbol_sizes(symbols: &mut [Symbol], sections: &[Section]) -> usize {
     // Symbols need to be sorted in ascending order by address to calculate size
     symbols.sort_by_key(|s| s.addr);
 
-    for i in 0..symbols.len() - 1 {
+    // Address range of section, that contains the symbol. Sections without size (or unknown
+    // ones) can't bound anything
+    let bounds = |sym: &Symbol| sym.section
+        .and_then(|i| sections.get(i))
+        .filter(|s| s.size != 0)
+        .map(|s| s.addr..s.addr + s.size);
+
+    for i in 0..symbols.len() {
         let sym = &symbols[i];
 
-        if sym.size == 0 {
+        // Symbols outside of sections (imports, absolute symbols) don't occupy any space
+        if sym.size == 0 && sym.section.is_some() {
             // Mach-O doesn't store symbol sizes, so they have to be calculated by hand
             // With symbols sorted, we can easily find next symbol to subtract current
             // symbol's address from the next (higher) one
             // This fix comes from binfarce macho.rs, I already started to bang my head
             // against the wall, so... much thanks to whoever found this
-            // TODO: Check if sizes are valid, especially for DATA symbols and for the last symbol
-            //       in section
-            //       For last symbol can subtract from section.origin + section.length
-            if let Some(next) = symbols[i..].iter().skip_while(|s| s.addr == sym.addr).next() {
-                // Avoid overflow: better to not have a size, than to have an invalid one
-                if next.addr > sym.addr {
-                    // Subtract current symbol address from next one
-                    symbols[i].size = next.addr - sym.addr;
+            let next = symbols[i..].iter().map(|s| s.addr).find(|addr| *addr > sym.addr);
+
+            // Symbol can't span past the end of its section, so the last symbol in section takes
+            // the rest of it, and next symbol, that is in another section, doesn't matter
+            let end = match (next, bounds(sym)) {
+                (Some(next), Some(section)) => Some(next.min(section.end)),
+                (next, None)                => next,
+                (None, Some(section))       => Some(section.end),
+            };
+
+            // Avoid overflow: better to not have a size, than to have an invalid one
+            if let Some(end) = end && end > sym.addr {
+                // Subtract current symbol address from next one
+                symbols[i].size = end - sym.addr;
+            }
+        }
+    }
+
+    let mut capped = 0;
+
+    // Address of the closest symbol after the current one in each section, along with the one
+    // after it, as symbols with the same address (aliases) don't bound each other. Sections of
+    // relocatable objects all start at 0, so only symbols of the same section are compared
+    let mut following = HashMap::<usize, (usize, Option<usize>)>::new();
+
+    // Sizes from the file can be garbage too (hand-written assembly, broken toolchains), and
+    // would inflate totals, if they were left as is. Symbol can't reach past the end of its
+    // section, or into the next symbol
+    for i in (0..symbols.len()).rev() {
+        let sym = &symbols[i];
+
+        let Some(index) = sym.section else {
+            continue;
+        };
+
+        let next = match following.get(&index) {
+            Some((addr, _)) if *addr > sym.addr => Some(*addr),
+            Some((_, next))                     => *next,
+            None                                => None,
+        };
+
+        following.entry(index)
+            .and_modify(|(addr, next)| if *addr != sym.addr { (*addr, *next) = (sym.addr, Some(*addr)) })
+            .or_insert((sym.addr, None));
+
+        let end = match (next, bounds(sym)) {
+            (Some(next), Some(section)) => Some(next.min(section.end)),
+            (next, None)                => next,
+            (None, Some(section))       => Some(section.end),
+        };
+
+        if sym.size != 0 && let Some(end) = end && sym.addr.saturating_add(sym.size) > end {
+            symbols[i].size = end.saturating_sub(sym.addr);
+            capped += 1;
+        }
+    }
+
+    capped
+}
+
+/// Returns architecture name of universal binary slice, as accepted by `--arch`
+fn arch_name(arch: object::Architecture) -> String {
+    match arch {
+        object::Architecture::Aarch64   => "arm64".to_string(),
+        object::Architecture::X86_64    => "x86_64".to_string(),
+        object::Architecture::I386      => "i386".to_string(),
+        object::Architecture::Arm       => "arm".to_string(),
+        object::Architecture::PowerPc   => "ppc".to_string(),
+        object::Architecture::PowerPc64 => "ppc64".to_string(),
+        arch                            => format!("{:?}", arch).to_lowercase(),
+    }
+}
+
+/// Returns architecture of the host, named as slices of universal binaries (see `arch_name`)
+pub fn host_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "aarch64" => "arm64",
+        "x86"     => "i386",
+        arch      => arch,
+    }
+}
+
+/// Lists slices of universal binary - architecture name & byte range in the file
+fn fat_slices<Fat: FatArch>(data: &[u8]) -> object::Result<Vec<(String, std::ops::Range<usize>)>> {
+    Ok(
+        MachOFatFile::<Fat>::parse(data)?.arches().iter()
+            .map(|arch| {
+                let (offset, size) = arch.file_range();
+
+                (arch_name(arch.architecture()), offset as usize..(offset + size) as usize)
+            })
+            .collect()
+    )
+}
+
+/// Parses an executable. If it's a universal (fat) Mach-O binary, slice of `arch` is parsed
+/// (host architecture, if `None`)
+pub fn parse(path: &std::path::Path, arch: Option<&str>) -> Result<ExecutableInfo, Box<dyn std::error::Error>> {
+    let file = std::fs::File::open(&path)?;
+    let data = unsafe { memmap2::Mmap::map(&file)? };
+
+    let slices = match object::FileKind::parse(&*data) {
+        Ok(object::FileKind::MachOFat32) => fat_slices::<object::macho::FatArch32>(&data)?,
+        Ok(object::FileKind::MachOFat64) => fat_slices::<object::macho::FatArch64>(&data)?,
+        _                                => Vec::new(),
+    };
+
+    let (arch, range) = if slices.is_empty() {
+        (None, 0..data.len())
+    } else {
+        // `aarch64` is what rust calls it, so it's accepted too
+        let arch = match arch.unwrap_or(host_arch()) {
+            "aarch64" => "arm64",
+            arch      => arch,
+        };
+
+        let (_, range) = slices.iter().find(|(name, _)| name == arch).ok_or_else(|| format!(
+            "Universal binary has no '{}' slice (available: {}), use --arch to select one",
+            arch,
+            slices.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(", ")
+        ))?;
+
+        (Some(arch.to_string()), range.clone())
+    };
+
+    let data = data.get(range.clone()).ok_or("Universal binary slice is out of file bounds")?;
+
+    if let Ok(object::FileKind::Archive) = object::FileKind::parse(data) {
+        return parse_archive_members(data);
+    }
+
+    let exe = File::parse(data)?;
+
+    let segments = match &exe {
+        // `object` only exposes `PT_LOAD` segments, so all program headers are parsed by hand
+        File::Elf32(elf) => elf_segments(elf),
+        File::Elf64(elf) => elf_segments(elf),
+        _ => exe.segments()
+            .map(
+                |s| Segment {
+                    kind:      s.name().ok().flatten().unwrap_or("LOAD").to_string(),
+                    load:      true,
+                    size:      s.size() as usize,
+                    addr:      s.address() as usize,
+                    phys_addr: s.address() as usize,
+                    file_size: s.file_range().1 as usize,
+                    flags:     s.flags().into(),
                 }
+            )
+            .collect(),
+    };
+
+    let sections = exe.sections()
+        .map(
+            |s| Section {
+                index:  s.index().0,
+                name:   s.name().unwrap_or("?").to_string(),
+                addr:   s.address() as usize,
+                size:   s.size() as usize,
+                offset: s.file_range().map(|(offset, _)| offset as usize),
+                kind:   section_kind(&s),
             }
+        )
+        .collect::<Vec<_>>();
+
+    // ELF thread-local symbols hold an offset into TLS block instead of an address, so they are
+    // rebased onto the start of TLS template
+    let tls_base = sections.iter()
+        .filter(|s| s.kind == SectionKind::Tls)
+        .map(|s| s.addr)
+        .min()
+        .unwrap_or(0);
+
+    let mut symbols = exe.symbols()
+        // Mapping symbols only mark code/data boundaries, so they carry no size and would break
+        // size calculation below by splitting real symbols into pieces
+        .filter(|s| !is_arm_mapping_symbol(s.name().unwrap_or("")))
+        .filter_map(
+            |s| new_symbol(
+                s.name().unwrap_or("?"),
+                s.size() as usize,
+                match s.kind() {
+                    object::SymbolKind::Tls if matches!(exe, object::File::Elf32(_) | object::File::Elf64(_)) => {
+                        tls_base + s.address() as usize
+                    }
+                    _ => s.address() as usize,
+                },
+                s.kind(),
+                s.section_index().and_then(|index| sections.iter().position(|sec| sec.index == index.0)),
+                s.is_global(),
+            )
+        )
+        .collect::<Vec<_>>();
+
+    // Stripped binary can still point to a separate file with debug info (and symbols)
+    if symbols.is_empty() && let Some(debug_path) = find_debug_file(path, &exe) {
+        symbols = parse(&debug_path, arch.as_deref())?.symbols_for(&sections);
+    }
+
+    let capped_symbols = fix_symbol_sizes(&mut symbols, &sections);
+
+    // Malformed import/export tables shouldn't prevent size analysis, so they're treated as empty
+    let imports = exe.imports()
+        .unwrap_or_default()
+        .iter()
+        .map(|i| Import {
+            name:    demangle(String::from_utf8_lossy(i.name()).as_ref()).name,
+            library: String::from_utf8_lossy(i.library()).to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    // Exports don't carry sizes, so they are taken from dynamic symbol table (ELF), or from
+    // symbols at the same address (other formats)
+    let export_sizes = exe.dynamic_symbols()
+        .filter_map(|s| Some((s.name_bytes().ok()?, s.size() as usize)))
+        .collect::<HashMap<_, _>>();
+
+    let exports = exe.exports()
+        .unwrap_or_default()
+        .iter()
+        .map(|e| {
+            let addr = e.address() as usize;
+
+            let size = export_sizes.get(e.name()).copied()
+                .filter(|size| *size != 0)
+                .or_else(|| symbols.iter().find(|s| s.addr == addr && s.size != 0).map(|s| s.size))
+                .unwrap_or(0);
+
+            Export { name: demangle(String::from_utf8_lossy(e.name()).as_ref()).name, addr, size }
+        })
+        .collect();
+
+    let libraries = match &exe {
+        File::Elf32(elf) => elf_needed(elf),
+        File::Elf64(elf) => elf_needed(elf),
+        // Other formats record libraries along with imports (Mach-O dylib ordinals, PE import
+        // descriptors)
+        _ => imports.iter()
+            .map(|i| i.library.clone())
+            .filter(|library| !library.is_empty())
+            .fold(Vec::new(), |mut libraries, library| {
+                if !libraries.contains(&library) {
+                    libraries.push(library);
+                }
+
+                libraries
+            }),
+    };
+
+    let reservations = reservations(&exe, &sections);
+
+    let relocations = exe.dynamic_relocations()
+        .map(|relocs| relocs
+            .map(|(addr, reloc)| Relocation {
+                addr:     addr as usize,
+                symbolic: matches!(reloc.target(), object::RelocationTarget::Symbol(_)),
+            })
+            .collect()
+        )
+        .unwrap_or_default();
+
+    Ok(ExecutableInfo {
+        segments, sections, symbols, imports, exports, libraries, reservations, relocations,
+        file_size:   data.len(),
+        file_offset: range.start,
+        slices:      slices.into_iter().map(|(name, _)| name).collect(),
+        arch,
+        capped_symbols,
+        ..Default::default()
+    })
+}
+
+/// Finds stack & heap reservations from linker-provided symbols (see `RESERVATION_SYMBOLS`).
+/// Reservation without known address is assumed to follow static RAM data, as linker scripts
+/// usually place it right after `.bss`. Reservations, that are covered by a section (like
+/// `.stack` or `._user_heap_stack`), are skipped, as that section already accounts for them
+fn reservations(exe: &object::File, sections: &[Section]) -> Vec<Reservation> {
+    let is_known = |name: &str| RESERVATION_SYMBOLS.iter()
+        .any(|r| [r.lower, r.upper, r.size].iter().any(|names| names.contains(&name)));
+
+    let known = exe.symbols()
+        .filter_map(|s| Some((s.name().ok().filter(|name| is_known(name))?, s.address() as usize)))
+        .collect::<HashMap<_, _>>();
+
+    let value = |names: &[&str]| names.iter().find_map(|name| known.get(name).copied());
+
+    let ram_end = sections.iter()
+        .filter(|s| s.kind.is_ram() && s.kind != SectionKind::Tls)
+        .map(|s| s.addr + s.size)
+        .max();
+
+    let mut reservations = Vec::new();
+
+    for symbols in RESERVATION_SYMBOLS {
+        let (lower, upper, size) = (value(symbols.lower), value(symbols.upper), value(symbols.size));
+
+        let size = match (size, lower, upper) {
+            (Some(size), _, _)                                => size,
+            (None, Some(lower), Some(upper)) if upper > lower => upper - lower,
+            _                                                 => continue,
+        };
+
+        let Some(addr) = lower.or(upper.map(|upper| upper.saturating_sub(size))).or(ram_end) else {
+            continue;
+        };
+
+        let covered = sections.iter()
+            .any(|s| s.kind.is_ram() && s.addr <= addr && addr < s.addr + s.size);
+
+        if size != 0 && !covered {
+            reservations.push(Reservation { name: symbols.name.to_string(), addr, size });
         }
     }
 
-    Ok(ExecutableInfo { segments, sections, symbols })
+    reservations
+}
+
+/// Returns name of ELF program header type
+fn elf_segment_kind(p_type: u32) -> String {
+    use object::elf::*;
+
+    match p_type {
+        PT_NULL         => "NULL".to_string(),
+        PT_LOAD         => "LOAD".to_string(),
+        PT_DYNAMIC      => "DYNAMIC".to_string(),
+        PT_INTERP       => "INTERP".to_string(),
+        PT_NOTE         => "NOTE".to_string(),
+        PT_SHLIB        => "SHLIB".to_string(),
+        PT_PHDR         => "PHDR".to_string(),
+        PT_TLS          => "TLS".to_string(),
+        PT_GNU_EH_FRAME => "GNU_EH_FRAME".to_string(),
+        PT_GNU_STACK    => "GNU_STACK".to_string(),
+        PT_GNU_RELRO    => "GNU_RELRO".to_string(),
+        PT_GNU_PROPERTY => "GNU_PROPERTY".to_string(),
+        PT_ARM_EXIDX    => "ARM_EXIDX".to_string(),
+        _               => format!("0x{:x}", p_type),
+    }
+}
+
+/// Parses all ELF program headers (not only `PT_LOAD`, like `object::Object::segments()` does)
+fn elf_segments<Elf: object::read::elf::FileHeader>(elf: &object::read::elf::ElfFile<Elf>) -> Vec<Segment> {
+    use object::read::elf::ProgramHeader;
+
+    let endian = elf.endian();
+
+    elf.elf_program_headers()
+        .iter()
+        .map(
+            |ph| {
+                let p_flags = ph.p_flags(endian);
+
+                Segment {
+                    kind:      elf_segment_kind(ph.p_type(endian)),
+                    load:      ph.p_type(endian) == object::elf::PT_LOAD,
+                    addr:      ph.p_vaddr(endian).into() as usize,
+                    phys_addr: ph.p_paddr(endian).into() as usize,
+                    size:      ph.p_memsz(endian).into() as usize,
+                    file_size: ph.p_filesz(endian).into() as usize,
+                    flags:     object::SegmentFlags::Elf { p_flags }.into(),
+                }
+            }
+        )
+        .collect()
+}
+
+/// Returns libraries from `DT_NEEDED` entries of ELF dynamic section, in the order they are listed
+fn elf_needed<Elf: object::read::elf::FileHeader>(elf: &object::read::elf::ElfFile<Elf>) -> Vec<String> {
+    use object::read::elf::Dyn;
+
+    let endian = elf.endian();
+    let sections = elf.elf_section_table();
+
+    let Ok(Some((dynamic, link))) = sections.dynamic(endian, elf.data()) else {
+        return Vec::new();
+    };
+
+    let Ok(strings) = sections.strings(endian, elf.data(), link) else {
+        return Vec::new();
+    };
+
+    dynamic.iter()
+        .filter(|d| d.tag32(endian) == Some(object::elf::DT_NEEDED))
+        .filter_map(|d| d.string(endian, strings).ok())
+        .map(|name| String::from_utf8_lossy(name).to_string())
+        .collect()
 }
 
 /// Try to find a crate name by symbol name in artifacts, if symbol has no crate
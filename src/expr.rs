@@ -0,0 +1,346 @@
+//! # `binsize::expr`
+//!
+//! Implements a small expression engine over symbol fields. It's used by derived columns -
+//! user-defined columns of symbol tables, and by `--where` symbol filter
+//!
+
+/// Fields, that can be used in derived columns
+pub const COLUMN_FIELDS: &[&str] = &[
+    "size", "addr", "percent", "retained", "aliases", "name", "crate", "kind", "lang", "section"
+];
+
+/// Fields, that can be used in `--where` filter
+pub const WHERE_FIELDS: &[&str] = &[
+    "size", "addr", "retained", "aliases", "name", "crate", "kind", "lang", "section"
+];
+
+/// Value of an expression or a field
+#[derive(Debug, Clone)]
+pub enum Value {
+    Number(f64),
+    Str(String),
+}
+
+impl Value {
+    /// Non-zero numbers and non-empty strings are true
+    pub fn truthy(&self) -> bool {
+        match self {
+            Value::Number(n) => *n != 0.0,
+            Value::Str(s)    => !s.is_empty(),
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Number(if value { 1.0 } else { 0.0 })
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => write!(f, "{}", *n as i64),
+            Value::Number(n)                                       => write!(f, "{:.02}", n),
+            Value::Str(s)                                          => write!(f, "{}", s),
+        }
+    }
+}
+
+/// Parsed expression
+pub enum Expr {
+    /// Numeric literal
+    Number(f64),
+
+    /// String literal (quoted, or a bare word, if allowed)
+    Str(String),
+
+    /// Value of a field
+    Field(String),
+
+    /// Negation
+    Neg(Box<Expr>),
+
+    /// Logical not
+    Not(Box<Expr>),
+
+    /// Regex match (`=~`)
+    Match(Box<Expr>, regex::Regex),
+
+    /// Binary operation (arithmetic, comparison or logical)
+    Binary(Box<Expr>, &'static str, Box<Expr>),
+}
+
+/// Binary operators from lowest to highest precedence
+const PRECEDENCE: &[&[&str]] = &[
+    &["||"],
+    &["&&"],
+    &["==", "!=", "<=", ">=", "<", ">"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+impl Expr {
+    /// Parses expression from `str`. Supports numbers, strings, `fields`, arithmetic (`+`, `-`,
+    /// `*`, `/`, `%`), comparison (`==`, `!=`, `<`, `<=`, `>`, `>=`), logical (`&&`, `||`, `!`)
+    /// operators, regex match (`=~`) and parentheses. If `bare_words` is `true`, identifiers,
+    /// that are not fields, are treated as strings (e.g. `kind == FUNC`)
+    pub fn parse(str: &str, fields: &'static [&'static str], bare_words: bool) -> Result<Self, String> {
+        let mut parser = Parser { tokens: tokenize(str)?, pos: 0, fields, bare_words };
+
+        let expr = parser.parse_binary(0)?;
+
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(format!("Unexpected '{}' in expression '{}'", token, str));
+        }
+
+        Ok(expr)
+    }
+
+    /// Evaluates expression, getting field values from `field`. Returns `None`, if some field
+    /// has no value, operands have wrong types, or the result is not a finite number (e.g.
+    /// division by zero)
+    pub fn eval(&self, field: &impl Fn(&str) -> Option<Value>) -> Option<Value> {
+        let value = match self {
+            Expr::Number(n)         => Value::Number(*n),
+            Expr::Str(s)            => Value::Str(s.clone()),
+            Expr::Field(name)       => field(name)?,
+            Expr::Neg(expr)         => match expr.eval(field)? {
+                Value::Number(n) => Value::Number(-n),
+                Value::Str(_)    => return Option::None,
+            },
+            Expr::Not(expr)         => (!expr.test(field)).into(),
+            Expr::Match(expr, re)   => re.is_match(&expr.eval(field)?.to_string()).into(),
+            Expr::Binary(lhs, "&&", rhs) => (lhs.test(field) && rhs.test(field)).into(),
+            Expr::Binary(lhs, "||", rhs) => (lhs.test(field) || rhs.test(field)).into(),
+            Expr::Binary(lhs, op, rhs) => {
+                match (lhs.eval(field)?, rhs.eval(field)?) {
+                    (Value::Number(lhs), Value::Number(rhs)) => match *op {
+                        "+"  => Value::Number(lhs + rhs),
+                        "-"  => Value::Number(lhs - rhs),
+                        "*"  => Value::Number(lhs * rhs),
+                        "/"  => Value::Number(lhs / rhs),
+                        "%"  => Value::Number(lhs % rhs),
+                        _    => compare(lhs.partial_cmp(&rhs)?, op).into(),
+                    },
+                    // Strings are compared case-insensitively, so `kind == func` works too
+                    (Value::Str(lhs), Value::Str(rhs)) => match *op {
+                        "==" => lhs.eq_ignore_ascii_case(&rhs).into(),
+                        "!=" => (!lhs.eq_ignore_ascii_case(&rhs)).into(),
+                        "<" | "<=" | ">" | ">=" => compare(lhs.to_lowercase().cmp(&rhs.to_lowercase()), op).into(),
+                        _    => return Option::None,
+                    },
+                    _ => return Option::None,
+                }
+            }
+        };
+
+        match value {
+            Value::Number(n) if !n.is_finite() => Option::None,
+            value                              => Some(value),
+        }
+    }
+
+    /// Evaluates expression as a condition. Missing values are false
+    pub fn test(&self, field: &impl Fn(&str) -> Option<Value>) -> bool {
+        self.eval(field).is_some_and(|v| v.truthy())
+    }
+
+    /// Returns `true` if expression uses field `name`
+    pub fn uses(&self, name: &str) -> bool {
+        match self {
+            Expr::Number(_) | Expr::Str(_) => false,
+            Expr::Field(field)             => field == name,
+            Expr::Neg(expr)                => expr.uses(name),
+            Expr::Not(expr)                => expr.uses(name),
+            Expr::Match(expr, _)           => expr.uses(name),
+            Expr::Binary(lhs, _, rhs)      => lhs.uses(name) || rhs.uses(name),
+        }
+    }
+}
+
+/// Converts result of comparison to the result of `op`
+fn compare(ordering: std::cmp::Ordering, op: &str) -> bool {
+    match op {
+        "==" => ordering.is_eq(),
+        "!=" => ordering.is_ne(),
+        "<"  => ordering.is_lt(),
+        "<=" => ordering.is_le(),
+        ">"  => ordering.is_gt(),
+        _    => ordering.is_ge(),
+    }
+}
+
+/// User-defined column
+pub struct DerivedColumn {
+    /// Column name (used as a header)
+    pub name: String,
+
+    /// Expression, that calculates column value
+    pub expr: Expr,
+}
+
+impl DerivedColumn {
+    /// Parses derived columns from `[binsize.columns]` config section:
+    ///
+    /// ```rust,ignore
+    /// [binsize.columns]
+    /// size_kib = "size / 1024"
+    /// ```
+    pub fn parse_table(table: &toml::Table) -> Result<Vec<DerivedColumn>, String> {
+        table.iter()
+            .map(|(name, value)| {
+                let expr = value.as_str()
+                    .ok_or_else(|| format!("Column '{}' must be a string with an expression", name))?;
+
+                Ok(DerivedColumn {
+                    name: name.clone(),
+                    expr: Expr::parse(expr, COLUMN_FIELDS, false).map_err(|e| format!("Column '{}': {}", name, e))?,
+                })
+            })
+            .collect()
+    }
+
+    /// Evaluates column value and formats it. Whole numbers are printed without fraction,
+    /// missing values as `-`
+    pub fn format(&self, field: &impl Fn(&str) -> Option<Value>) -> String {
+        match self.expr.eval(field) {
+            Some(value) => value.to_string(),
+            None        => "-".to_string(),
+        }
+    }
+}
+
+/// Splits expression into tokens: numbers, identifiers, strings (kept with leading `"`),
+/// operators & parentheses
+fn tokenize(str: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = str.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            _ if c.is_whitespace() => {}
+            '=' | '!' | '<' | '>' | '&' | '|' => {
+                let mut token = c.to_string();
+
+                if let Some(next) = chars.next_if(|n| matches!((c, n), ('&', '&') | ('|', '|') | (_, '=') | ('=', '~'))) {
+                    token.push(next);
+                }
+
+                if token == "=" || token == "&" || token == "|" {
+                    return Err(format!("Unexpected '{}' in expression '{}' (did you mean '{}{}'?)", token, str, token, c));
+                }
+
+                tokens.push(token);
+            }
+            '+' | '-' | '*' | '/' | '%' | '(' | ')' => tokens.push(c.to_string()),
+            '"' | '\'' => {
+                let mut token = "\"".to_string();
+
+                loop {
+                    match chars.next() {
+                        Some(n) if n == c => break,
+                        Some(n)           => token.push(n),
+                        None              => return Err(format!("Unterminated string in expression '{}'", str)),
+                    }
+                }
+
+                tokens.push(token);
+            }
+            _ if c.is_ascii_alphanumeric() || c == '_' || c == '.' => {
+                let mut token = c.to_string();
+
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '.') {
+                    token.push(c);
+                }
+
+                tokens.push(token);
+            }
+            _ => return Err(format!("Unexpected character '{}' in expression '{}'", c, str)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser over tokens
+struct Parser {
+    tokens:     Vec<String>,
+    pos:        usize,
+    fields:     &'static [&'static str],
+    bare_words: bool,
+}
+
+impl Parser {
+    /// Parses chain of binary operators with precedence `level` (see `PRECEDENCE`) or higher
+    fn parse_binary(&mut self, level: usize) -> Result<Expr, String> {
+        let Some(ops) = PRECEDENCE.get(level) else {
+            return self.parse_match();
+        };
+
+        let mut expr = self.parse_binary(level + 1)?;
+
+        while let Some(op) = self.tokens.get(self.pos).and_then(|t| ops.iter().find(|op| **op == t)) {
+            self.pos += 1;
+
+            expr = Expr::Binary(Box::new(expr), op, Box::new(self.parse_binary(level + 1)?));
+        }
+
+        Ok(expr)
+    }
+
+    /// Parses unary expression, optionally followed by `=~ "regex"`
+    fn parse_match(&mut self) -> Result<Expr, String> {
+        let expr = self.parse_unary()?;
+
+        if self.tokens.get(self.pos).map(|t| t.as_str()) != Some("=~") {
+            return Ok(expr);
+        }
+
+        self.pos += 1;
+
+        match self.parse_unary()? {
+            Expr::Str(re) => regex::Regex::new(&re)
+                .map(|re| Expr::Match(Box::new(expr), re))
+                .map_err(|e| format!("Invalid regex '{}': {}", re, e)),
+            _ => Err("Right side of '=~' must be a string".to_string()),
+        }
+    }
+
+    /// Parses unary minus/not, parenthesized expression, number, string or field
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        let token = self.tokens.get(self.pos).ok_or("Unexpected end of expression")?.clone();
+        self.pos += 1;
+
+        match token.as_str() {
+            "-" => Ok(Expr::Neg(Box::new(self.parse_unary()?))),
+            "!" => Ok(Expr::Not(Box::new(self.parse_unary()?))),
+            "(" => {
+                let expr = self.parse_binary(0)?;
+
+                if self.tokens.get(self.pos).map(|t| t.as_str()) != Some(")") {
+                    return Err("Missing ')'".to_string());
+                }
+
+                self.pos += 1;
+
+                Ok(expr)
+            }
+            _ if token.starts_with('"') => Ok(Expr::Str(token[1..].to_string())),
+            _ if token.starts_with(|c: char| c.is_ascii_digit() || c == '.') => {
+                token.parse::<f64>()
+                    .map(Expr::Number)
+                    .map_err(|_| format!("Invalid number '{}'", token))
+            }
+            _ if self.fields.contains(&token.as_str()) => Ok(Expr::Field(token)),
+            _ if token.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') => {
+                if self.bare_words {
+                    Ok(Expr::Str(token))
+                } else {
+                    Err(format!("Unknown field '{}' (possible values: {})", token, self.fields.join(", ")))
+                }
+            }
+            _ => Err(format!("Unexpected '{}'", token)),
+        }
+    }
+}
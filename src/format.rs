@@ -0,0 +1,774 @@
+//! # `binsize::format`
+//!
+//! Implements machine-readable output formats (JSON/CSV/Markdown), which can be produced alongside
+//! or instead of human-readable tables
+//!
+
+use std::io::Write;
+use std::collections::HashMap;
+use json::JsonValue;
+use crate::exe::{Export, Import, LibraryUsage, Section, Stats, Symbol, TlsUsage};
+use crate::link::MemoryRegion;
+use crate::delta;
+use crate::template::Template;
+
+/// Max symbols, listed in Markdown report
+const MARKDOWN_MAX_SYMBOLS: usize = 20;
+
+/// Output format
+#[derive(PartialEq, Clone, Copy)]
+pub enum OutputFormat {
+    /// Human-readable tables
+    Table,
+
+    /// Single JSON document
+    Json,
+
+    /// Comma separated values, one block per table
+    Csv,
+
+    /// JSON Lines - one JSON object per line (symbol, crate, etc.), written as soon as it's ready
+    Jsonl,
+
+    /// Markdown report for GitHub Actions job summary (`$GITHUB_STEP_SUMMARY`)
+    GhSummary,
+
+    /// Custom report, rendered through user template (`--template`)
+    Template,
+}
+
+impl OutputFormat {
+    /// Returns `true` if format is intended to be consumed by other programs
+    pub fn is_machine(&self) -> bool {
+        *self != OutputFormat::Table
+    }
+}
+
+impl TryFrom<&str> for OutputFormat {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        use OutputFormat::*;
+
+        match value {
+            "table"      => Ok(Table),
+            "json"       => Ok(Json),
+            "csv"        => Ok(Csv),
+            "jsonl"      => Ok(Jsonl),
+            "gh-summary" => Ok(GhSummary),
+            _            => Err(format!("Unknown output format: '{}' (possible values: table, json, csv, jsonl, gh-summary)", value)),
+        }
+    }
+}
+
+/// Data that is rendered into a machine-readable format. Tables, that weren't enabled for
+/// output, are left as `None`
+#[derive(Default)]
+pub struct MachineReport<'a> {
+    /// Path of analyzed file. Only set, if several files are reported in one run, so their
+    /// records can be told apart
+    pub file: Option<&'a str>,
+
+    /// Filtered symbols along with their percentages
+    pub symbols: Option<Vec<(&'a Symbol, f32)>>,
+
+    /// Crate names and their sizes
+    pub crates: Option<Vec<(String, usize)>>,
+
+    /// Crate categories (local, dependencies, std, unknown) and their sizes
+    pub categories: Option<Vec<(String, usize)>>,
+
+    /// Summary statistics
+    pub stats: Option<Stats>,
+
+    /// Symbols, imported from shared libraries
+    pub imports: Option<&'a Vec<Import>>,
+
+    /// Symbols, exported for dynamic linking
+    pub exports: Option<&'a Vec<Export>>,
+
+    /// Shared library dependencies
+    pub libraries: Option<Vec<LibraryUsage>>,
+
+    /// Thread-local storage usage
+    pub tls: Option<TlsUsage>,
+
+    /// Sections
+    pub sections: Option<&'a Vec<Section>>,
+
+    /// Memory regions from linker script
+    pub regions: Option<&'a Vec<MemoryRegion>>,
+
+    /// Previous report to compare against (only used by `GhSummary`)
+    pub baseline: Option<&'a Baseline>,
+}
+
+/// Totals, parsed from previously saved JSON report (`--format json`), used to show how sizes
+/// changed since then
+#[derive(Default)]
+pub struct Baseline {
+    /// Total size of all symbols, if report contained symbols
+    pub symbols_total: Option<usize>,
+
+    /// Symbol sizes by key (see `delta::keyed`), so symbols are matched even if rustc hashes
+    /// changed since then
+    pub symbols: HashMap<String, usize>,
+
+    /// Crate sizes by name
+    pub crates: HashMap<String, usize>,
+
+    /// Section sizes by name
+    pub sections: HashMap<String, usize>,
+
+    /// Used space of memory regions by name
+    pub regions: HashMap<String, usize>,
+}
+
+impl Baseline {
+    /// Parses JSON report, produced by `--format json`
+    pub fn from_file(path: &std::path::Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let root = json::parse(std::fs::read_to_string(path)?.as_str())?;
+
+        let mut baseline = Baseline::default();
+
+        if root["symbols"].is_array() {
+            baseline.symbols_total = Some(
+                root["symbols"].members().filter_map(|s| s["size"].as_usize()).sum()
+            );
+
+            baseline.symbols = delta::keyed(
+                root["symbols"].members()
+                    .filter_map(|s| Some((s["name"].as_str()?, s["addr"].as_usize()?, s["size"].as_usize()?)))
+                    .collect()
+            ).into_iter().map(|(size, key)| (key, size)).collect();
+        }
+
+        let named = |key: &str, value: &str| {
+            root[key].members()
+                .filter_map(|obj| Some((obj["name"].as_str()?.to_string(), obj[value].as_usize()?)))
+                .collect::<HashMap<_, _>>()
+        };
+
+        baseline.crates   = named("crates", "size");
+        baseline.sections = named("sections", "size");
+        baseline.regions  = named("regions", "used");
+
+        Ok(baseline)
+    }
+}
+
+impl MachineReport<'_> {
+    /// Renders report into a string using `format`
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Json  => self.to_json().pretty(2),
+            OutputFormat::Csv   => self.to_csv(),
+            OutputFormat::GhSummary => self.to_markdown(),
+            OutputFormat::Table => panic!("Table is not a machine-readable format"),
+            OutputFormat::Template => panic!("Template report is rendered with render_template()"),
+            OutputFormat::Jsonl => panic!("JSON Lines are written into the stream with write_jsonl()"),
+        }
+    }
+
+    /// Renders report through `template`. Template sees the same data as JSON format
+    pub fn render_template(&self, template: &Template) -> String {
+        template.render(&self.to_json())
+    }
+
+    /// Writes report as JSON Lines into `out`, one object per line. Each object has a `type`
+    /// field (`symbol`, `crate`, `category`, `stats`, `tls`, `import`, `export`, `library`,
+    /// `section` or `region`), followed by `file`, if it's set.
+    /// Each line is written as soon as it's built, so JSON of whole report is never in memory
+    pub fn write_jsonl(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let mut write_line = |kind: &str, mut obj: JsonValue| {
+            // `type` goes first, so lines are easier to read & grep
+            let mut line = JsonValue::new_object();
+
+            line["type"] = kind.into();
+
+            if let Some(file) = self.file {
+                line["file"] = file.into();
+            }
+
+            for (key, value) in obj.entries_mut() {
+                line[key] = value.take();
+            }
+
+            writeln!(out, "{}", line.dump())
+        };
+
+        if let Some(symbols) = &self.symbols {
+            for (sym, percentage) in symbols {
+                write_line("symbol", symbol_json(sym, *percentage))?;
+            }
+        }
+
+        if let Some(crates) = &self.crates {
+            for (name, size) in crates {
+                write_line("crate", size_json(name, *size))?;
+            }
+        }
+
+        if let Some(categories) = &self.categories {
+            for (name, size) in categories {
+                write_line("category", size_json(name, *size))?;
+            }
+        }
+
+        if let Some(stats) = &self.stats {
+            write_line("stats", stats_json(stats))?;
+        }
+
+        if let Some(tls) = &self.tls {
+            write_line("tls", tls_json(tls))?;
+        }
+
+        if let Some(imports) = self.imports {
+            for import in imports {
+                write_line("import", import_json(import))?;
+            }
+        }
+
+        if let Some(exports) = self.exports {
+            for export in exports {
+                write_line("export", export_json(export))?;
+            }
+        }
+
+        if let Some(libraries) = &self.libraries {
+            for library in libraries {
+                write_line("library", library_json(library))?;
+            }
+        }
+
+        if let Some(sections) = self.sections {
+            for section in sections {
+                write_line("section", section_json(section))?;
+            }
+        }
+
+        if let Some(regions) = self.regions {
+            for reg in regions {
+                write_line("region", region_json(reg))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds JSON document from all present tables
+    pub fn to_json(&self) -> JsonValue {
+        let mut root = JsonValue::new_object();
+
+        if let Some(file) = self.file {
+            root["file"] = file.into();
+        }
+
+        if let Some(symbols) = &self.symbols {
+            let mut arr = JsonValue::new_array();
+
+            for (sym, percentage) in symbols {
+                arr.push(symbol_json(sym, *percentage)).unwrap();
+            }
+
+            root["symbols"] = arr;
+        }
+
+        if let Some(crates) = &self.crates {
+            let mut arr = JsonValue::new_array();
+
+            for (name, size) in crates {
+                arr.push(size_json(name, *size)).unwrap();
+            }
+
+            root["crates"] = arr;
+        }
+
+        if let Some(categories) = &self.categories {
+            let mut arr = JsonValue::new_array();
+
+            for (name, size) in categories {
+                arr.push(size_json(name, *size)).unwrap();
+            }
+
+            root["categories"] = arr;
+        }
+
+        if let Some(stats) = &self.stats {
+            root["stats"] = stats_json(stats);
+        }
+
+        if let Some(tls) = &self.tls {
+            root["tls"] = tls_json(tls);
+        }
+
+        if let Some(imports) = self.imports {
+            let mut arr = JsonValue::new_array();
+
+            for import in imports {
+                arr.push(import_json(import)).unwrap();
+            }
+
+            root["imports"] = arr;
+        }
+
+        if let Some(exports) = self.exports {
+            let mut arr = JsonValue::new_array();
+
+            for export in exports {
+                arr.push(export_json(export)).unwrap();
+            }
+
+            root["exports"] = arr;
+        }
+
+        if let Some(libraries) = &self.libraries {
+            let mut arr = JsonValue::new_array();
+
+            for library in libraries {
+                arr.push(library_json(library)).unwrap();
+            }
+
+            root["libraries"] = arr;
+        }
+
+        if let Some(sections) = self.sections {
+            let mut arr = JsonValue::new_array();
+
+            for section in sections {
+                arr.push(section_json(section)).unwrap();
+            }
+
+            root["sections"] = arr;
+        }
+
+        if let Some(regions) = self.regions {
+            let mut arr = JsonValue::new_array();
+
+            for reg in regions {
+                arr.push(region_json(reg)).unwrap();
+            }
+
+            root["regions"] = arr;
+        }
+
+        root
+    }
+
+    /// Builds Markdown report from all present tables. If `baseline` is set, `Δ` columns show
+    /// the change against it. Only the largest symbols are listed, to keep summary readable
+    fn to_markdown(&self) -> String {
+        let mut md = vec![match self.file {
+            Some(file) => format!("## binsize report: {}", markdown_code(file)),
+            None       => "## binsize report".to_string(),
+        }];
+
+        let delta = |size: usize, base: Option<usize>| {
+            base.map(|base| format!("{:+}", size as i64 - base as i64))
+        };
+
+        // Renders a table, appending `Δ` column to each row if baseline is present. Rows without
+        // delta (e.g. categories) are passed with `None`
+        let table = |header: &[&str], rows: Vec<(Vec<String>, Option<String>)>| {
+            let with_delta = self.baseline.is_some() && rows.iter().any(|(_, delta)| delta.is_some());
+
+            let mut header = header.iter().map(|h| h.to_string()).collect::<Vec<_>>();
+
+            if with_delta {
+                header.push("Δ".to_string());
+            }
+
+            let mut lines = vec![
+                format!("| {} |", header.join(" | ")),
+                format!("|{}", "---|".repeat(header.len())),
+            ];
+
+            for (mut row, delta) in rows {
+                if with_delta {
+                    row.push(delta.unwrap_or_default());
+                }
+
+                lines.push(format!("| {} |", row.join(" | ")));
+            }
+
+            lines.join("\n")
+        };
+
+        if let Some(symbols) = &self.symbols {
+            let total = symbols.iter().fold(0, |r, (s, _)| r + s.size);
+            let base = self.baseline.and_then(|b| b.symbols_total);
+
+            md.push(match base {
+                Some(base) => format!("**Total size of symbols:** {} bytes ({} bytes)", total, delta(total, Some(base)).unwrap()),
+                None       => format!("**Total size of symbols:** {} bytes", total),
+            });
+
+            // Baseline symbols are matched by key, only if baseline report contained symbols
+            let keys = delta::keyed(symbols.iter().map(|(sym, _)| (sym.name.as_str(), sym.addr, sym.addr)).collect())
+                .into_iter()
+                .collect::<HashMap<_, _>>();
+
+            let symbol_base = |sym: &Symbol| {
+                self.baseline
+                    .filter(|b| b.symbols_total.is_some())
+                    .map(|b| b.symbols.get(&keys[&sym.addr]).copied().unwrap_or(0))
+            };
+
+            let mut largest = symbols.iter().collect::<Vec<_>>();
+            largest.sort_by_key(|(sym, _)| std::cmp::Reverse(sym.size));
+
+            md.push("### Largest symbols".to_string());
+            md.push(table(
+                &["Size", "Percentage", "Crate", "Name"],
+                largest.into_iter()
+                    .take(MARKDOWN_MAX_SYMBOLS)
+                    .map(|(sym, percentage)| (
+                        vec![
+                            sym.size.to_string(),
+                            format!("{:.02}%", percentage),
+                            markdown_code(&sym.crate_name),
+                            markdown_code(&sym.name),
+                        ],
+                        delta(sym.size, symbol_base(sym))
+                    ))
+                    .collect()
+            ));
+        }
+
+        if let Some(crates) = &self.crates {
+            let mut crates = crates.clone();
+            crates.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+
+            md.push("### Crates".to_string());
+            md.push(table(
+                &["Crate", "Size"],
+                crates.iter()
+                    .map(|(name, size)| (
+                        vec![markdown_code(name), size.to_string()],
+                        delta(*size, self.baseline.map(|b| b.crates.get(name).copied().unwrap_or(0)))
+                    ))
+                    .collect()
+            ));
+        }
+
+        if let Some(categories) = &self.categories {
+            md.push("### Categories".to_string());
+            md.push(table(
+                &["Category", "Size"],
+                categories.iter()
+                    .map(|(name, size)| (vec![name.clone(), size.to_string()], None))
+                    .collect()
+            ));
+        }
+
+        if let Some(stats) = &self.stats {
+            md.push("### Statistics".to_string());
+            md.push(table(
+                &["Statistic", "Value"],
+                stats.entries()
+                    .into_iter()
+                    .map(|(name, value)| (vec![name.to_string(), value], None))
+                    .collect()
+            ));
+        }
+
+        if let Some(sections) = self.sections {
+            md.push("### Sections".to_string());
+            md.push(table(
+                &["Section", "Size"],
+                sections.iter()
+                    .map(|section| (
+                        vec![markdown_code(&section.name), section.size.to_string()],
+                        delta(section.size, self.baseline.map(|b| b.sections.get(&section.name).copied().unwrap_or(0)))
+                    ))
+                    .collect()
+            ));
+        }
+
+        if let Some(regions) = self.regions {
+            md.push("### Memory regions".to_string());
+            md.push(table(
+                &["Region", "Used", "Size", "Percentage"],
+                regions.iter()
+                    .map(|reg| (
+                        vec![
+                            reg.name.clone(),
+                            reg.used.to_string(),
+                            reg.length.to_string(),
+                            format!("{:.02}%", reg.used_percentage),
+                        ],
+                        delta(reg.used, self.baseline.map(|b| b.regions.get(&reg.name).copied().unwrap_or(0)))
+                    ))
+                    .collect()
+            ));
+        }
+
+        md.join("\n\n") + "\n"
+    }
+
+    /// Builds CSV blocks from all present tables. Each block starts with `# <table>` line,
+    /// followed by a header line. Blocks are separated with an empty line. If `file` is set,
+    /// every line of a block starts with `file` column
+    fn to_csv(&self) -> String {
+        let mut blocks = Vec::<Vec<String>>::new();
+
+        if let Some(symbols) = &self.symbols {
+            let mut block = vec!["# symbols".to_string(), "size,percent,kind,lang,crate,addr,name".to_string()];
+
+            for (sym, percentage) in symbols {
+                block.push(format!(
+                    "{},{:.02},{},{},{},{},{}",
+                    sym.size,
+                    percentage,
+                    sym.kind.to_string().trim(),
+                    csv_escape(&sym.lang.to_string()),
+                    csv_escape(&sym.crate_name),
+                    sym.addr,
+                    csv_escape(&sym.name)
+                ));
+            }
+
+            blocks.push(block);
+        }
+
+        if let Some(crates) = &self.crates {
+            let mut block = vec!["# crates".to_string(), "name,size".to_string()];
+
+            for (name, size) in crates {
+                block.push(format!("{},{}", csv_escape(name), size));
+            }
+
+            blocks.push(block);
+        }
+
+        if let Some(categories) = &self.categories {
+            let mut block = vec!["# categories".to_string(), "name,size".to_string()];
+
+            for (name, size) in categories {
+                block.push(format!("{},{}", name, size));
+            }
+
+            blocks.push(block);
+        }
+
+        if let Some(stats) = &self.stats {
+            let mut block = vec!["# stats".to_string(), "name,value".to_string()];
+
+            for (name, value) in stats.entries() {
+                block.push(format!("{},{}", name, value));
+            }
+
+            blocks.push(block);
+        }
+
+        if let Some(tls) = &self.tls {
+            let block = vec![
+                "# tls".to_string(),
+                "data,bss,total".to_string(),
+                format!("{},{},{}", tls.data, tls.bss, tls.total()),
+            ];
+
+            blocks.push(block);
+        }
+
+        if let Some(imports) = self.imports {
+            let mut block = vec!["# imports".to_string(), "library,name".to_string()];
+
+            for import in imports {
+                block.push(format!("{},{}", csv_escape(&import.library), csv_escape(&import.name)));
+            }
+
+            blocks.push(block);
+        }
+
+        if let Some(exports) = self.exports {
+            let mut block = vec!["# exports".to_string(), "addr,size,name".to_string()];
+
+            for export in exports {
+                block.push(format!("{},{},{}", export.addr, export.size, csv_escape(&export.name)));
+            }
+
+            blocks.push(block);
+        }
+
+        if let Some(libraries) = &self.libraries {
+            let mut block = vec!["# libraries".to_string(), "name,imports,stub_size".to_string()];
+
+            for library in libraries {
+                block.push(format!(
+                    "{},{},{}",
+                    csv_escape(&library.name),
+                    library.imports.map(|count| count.to_string()).unwrap_or_default(),
+                    library.stub_size.map(|size| size.to_string()).unwrap_or_default()
+                ));
+            }
+
+            blocks.push(block);
+        }
+
+        if let Some(sections) = self.sections {
+            let mut block = vec!["# sections".to_string(), "name,addr,size".to_string()];
+
+            for section in sections {
+                block.push(format!("{},{},{}", csv_escape(&section.name), section.addr, section.size));
+            }
+
+            blocks.push(block);
+        }
+
+        if let Some(regions) = self.regions {
+            let mut block = vec!["# regions".to_string(), "name,origin,used,length,percent".to_string()];
+
+            for reg in regions {
+                block.push(format!(
+                    "{},{},{},{},{:.02}",
+                    csv_escape(&reg.name), reg.origin, reg.used, reg.length, reg.used_percentage
+                ));
+            }
+
+            blocks.push(block);
+        }
+
+        if let Some(file) = self.file {
+            let file = csv_escape(file);
+
+            for block in blocks.iter_mut() {
+                for (i, line) in block.iter_mut().enumerate().skip(1) {
+                    *line = format!("{},{}", if i == 1 { "file" } else { file.as_str() }, line);
+                }
+            }
+        }
+
+        blocks.into_iter()
+            .map(|block| block.join("\n"))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// Builds JSON object of a symbol
+fn symbol_json(sym: &Symbol, percentage: f32) -> JsonValue {
+    let mut obj = JsonValue::new_object();
+
+    obj["name"]    = sym.name.as_str().into();
+    obj["crate"]   = sym.crate_name.as_str().into();
+    obj["kind"]    = sym.kind.to_string().trim().into();
+    obj["lang"]    = sym.lang.to_string().into();
+    obj["addr"]    = sym.addr.into();
+    obj["size"]    = sym.size.into();
+    obj["percent"] = percentage.into();
+
+    if !sym.aliases.is_empty() {
+        obj["aliases"] = sym.aliases.iter().map(|a| a.as_str()).collect::<Vec<_>>().into();
+    }
+
+    obj
+}
+
+/// Builds JSON object of summary statistics
+fn stats_json(stats: &Stats) -> JsonValue {
+    let mut obj = JsonValue::new_object();
+
+    obj["symbols"]   = stats.symbols.into();
+    obj["functions"] = stats.functions.into();
+    obj["data"]      = stats.data.into();
+    obj["total"]     = stats.total.into();
+    obj["mean"]      = stats.mean.into();
+    obj["median"]    = stats.median.into();
+    obj["max"]       = stats.max.into();
+    obj["crates"]    = stats.crates.into();
+    obj["file_size"] = stats.file_size.into();
+
+    obj
+}
+
+/// Builds JSON object of an imported symbol
+fn import_json(import: &Import) -> JsonValue {
+    let mut obj = JsonValue::new_object();
+
+    obj["name"]    = import.name.as_str().into();
+    obj["library"] = import.library.as_str().into();
+
+    obj
+}
+
+/// Builds JSON object of an exported symbol
+fn export_json(export: &Export) -> JsonValue {
+    let mut obj = JsonValue::new_object();
+
+    obj["name"] = export.name.as_str().into();
+    obj["addr"] = export.addr.into();
+    obj["size"] = export.size.into();
+
+    obj
+}
+
+/// Builds JSON object of thread-local storage usage
+fn tls_json(tls: &TlsUsage) -> JsonValue {
+    let mut obj = JsonValue::new_object();
+
+    obj["data"]  = tls.data.into();
+    obj["bss"]   = tls.bss.into();
+    obj["total"] = tls.total().into();
+
+    obj
+}
+
+/// Builds JSON object of a shared library dependency
+fn library_json(library: &LibraryUsage) -> JsonValue {
+    let mut obj = JsonValue::new_object();
+
+    obj["name"]      = library.name.as_str().into();
+    obj["imports"]   = library.imports.into();
+    obj["stub_size"] = library.stub_size.into();
+
+    obj
+}
+
+/// Builds JSON object of a named size (crate or category)
+fn size_json(name: &str, size: usize) -> JsonValue {
+    let mut obj = JsonValue::new_object();
+
+    obj["name"] = name.into();
+    obj["size"] = size.into();
+
+    obj
+}
+
+/// Builds JSON object of a section
+fn section_json(section: &Section) -> JsonValue {
+    let mut obj = JsonValue::new_object();
+
+    obj["name"] = section.name.as_str().into();
+    obj["addr"] = section.addr.into();
+    obj["size"] = section.size.into();
+
+    obj
+}
+
+/// Builds JSON object of a memory region
+fn region_json(reg: &MemoryRegion) -> JsonValue {
+    let mut obj = JsonValue::new_object();
+
+    obj["name"]    = reg.name.as_str().into();
+    obj["origin"]  = reg.origin.into();
+    obj["used"]    = reg.used.into();
+    obj["length"]  = reg.length.into();
+    obj["percent"] = reg.used_percentage.into();
+
+    obj
+}
+
+/// Wraps value into inline code, escaping table delimiters
+fn markdown_code(value: &str) -> String {
+    format!("`{}`", value.replace('|', "\\|").replace('`', "'"))
+}
+
+/// Quotes CSV value, if it contains a delimiter, a quote or a newline
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
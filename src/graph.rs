@@ -0,0 +1,555 @@
+//! # `binsize::graph`
+//!
+//! Implements symbol reference graph. Linked executables rarely keep relocations, so references
+//! are recovered from several sources: relocations (if present, e.g. with `--emit-relocs`),
+//! dynamic relocations, pointers in data sections and call instructions in code (x86, ARM Thumb
+//! & AArch64). Graph is an approximation - indirect calls through registers can't be seen
+//!
+
+use std::path::Path;
+use json::JsonValue;
+use object::{Architecture, File, Object, ObjectSection, ObjectSymbol, RelocationTarget, SectionKind};
+use crate::exe::{ExecutableInfo, VECTOR_TABLE_SECTIONS};
+
+/// Sections, that are kept by linker regardless of references, or are used by loader & runtime
+/// directly, so their symbols are roots
+const ROOT_SECTIONS: &[&str] = &[
+    ".init",
+    ".fini",
+    ".init_array",
+    ".fini_array",
+    ".preinit_array",
+    ".ctors",
+    ".dtors",
+    ".dynamic",
+    ".got",
+    ".got.plt",
+    ".eh_frame",
+    ".eh_frame_hdr",
+    ".interp",
+];
+
+/// Symbol in a graph
+pub struct Node {
+    /// Symbol name
+    pub name: String,
+
+    /// Crate name
+    pub crate_name: String,
+
+    /// Symbol address
+    pub addr: usize,
+
+    /// Symbol size
+    pub size: usize,
+}
+
+/// Graph of references between symbols
+pub struct ReferenceGraph {
+    /// Symbols, sorted by address
+    pub nodes: Vec<Node>,
+
+    /// Outgoing references of each node (indices into `nodes`)
+    pub edges: Vec<Vec<usize>>,
+
+    /// Nodes, that are referenced from outside of any symbol: entry point, exported symbols,
+    /// symbols of sections, used by loader & runtime (like `.init_array` or vector table), and
+    /// pointers from data without symbols. Sorted & deduplicated
+    pub roots: Vec<usize>,
+}
+
+impl ReferenceGraph {
+    /// Builds reference graph of symbols of `exe`, parsed from file at `path`
+    pub fn build(path: &Path, exe: &ExecutableInfo) -> Result<Self, Box<dyn std::error::Error>> {
+        let file = std::fs::File::open(path)?;
+        let data = unsafe { memmap2::Mmap::map(&file)? };
+
+        let data = data.get(exe.file_offset..exe.file_offset + exe.file_size)
+            .ok_or("Parsed image is out of file bounds")?;
+
+        let file = File::parse(data)?;
+
+        let mut nodes = exe.symbols.iter()
+            .filter(|s| s.size != 0)
+            .map(|s| Node {
+                name:       s.name.clone(),
+                crate_name: s.crate_name.clone(),
+                addr:       s.addr,
+                size:       s.size,
+            })
+            .collect::<Vec<_>>();
+
+        nodes.sort_by_key(|n| n.addr);
+        nodes.dedup_by_key(|n| n.addr);
+
+        let mut graph = Self { edges: vec![Vec::new(); nodes.len()], nodes, roots: Vec::new() };
+
+        graph.add_root(file.entry() as usize);
+
+        for export in exe.exports.iter() {
+            graph.add_root(export.addr);
+        }
+
+        // Symbols outside of sections (like imports) can't be removed either
+        for sym in exe.symbols.iter() {
+            if exe.section_of(sym).is_none_or(|s| is_root_section(&s.name)) {
+                graph.add_root(sym.addr);
+            }
+        }
+
+        let target_addr = |target: RelocationTarget, addend: i64| match target {
+            RelocationTarget::Symbol(index) => file.symbol_by_index(index).ok().map(|s| s.address() as usize),
+            RelocationTarget::Section(index) => file.section_by_index(index).ok()
+                .map(|s| (s.address() as i64 + addend) as usize),
+            RelocationTarget::Absolute => Some(addend as usize),
+            _ => None,
+        };
+
+        for (offset, reloc) in file.dynamic_relocations().into_iter().flatten() {
+            if let Some(target) = target_addr(reloc.target(), reloc.addend()) {
+                graph.add_reference(offset as usize, target);
+            }
+        }
+
+        for section in file.sections() {
+            let addr = section.address() as usize;
+
+            for (offset, reloc) in section.relocations() {
+                if let Some(target) = target_addr(reloc.target(), reloc.addend()) {
+                    graph.add_reference(addr + offset as usize, target);
+                }
+            }
+
+            let Ok(bytes) = section.data() else {
+                continue;
+            };
+
+            match section.kind() {
+                SectionKind::Text => {
+                    graph.scan_code(file.architecture(), addr, bytes);
+                }
+                SectionKind::Data | SectionKind::ReadOnlyData | SectionKind::ReadOnlyDataWithRel => {
+                    graph.scan_pointers(addr, bytes, file.is_64(), file.is_little_endian());
+                }
+                _ if section.name().is_ok_and(is_root_section) => {
+                    graph.scan_pointers(addr, bytes, file.is_64(), file.is_little_endian());
+                }
+                _ => {}
+            }
+        }
+
+        for edges in graph.edges.iter_mut() {
+            edges.sort();
+            edges.dedup();
+        }
+
+        graph.roots.sort();
+        graph.roots.dedup();
+
+        Ok(graph)
+    }
+
+    /// Returns index of node, that contains `addr`
+    fn find(&self, addr: usize) -> Option<usize> {
+        let index = self.nodes.partition_point(|n| n.addr <= addr).checked_sub(1)?;
+
+        (addr < self.nodes[index].addr + self.nodes[index].size).then_some(index)
+    }
+
+    /// Returns index of node, that starts at `addr`. Thumb function addresses have lowest bit
+    /// set, so it's ignored, if there is no node at exact address
+    fn find_start(&self, addr: usize) -> Option<usize> {
+        [addr, addr & !1].into_iter()
+            .find_map(|addr| self.nodes.binary_search_by_key(&addr, |n| n.addr).ok())
+    }
+
+    /// Marks node, that starts at `addr`, as a root
+    fn add_root(&mut self, addr: usize) {
+        if let Some(index) = self.find_start(addr) {
+            self.roots.push(index);
+        }
+    }
+
+    /// Adds reference from location `from` to `to`. References from outside of symbols make
+    /// their targets roots
+    fn add_reference(&mut self, from: usize, to: usize) {
+        let Some(to) = self.find(to) else {
+            return;
+        };
+
+        match self.find(from) {
+            Some(from) if from != to => self.edges[from].push(to),
+            Some(_)                  => {}
+            None                     => self.roots.push(to),
+        }
+    }
+
+    /// Adds references from pointer-sized values in data, that point to the start of a symbol
+    fn scan_pointers(&mut self, addr: usize, bytes: &[u8], is_64: bool, little_endian: bool) {
+        let size = if is_64 { 8 } else { 4 };
+
+        for (i, chunk) in bytes.chunks_exact(size).enumerate() {
+            let value = match (is_64, little_endian) {
+                (true, true)   => u64::from_le_bytes(chunk.try_into().unwrap()) as usize,
+                (true, false)  => u64::from_be_bytes(chunk.try_into().unwrap()) as usize,
+                (false, true)  => u32::from_le_bytes(chunk.try_into().unwrap()) as usize,
+                (false, false) => u32::from_be_bytes(chunk.try_into().unwrap()) as usize,
+            };
+
+            if value != 0 && let Some(to) = self.find_start(value) {
+                let to = self.nodes[to].addr;
+                self.add_reference(addr + i * size, to);
+            }
+        }
+    }
+
+    /// Adds references from direct calls, jumps & memory operands in code, that target the
+    /// start of a symbol
+    fn scan_code(&mut self, arch: Architecture, addr: usize, bytes: &[u8]) {
+        let mut refs = Vec::new();
+
+        match arch {
+            Architecture::X86_64 | Architecture::X86_64_X32 | Architecture::I386 => {
+                for i in 0..bytes.len().saturating_sub(4) {
+                    // `call rel32` & `jmp rel32`
+                    if bytes[i] == 0xE8 || bytes[i] == 0xE9 {
+                        let rel = i32::from_le_bytes(bytes[i + 1..i + 5].try_into().unwrap());
+                        refs.push((addr + i, (addr + i + 5).wrapping_add_signed(rel as isize)));
+                    }
+
+                    if let Some((end, disp)) = x86_memory_operand(&bytes[i..]) {
+                        // RIP-relative in 64-bit mode, absolute in 32-bit
+                        let target = match arch {
+                            Architecture::I386 => disp as u32 as usize,
+                            _                  => (addr + i + end).wrapping_add_signed(disp as isize),
+                        };
+
+                        refs.push((addr + i, target));
+                    }
+                }
+            }
+            Architecture::Arm => {
+                // Thumb-2 `bl` & `b.w`
+                for i in (0..bytes.len().saturating_sub(3)).step_by(2) {
+                    let hw1 = u16::from_le_bytes([bytes[i], bytes[i + 1]]) as u32;
+                    let hw2 = u16::from_le_bytes([bytes[i + 2], bytes[i + 3]]) as u32;
+
+                    if hw1 & 0xF800 != 0xF000 || hw2 & 0xD000 != 0xD000 && hw2 & 0xD000 != 0x9000 {
+                        continue;
+                    }
+
+                    let s = (hw1 >> 10) & 1;
+                    let i1 = !((hw2 >> 13) & 1 ^ s) & 1;
+                    let i2 = !((hw2 >> 11) & 1 ^ s) & 1;
+
+                    let imm = (s << 24) | (i1 << 23) | (i2 << 22) | ((hw1 & 0x3FF) << 12) | ((hw2 & 0x7FF) << 1);
+                    let rel = ((imm << 7) as i32) >> 7;
+
+                    refs.push((addr + i, (addr + i + 4).wrapping_add_signed(rel as isize)));
+                }
+            }
+            Architecture::Aarch64 => {
+                // `bl` & `b`
+                for (i, chunk) in bytes.chunks_exact(4).enumerate() {
+                    let insn = u32::from_le_bytes(chunk.try_into().unwrap());
+
+                    if insn & 0x7C00_0000 == 0x1400_0000 {
+                        let rel = (((insn & 0x03FF_FFFF) << 6) as i32) >> 4;
+                        refs.push((addr + i * 4, (addr + i * 4).wrapping_add_signed(rel as isize)));
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        // Random bytes can look like an instruction, so only references to the very start of a
+        // symbol are trusted
+        for (from, to) in refs {
+            if let Some(to) = self.find_start(to) {
+                let to = self.nodes[to].addr;
+                self.add_reference(from, to);
+            }
+        }
+    }
+
+    /// Writes graph into file at `path`. Format is chosen by extension: GraphML for `.graphml`,
+    /// JSON otherwise. Nodes carry retained sizes & root flags along with symbol info
+    pub fn export(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let contents = if path.extension().is_some_and(|ext| ext == "graphml") {
+            self.to_graphml()
+        } else {
+            self.to_json()?
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(path, contents)?;
+
+        Ok(())
+    }
+
+    /// Serializes graph into JSON: `nodes` array (indices are ids) and `edges` array of
+    /// `source`/`target` pairs
+    fn to_json(&self) -> Result<String, json::Error> {
+        let retained = self.retained_sizes();
+
+        let mut nodes = JsonValue::new_array();
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            let mut obj = JsonValue::new_object();
+
+            obj["id"]       = i.into();
+            obj["name"]     = node.name.as_str().into();
+            obj["crate"]    = node.crate_name.as_str().into();
+            obj["addr"]     = node.addr.into();
+            obj["size"]     = node.size.into();
+            obj["retained"] = retained[i].into();
+            obj["root"]     = self.roots.binary_search(&i).is_ok().into();
+
+            nodes.push(obj)?;
+        }
+
+        let mut edges = JsonValue::new_array();
+
+        for (from, to) in self.edges() {
+            let mut obj = JsonValue::new_object();
+
+            obj["source"] = from.into();
+            obj["target"] = to.into();
+
+            edges.push(obj)?;
+        }
+
+        let mut root = JsonValue::new_object();
+
+        root["nodes"] = nodes;
+        root["edges"] = edges;
+
+        Ok(root.pretty(2))
+    }
+
+    /// Serializes graph into GraphML
+    fn to_graphml(&self) -> String {
+        let retained = self.retained_sizes();
+
+        let mut xml = String::new();
+
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+
+        for (id, kind) in [("name", "string"), ("crate", "string"), ("addr", "long"), ("size", "long"), ("retained", "long"), ("root", "boolean")] {
+            xml.push_str(&format!("  <key id=\"{}\" for=\"node\" attr.name=\"{}\" attr.type=\"{}\"/>\n", id, id, kind));
+        }
+
+        xml.push_str("  <graph id=\"references\" edgedefault=\"directed\">\n");
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            xml.push_str(&format!("    <node id=\"n{}\">\n", i));
+            xml.push_str(&format!("      <data key=\"name\">{}</data>\n", xml_escape(&node.name)));
+            xml.push_str(&format!("      <data key=\"crate\">{}</data>\n", xml_escape(&node.crate_name)));
+            xml.push_str(&format!("      <data key=\"addr\">{}</data>\n", node.addr));
+            xml.push_str(&format!("      <data key=\"size\">{}</data>\n", node.size));
+            xml.push_str(&format!("      <data key=\"retained\">{}</data>\n", retained[i]));
+            xml.push_str(&format!("      <data key=\"root\">{}</data>\n", self.roots.binary_search(&i).is_ok()));
+            xml.push_str("    </node>\n");
+        }
+
+        for (from, to) in self.edges() {
+            xml.push_str(&format!("    <edge source=\"n{}\" target=\"n{}\"/>\n", from, to));
+        }
+
+        xml.push_str("  </graph>\n");
+        xml.push_str("</graphml>\n");
+
+        xml
+    }
+
+    /// Returns all edges as `(from, to)` pairs of node indices
+    fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.edges.iter()
+            .enumerate()
+            .flat_map(|(from, edges)| edges.iter().map(move |to| (from, *to)))
+    }
+
+    /// Returns indices of nodes, that can't be reached from any of the roots
+    pub fn unreachable(&self) -> Vec<usize> {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = self.roots.clone();
+
+        while let Some(node) = stack.pop() {
+            if !visited[node] {
+                visited[node] = true;
+                stack.extend(self.edges[node].iter().filter(|n| !visited[**n]));
+            }
+        }
+
+        (0..self.nodes.len()).filter(|n| !visited[*n]).collect()
+    }
+
+    /// Calculates retained size of each node - its own size plus sizes of all nodes, that are
+    /// only reachable through it (which it dominates). Nodes, that aren't reachable from roots,
+    /// are treated as roots themselves
+    pub fn retained_sizes(&self) -> Vec<usize> {
+        let n = self.nodes.len();
+
+        // Synthetic root gets index `n`, and references all roots
+        let mut successors = self.edges.clone();
+        successors.push(self.roots.clone());
+
+        let mut predecessors = vec![Vec::new(); n + 1];
+
+        for (from, edges) in successors.iter().enumerate() {
+            for to in edges.iter() {
+                predecessors[*to].push(from);
+            }
+        }
+
+        // Nodes without references can't be reached, so they are attached to the root
+        for (node, preds) in predecessors.iter_mut().enumerate().take(n) {
+            if preds.is_empty() {
+                successors[n].push(node);
+                preds.push(n);
+            }
+        }
+
+        // Postorder numbering with iterative DFS. Unvisited nodes (cycles without outside
+        // references) are attached to the root, until everything is visited
+        let mut postorder = Vec::with_capacity(n + 1);
+        let mut visited = vec![false; n + 1];
+
+        postorder_dfs(n, &successors, &mut visited, &mut postorder);
+
+        for node in 0..n {
+            if !visited[node] {
+                successors[n].push(node);
+                predecessors[node].push(n);
+                postorder_dfs(node, &successors, &mut visited, &mut postorder);
+            }
+        }
+
+        // Root must be the last in postorder
+        postorder.retain(|node| *node != n);
+        postorder.push(n);
+
+        let mut order = vec![0; n + 1];
+
+        for (i, node) in postorder.iter().enumerate() {
+            order[*node] = i;
+        }
+
+        // Iterative dominators algorithm (Cooper, Harvey & Kennedy)
+        let mut idom = vec![usize::MAX; n + 1];
+        idom[n] = n;
+
+        let intersect = |idom: &[usize], mut a: usize, mut b: usize| {
+            while a != b {
+                while order[a] < order[b] {
+                    a = idom[a];
+                }
+
+                while order[b] < order[a] {
+                    b = idom[b];
+                }
+            }
+
+            a
+        };
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for node in postorder.iter().rev().skip(1) {
+                let mut new_idom = usize::MAX;
+
+                for pred in predecessors[*node].iter().filter(|p| idom[**p] != usize::MAX) {
+                    new_idom = if new_idom == usize::MAX { *pred } else { intersect(&idom, *pred, new_idom) };
+                }
+
+                if new_idom != usize::MAX && idom[*node] != new_idom {
+                    idom[*node] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+
+        // Children come before their dominators in postorder, so sizes can be accumulated
+        // in a single pass
+        let mut retained = self.nodes.iter().map(|n| n.size).collect::<Vec<_>>();
+        retained.push(0);
+
+        for node in postorder.iter().filter(|node| **node != n) {
+            retained[idom[*node]] += retained[*node];
+        }
+
+        retained.truncate(n);
+        retained
+    }
+}
+
+/// Opcodes (without `0x0F` escape), that take ModRM operand and no immediate: `mov`, `lea`,
+/// `cmp`, `test` & arithmetic
+const X86_MODRM_OPCODES: &[u8] = &[0x89, 0x8B, 0x8D, 0x39, 0x3B, 0x85, 0x03, 0x0B, 0x23, 0x2B, 0x33];
+
+/// Same, but with `0x0F` escape: `movzx` & `movsx`
+const X86_MODRM_OPCODES_0F: &[u8] = &[0xB6, 0xB7, 0xBE, 0xBF];
+
+/// Decodes x86 instruction at the start of `bytes`, if it's one of `X86_MODRM_OPCODES*` with
+/// `[disp32]` (or `[rip + disp32]` in 64-bit mode) memory operand. Returns instruction length
+/// and displacement
+fn x86_memory_operand(bytes: &[u8]) -> Option<(usize, i32)> {
+    // Optional REX prefix
+    let mut i = usize::from(bytes.first().is_some_and(|b| (0x40..=0x4F).contains(b)));
+
+    match *bytes.get(i)? {
+        0x0F if X86_MODRM_OPCODES_0F.contains(bytes.get(i + 1)?) => i += 2,
+        op if X86_MODRM_OPCODES.contains(&op) => i += 1,
+        _ => return None,
+    }
+
+    // mod = 00 & r/m = 101
+    if bytes.get(i)? & 0xC7 != 0x05 {
+        return None;
+    }
+
+    let disp = i32::from_le_bytes(bytes.get(i + 1..i + 5)?.try_into().unwrap());
+
+    Some((i + 5, disp))
+}
+
+/// Escapes special XML characters in `s`
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Returns `true`, if symbols of section `name` are roots
+fn is_root_section(name: &str) -> bool {
+    ROOT_SECTIONS.contains(&name) || VECTOR_TABLE_SECTIONS.contains(&name) || name.starts_with(".note")
+}
+
+/// Appends nodes, reachable from `start` and not `visited` yet, to `postorder`
+fn postorder_dfs(start: usize, successors: &[Vec<usize>], visited: &mut [bool], postorder: &mut Vec<usize>) {
+    let mut stack = vec![(start, 0)];
+    visited[start] = true;
+
+    while let Some((node, next)) = stack.last_mut() {
+        if let Some(succ) = successors[*node].get(*next).copied() {
+            *next += 1;
+
+            if !visited[succ] {
+                visited[succ] = true;
+                stack.push((succ, 0));
+            }
+        } else {
+            postorder.push(*node);
+            stack.pop();
+        }
+    }
+}
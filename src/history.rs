@@ -0,0 +1,166 @@
+//! # `binsize::history`
+//!
+//! Implements historical size tracking - totals of each run can be recorded into a JSON Lines
+//! file, and later printed as a trend
+//!
+
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use json::JsonValue;
+
+/// Default location of history file inside target directory
+pub const DEFAULT_HISTORY_FILE: &str = "binsize/history.jsonl";
+
+/// Totals of a single recorded run
+pub struct Record {
+    /// Unix timestamp (seconds)
+    pub timestamp: u64,
+
+    /// Short git commit hash (empty, if not in a git repo)
+    pub commit: String,
+
+    /// Path of analyzed file, if multiple files were analyzed in the run (empty otherwise)
+    pub file: String,
+
+    /// Total size of all symbols
+    pub total: usize,
+
+    /// Crate names and their sizes
+    pub crates: Vec<(String, usize)>,
+
+    /// Section names and their sizes
+    pub sections: Vec<(String, usize)>,
+
+    /// Memory region names and their used space
+    pub regions: Vec<(String, usize)>,
+}
+
+impl Record {
+    /// Creates a record for current moment & commit
+    pub fn new(
+        file: String,
+        total: usize,
+        crates: Vec<(String, usize)>,
+        sections: Vec<(String, usize)>,
+        regions: Vec<(String, usize)>
+    ) -> Self {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        Self { timestamp, commit: git_commit(), file, total, crates, sections, regions }
+    }
+
+    /// Converts record into single-line JSON object
+    fn to_json(&self) -> JsonValue {
+        let named = |values: &[(String, usize)]| {
+            let mut obj = JsonValue::new_object();
+
+            for (name, size) in values {
+                obj[name.as_str()] = (*size).into();
+            }
+
+            obj
+        };
+
+        let mut obj = JsonValue::new_object();
+
+        obj["timestamp"] = self.timestamp.into();
+        obj["commit"]    = self.commit.as_str().into();
+
+        if !self.file.is_empty() {
+            obj["file"] = self.file.as_str().into();
+        }
+
+        obj["total"]     = self.total.into();
+        obj["crates"]    = named(&self.crates);
+        obj["sections"]  = named(&self.sections);
+        obj["regions"]   = named(&self.regions);
+
+        obj
+    }
+
+    /// Parses record from JSON object
+    fn from_json(obj: &JsonValue) -> Option<Self> {
+        let named = |value: &JsonValue| {
+            value.entries()
+                .filter_map(|(name, size)| Some((name.to_string(), size.as_usize()?)))
+                .collect::<Vec<_>>()
+        };
+
+        Some(Self {
+            timestamp: obj["timestamp"].as_u64()?,
+            commit:    obj["commit"].as_str().unwrap_or("").to_string(),
+            file:      obj["file"].as_str().unwrap_or("").to_string(),
+            total:     obj["total"].as_usize()?,
+            crates:    named(&obj["crates"]),
+            sections:  named(&obj["sections"]),
+            regions:   named(&obj["regions"]),
+        })
+    }
+
+    /// Returns size of region `name`, if it was recorded
+    pub fn region(&self, name: &str) -> Option<usize> {
+        self.regions.iter().find(|(n, _)| n == name).map(|(_, size)| *size)
+    }
+
+    /// Formats timestamp as `YYYY-MM-DD HH:MM` (UTC)
+    pub fn date(&self) -> String {
+        let days = (self.timestamp / 86400) as i64;
+        let secs = self.timestamp % 86400;
+
+        // Converts days since epoch into a civil date (Howard Hinnant's algorithm)
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        format!("{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, secs / 3600, secs % 3600 / 60)
+    }
+}
+
+/// Returns short hash of current git commit, or an empty string
+fn git_commit() -> String {
+    Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Appends record to history file, creating it (and its directory) if needed
+pub fn append(path: &Path, record: &Record) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+
+    writeln!(file, "{}", record.to_json().dump())?;
+
+    Ok(())
+}
+
+/// Loads all records from history file. Invalid lines are skipped
+pub fn load(path: &Path) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+    let history = std::fs::read_to_string(path)?;
+
+    Ok(
+        history.lines()
+            .filter_map(|line| json::parse(line).ok())
+            .filter_map(|obj| Record::from_json(&obj))
+            .collect()
+    )
+}
@@ -0,0 +1,120 @@
+//! # `binsize::image`
+//!
+//! Implements parsing of flashed firmware images (Intel HEX & raw binary). Images carry no
+//! symbols or sections, only occupied address ranges, which are turned into segments, so
+//! memory region usage can still be calculated
+//!
+
+use std::path::Path;
+use crate::exe::{ExecutableInfo, Segment, SegmentFlags};
+
+/// Extensions of Intel HEX files
+const HEX_EXTENSIONS: &[&str] = &["hex", "ihex", "ihx"];
+
+/// Extensions of raw binary images
+const BIN_EXTENSIONS: &[&str] = &["bin"];
+
+/// Kind of firmware image
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum ImageKind {
+    /// Intel HEX - records with addresses & data
+    Hex,
+
+    /// Raw binary - memory contents without addresses
+    Bin,
+}
+
+impl ImageKind {
+    /// Detects image kind by file extension. Returns `None` for other files (executables)
+    pub fn detect(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+
+        if HEX_EXTENSIONS.contains(&ext.as_str()) {
+            Some(ImageKind::Hex)
+        } else if BIN_EXTENSIONS.contains(&ext.as_str()) {
+            Some(ImageKind::Bin)
+        } else {
+            None
+        }
+    }
+}
+
+/// Parses firmware image at `path`. Raw binary has no addresses, so it's placed at `base`
+pub fn parse(path: &Path, kind: ImageKind, base: usize) -> Result<ExecutableInfo, Box<dyn std::error::Error>> {
+    let data = std::fs::read(path)?;
+
+    let ranges = match kind {
+        ImageKind::Hex => parse_hex(String::from_utf8_lossy(&data).as_ref())?,
+        ImageKind::Bin => vec![(base, data.len())],
+    };
+
+    let segments = ranges.into_iter()
+        .filter(|(_, size)| *size != 0)
+        .map(|(addr, size)| Segment {
+            kind:      "LOAD".to_string(),
+            load:      true,
+            addr,
+            phys_addr: addr,
+            size,
+            file_size: size,
+            flags:     SegmentFlags { read: true, write: false, execute: false },
+        })
+        .collect();
+
+    Ok(ExecutableInfo { segments, file_size: data.len(), ..Default::default() })
+}
+
+/// Parses Intel HEX records, returning occupied address ranges (start & size). Adjacent data
+/// records are merged into a single range
+fn parse_hex(text: &str) -> Result<Vec<(usize, usize)>, String> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+
+    // Upper part of address, set by extended segment/linear address records
+    let mut base = 0;
+
+    for (i, line) in text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        let line = line.trim();
+
+        let bytes = line.strip_prefix(':')
+            .filter(|record| record.len() % 2 == 0)
+            .and_then(|record| {
+                (0..record.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(&record[i..i + 2], 16).ok())
+                    .collect::<Option<Vec<_>>>()
+            })
+            .filter(|bytes| bytes.len() >= 5 && bytes.len() == bytes[0] as usize + 5)
+            .ok_or_else(|| format!("Invalid Intel HEX record on line {}", i + 1))?;
+
+        if bytes.iter().fold(0u8, |r, b| r.wrapping_add(*b)) != 0 {
+            return Err(format!("Invalid Intel HEX checksum on line {}", i + 1));
+        }
+
+        let len = bytes[0] as usize;
+        let offset = u16::from_be_bytes([bytes[1], bytes[2]]) as usize;
+        let payload = &bytes[4..4 + len];
+
+        match bytes[3] {
+            // Data
+            0x00 => {
+                let addr = base + offset;
+
+                match ranges.last_mut() {
+                    Some((start, size)) if *start + *size == addr => *size += len,
+                    _ => ranges.push((addr, len)),
+                }
+            }
+            // End of file
+            0x01 => break,
+            // Extended segment address
+            0x02 if len == 2 => base = (u16::from_be_bytes([payload[0], payload[1]]) as usize) << 4,
+            // Extended linear address
+            0x04 if len == 2 => base = (u16::from_be_bytes([payload[0], payload[1]]) as usize) << 16,
+            // Start addresses don't occupy memory
+            0x03 | 0x05 => {}
+            kind => return Err(format!("Unsupported Intel HEX record type {:02X} on line {}", kind, i + 1)),
+        }
+    }
+
+    Ok(ranges)
+}
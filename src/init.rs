@@ -0,0 +1,126 @@
+//! # `binsize::init`
+//!
+//! Implements `binsize init` command, which writes a commented starter config
+//!
+
+use std::path::{Path, PathBuf};
+
+/// Directories, that are searched for linker script with `MEMORY` declaration (relative to
+/// project root)
+const MEMORY_X_DIRS: &[&str] = &[".", "ld", "link", "linker", "boards"];
+
+/// Linker script names, that usually contain `MEMORY` declaration
+const MEMORY_X_NAMES: &[&str] = &["memory.x", "memory.ld"];
+
+/// Try to find a linker script with `MEMORY` declaration in usual places of an embedded project
+pub fn find_memory_x(root: &Path) -> Option<PathBuf> {
+    for dir in MEMORY_X_DIRS {
+        for name in MEMORY_X_NAMES {
+            let path = root.join(dir).join(name);
+
+            if path.is_file() {
+                // Strip "./" for prettiness
+                return Some(path.strip_prefix(root).unwrap_or(&path).to_path_buf());
+            }
+        }
+    }
+
+    None
+}
+
+/// Detect default profile: `release` if project's `Cargo.toml` customizes it (which usually
+/// means release builds are the ones that matter, e.g. on embedded), `dev` otherwise
+pub fn detect_profile(root: &Path) -> String {
+    let manifest = std::fs::read_to_string(root.join("Cargo.toml"))
+        .ok()
+        .and_then(|s| toml::from_str::<toml::Table>(s.as_str()).ok());
+
+    let has_release = manifest
+        .as_ref()
+        .and_then(|m| m.get("profile"))
+        .and_then(|p| p.get("release"))
+        .is_some();
+
+    if has_release { "release" } else { "dev" }.to_string()
+}
+
+/// Generates config contents. All options, except for `profile` & `ld-file`, are commented out,
+/// and set to their default values
+pub fn generate_config(profile: &str, ld_file: Option<&Path>) -> String {
+    let ld_file = match ld_file {
+        Some(path) => format!("ld-file = \"{}\"", path.display()),
+        None       => "# ld-file = \"memory.x\"".to_string(),
+    };
+
+    format!(
+r#"# binsize config, generated by `binsize init`
+# Command line arguments override values from this file
+
+[binsize]
+# Cargo profile to build the project with
+profile = "{profile}"
+
+# Linker script with MEMORY declaration, enables memory regions usage table
+{ld_file}
+
+# Colored output
+# color = true
+
+# Outputs (symbols, sections, segments, crates, regions, ram, all) with optional columns
+# output = ["symbols", "segments"]
+
+# Output formats: table, json, csv
+# format = ["table"]
+
+# Write machine output to stderr and tables to stdout
+# machine-stderr = false
+
+# Path to binary, skips `cargo build`
+# file = "target/release/app"
+
+# Filter symbol names (regex)
+# filter = "std"
+
+# Sort symbols by size (asc/desc), or by compound keys
+# sort = "desc"
+# sort-by = "size:desc,name:asc"
+
+# What symbol percentage is calculated against: symbols, filesize, section
+# percent-of = "symbols"
+
+# Max rows to output
+# max-rows = 0
+
+# How to shorten values, that don't fit: start, middle, end, wrap
+# truncate = "wrap"
+
+# Yellow & red thresholds for coloring
+# size-threshold = [200, 500]
+# percentage-threshold = [0.5, 1.0]
+"#
+    )
+}
+
+/// Writes starter config into `path`. Won't overwrite existing config, unless `force` is set
+pub fn write_config(path: &Path, force: bool) -> Result<String, Box<dyn std::error::Error>> {
+    if path.exists() && !force {
+        return Err(
+            format!("'{}' already exists, use --force to overwrite it", path.display()).into()
+        );
+    }
+
+    let root = Path::new(".");
+
+    let profile = detect_profile(root);
+    let ld_file = find_memory_x(root);
+
+    let config = generate_config(profile.as_str(), ld_file.as_deref());
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(path, &config)?;
+
+    Ok(config)
+}
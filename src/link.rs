@@ -7,7 +7,7 @@ use std::error::Error;
 use std::fmt::Display;
 use std::sync::OnceLock;
 use std::collections::HashMap;
-use crate::exe::Segment;
+use crate::exe::{Reservation, Segment};
 
 /// Compiled regex pattern for matching memory region declaration under MEMORY in LD scripts
 static MEM_REG_PATTERN: OnceLock<regex::Regex> = OnceLock::new();
@@ -20,7 +20,7 @@ static VARIABLE_PATTERN: OnceLock<regex::Regex> = OnceLock::new();
 ///
 /// Example:
 /// ```
-/// let mut exe = exe::parse(exe_path).unwrap();
+/// let mut exe = exe::parse(exe_path, None).unwrap();
 ///
 /// let mut regions = link::MemoryRegion::from_file(ld_path).unwrap();
 ///
@@ -57,6 +57,13 @@ impl MemoryRegion {
         (self.origin, self.origin + self.length)
     }
 
+    /// Returns `true` if `addr` lies inside of a region
+    pub fn contains(&self, addr: usize) -> bool {
+        let (start, end) = self.bounds();
+
+        start <= addr && addr < end
+    }
+
     /// Helper function to create a generic boxed error from a message
     fn create_error(str: &str) -> Box<dyn Error> {
         Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, str))
@@ -208,14 +215,46 @@ impl MemoryRegion {
 
     /// Uses program headers (LOAD segments) from parsed binary to enrich regions, parsed from
     /// linker script, with actual usage data
+    ///
+    /// Segment's memory size is counted against region, which contains its run address (VMA).
+    /// If segment is loaded from a different address (LMA), like initialized `.data`, that is
+    /// stored in FLASH and copied to RAM on startup, its file size is also counted against region,
+    /// which contains load address.
+    ///
+    /// Initialized part of thread-local storage (`.tdata`) is a part of loadable segment, but
+    /// zero-initialized part (`.tbss`) isn't, so it's counted separately from `TLS` segment
     pub fn use_segments_data(regions: &mut Vec<MemoryRegion>, segments: &Vec<Segment>) {
         for reg in regions.iter_mut() {
             let (start, end) = reg.bounds();
 
-            for seg in segments.iter() {
+            for seg in segments.iter().filter(|s| s.load) {
                 if start <= seg.addr && seg.addr <= end {
                     reg.used += seg.size;
                 }
+
+                if seg.phys_addr != seg.addr && start <= seg.phys_addr && seg.phys_addr <= end {
+                    reg.used += seg.file_size;
+                }
+            }
+
+            for seg in segments.iter().filter(|s| s.kind == "TLS") {
+                if start <= seg.addr && seg.addr <= end {
+                    reg.used += seg.size.saturating_sub(seg.file_size);
+                }
+            }
+
+            reg.used_percentage = reg.used as f32 / (reg.length as f32 / 100.0)
+        }
+    }
+
+    /// Adds stack & heap reservations to used space of regions, that contain them. Should be
+    /// called after `use_segments_data`, as reservations aren't a part of any segment
+    pub fn use_reservations(regions: &mut [MemoryRegion], reservations: &[Reservation]) {
+        for reg in regions.iter_mut() {
+            let (start, end) = reg.bounds();
+
+            for reservation in reservations.iter().filter(|r| start <= r.addr && r.addr < end) {
+                reg.used += reservation.size;
             }
 
             reg.used_percentage = reg.used as f32 / (reg.length as f32 / 100.0)
@@ -11,6 +11,9 @@
 //!
 //! Note: file, that is being analyzed, must have `.symtab` section, otherwise `binsize` won't
 //! be able to parse exported symbols. So don't strip your binaries, if you want this to work.
+//! If binary was stripped with debug info split into a separate file, `binsize` reads symbols from it,
+//! looking it up by `.gnu_debuglink` (next to the binary, in `.debug` subdirectory or under
+//! `/usr/lib/debug`) or by build-id (`/usr/lib/debug/.build-id/`).
 //!
 //! Note: this is only a prototype, bugs are expected.
 //!
@@ -49,6 +52,20 @@
 //! $ binsize --profile release
 //! ```
 //!
+//! Like in cargo, `--release`/`-r` is a shortcut for `--profile release` (`release = true` in
+//! config):
+//!
+//! ```rust,ignore
+//! $ binsize --release
+//! ```
+//!
+//! Options with values can also be given in `--key=value` form, like in other cargo tools (e.g.
+//! `--profile=release` or `-p=release`). For options with several values, the rest follow as usual:
+//!
+//! ```rust,ignore
+//! $ binsize --profile=release --size-threshold=500 1200
+//! ```
+//!
 //! If you want to skip building through cargo, or want to analyze some other binary, pass a path
 //! to said file using `--file`:
 //!
@@ -56,6 +73,49 @@
 //! $ binsize --file ~/projects/super-cool-project/target/release/super-cool-project
 //! ```
 //!
+//! Path can also be given without `--file`, as a positional argument:
+//!
+//! ```rust,ignore
+//! $ binsize target/release/super-cool-project
+//! ```
+//!
+//! Any other `cargo build` options (e.g. `--features`, `--locked`, `-Zbuild-std`) can be passed after `--`.
+//! They are appended to `cargo build` command as is, and replace `cargo-args` from config:
+//!
+//! ```rust,ignore
+//! $ binsize --profile release -- --features defmt --locked
+//! ```
+//!
+//! If build fails, compiler errors & warnings are printed to stderr (with headers colored by level,
+//! if color is enabled), followed by errors of cargo itself, so they never mix with size report.
+//!
+//! `binsize` needs a symbol table, so stripped binaries can't be analyzed. If binary turns out to be stripped,
+//! `binsize` explains why instead of printing empty tables: cargo profile is checked for `strip = true`
+//! (or `"symbols"`) and `debug = 0`, and `CARGO_PROFILE_*` environment overrides are suggested. Profile
+//! is resolved like cargo does it - from `CARGO_PROFILE_*` environment variables, `.cargo/config.toml`
+//! files and `Cargo.toml`, and such settings are also reported before building. With `--fix-profile`
+//! (or `--override-profile-settings`), these overrides are applied to the build automatically, without
+//! changing `Cargo.toml`:
+//!
+//! ```rust,ignore
+//! $ binsize --profile release --fix-profile
+//! ```
+//!
+//! If `cargo build` produces several artifacts (e.g. multiple binaries, or a binary and a `cdylib`),
+//! `binsize` analyzes the last binary (or dynamic library), and reports which other artifacts were
+//! available. Use `--artifact` to select one by name:
+//!
+//! ```rust,ignore
+//! $ binsize --artifact my-other-bin
+//! ```
+//!
+//! Universal (fat) Mach-O binaries contain a slice for each architecture. Slice of host architecture is
+//! analyzed by default, and available ones are listed. Use `--arch` to select another one:
+//!
+//! ```rust,ignore
+//! $ binsize --file target/universal/app --arch x86_64
+//! ```
+//!
 //! If you want to enable colored output, use `--color`/`-c` flag:
 //!
 //! ```rust,ignore
@@ -71,668 +131,5581 @@
 //! $ binsize --percentage-threshold 1.2 5.0 --size-threshold 500 1200
 //! ```
 //!
+//! Symbol names are printed in bold, with generic arguments dimmed, so the path stands out.
+//!
+//! Memory region usage (`segments` output with linker script) is colored the same way: yellow above
+//! 50% and red above 75% by default. Mature firmware often runs close to full flash on purpose, so
+//! these thresholds can be overridden using `--segment-threshold`:
+//!
+//! ```rust,ignore
+//! $ binsize --segment-threshold 90 95
+//! ```
+//!
+//! By default, `Percentage` column of symbols table is calculated against total size of all
+//! (filtered) symbols. Use `--percent-of` with `filesize` or `section` to calculate it against size
+//! of the whole file or size of the section, that contains the symbol:
+//!
+//! ```rust,ignore
+//! $ binsize --percent-of section
+//! ```
+//!
 //! If you want to sort symbols by size, use `--asc`/`-a` or `--desc`/`-d`:
 //!
 //! ```rust,ignore
 //! $ binsize --asc
 //! ```
 //!
+//! For more control over sorting use `--sort-by` with a comma separated list of `FIELD:asc|desc` keys.
+//! Symbols are sorted by the first key, and those which are equal by it - by the next one and so on.
+//! Possible fields are: `size`, `name`, `crate`, `kind`, `addr`.
+//! Sorting (both `--sort-by` and `--asc`/`--desc`, which sort by size) also applies to sections and program
+//! headers (segments) tables. Fields, that these tables don't have, are skipped (`kind` is segment type).
+//! Memory regions from linker script keep their order:
+//!
+//! ```rust,ignore
+//! $ binsize --sort-by size:desc,name:asc
+//! ```
+//!
+//! `--max-rows` is shared between all tables. To cap rows of a specific table, use `--limit KIND=ROWS`
+//! (`KIND` is one of `--output` values, can be repeated). It takes precedence over `--max-rows` for that table,
+//! and is applied after sorting & filtering. Cut rows are reported with `… N more rows` line, while
+//! totals below the table still account for all of them. In config, limits are given as a table:
+//!
+//! ```rust,ignore
+//! $ binsize --output symbols --output crates --limit symbols=50 --limit crates=20
+//! ```
+//!
+//! To keep reports short, small symbols can be rolled into a single `… N other symbols` row with `--collapse-below`.
+//! Threshold is either a size (`512`, `1K`, `2KiB`), or a percent of table total (`0.5%`). Crates table is
+//! collapsed the same way, into `… N other crates` row. Totals stay accurate, as collapsed sizes are summed up,
+//! and the row is printed below the table, so it's never cut by `--max-rows`/`--limit`:
+//!
+//! ```rust,ignore
+//! $ binsize --collapse-below 0.1% --output symbols --output crates
+//! ```
+//!
 //! If you want to specify what information you'd like to see - use `--output`/`-o`. Possible
-//! values are: `sym/symbols`, `sec/sections`, `seg/segments`, `cr/crates`, `*/all`. Columns
-//! for each output table can be specified using `OUTPUT=FIELDS` syntax (where `OUTPUT` is one
-//! of aforementioned values and `FIELDS` is a comma-separated list of columns).
+//! values are: `sym/symbols`, `sec/sections`, `seg/segments`, `cr/crates`, `reg/regions`,
+//! `ram`, `cat/categories`, `st/stats`, `imp/imports`, `exp/exports`, `lib/libraries`, `tls`,
+//! `dom/dominators`, `unr/unreachable`, `dbg/debug`, `z/compression`, `mem/members`,
+//! `rel/relocations`, `gen/generated`, `abi/c-abi`, `*/all`.
+//! Columns for each output table can be specified using `OUTPUT=FIELDS` syntax (where `OUTPUT` is
+//! one of aforementioned values and `FIELDS` is a comma-separated list of columns).
 //! For symbol table possible fields are: `*/all`, `s/size`, `%/p/percent`, `k/kind`, `c/crate`,
-//! `n/name`.
-//! For crate table possible fields are: `*/all`, `n/name`, `s/size`.
-//! For section table possible fields are: `*/all`, `n/name`, `a/addr`, `s/size`.
+//! `n/name`, `b/bar`, `l/lang`, `r/retained`, `d/derived`, `delta`.
+//! For crate table possible fields are: `*/all`, `n/name`, `s/size`, `b/bar`, `delta`, `c/count`,
+//! `a/avg`, `f/flash`, `r/ram`.
+//! For category table possible fields are: `*/all`, `n/name`, `s/size`, `%/p/percent`.
+//! For section table possible fields are: `*/all`, `n/name`, `a/addr`, `s/size`, `%/p/percent`,
+//! `fp/file-percent`, `c/coverage`, `o/offset`, `cat/categories`.
 //! For segment table possible fields are: `*/all`, `n/name`, `a/addr`, `u/used`, `s/size`,
-//! `%/p/percent`.
+//! `%/p/percent`, `t/type`, `fs/filesize`, `f/flags`.
+//! For library table possible fields are: `*/all`, `n/name`, `i/imports`, `s/stubs`.
 //! By default, only `symbols` are shown:
 //!
 //! ```rust,ignore
 //! $ binsize --output sections --output crates
 //! ```
 //!
+//! Symbol & crate tables have an optional `bar` column, which isn't shown by default. It renders a bar,
+//! proportional to the size of the largest symbol (crate) in the table, so relative sizes can be seen
+//! at a glance. Request it explicitly in column list, or use `all`:
+//!
+//! ```rust,ignore
+//! $ binsize --output symbols=size,percent,bar,name --output crates=all
+//! ```
+//!
+//! Crate table also shows how many symbols each crate contributes (`count`), and, optionally,
+//! their average size (`avg`). Many small symbols usually point at generic instantiations, while few
+//! large ones - at big functions or tables:
+//!
+//! ```rust,ignore
+//! $ binsize --output crates=name,size,count,avg
+//! ```
+//!
+//! For embedded budgeting, optional `flash` & `ram` columns of crate table split size of each
+//! crate by destination, using sections of its symbols: code & read-only data go to flash, writable
+//! data & bss - to RAM (initial values of writable data take space in flash too, but are only
+//! counted towards RAM):
+//!
+//! ```rust,ignore
+//! $ binsize --output crates=name,size,flash,ram
+//! ```
+//!
+//! Symbol table also has an optional `lang` column, which shows source language of a symbol, detected
+//! by demangler: `Rust`, `C++`, `C` (not mangled, but a valid identifier) or `other`. With it, size
+//! summary below the table is also split by language, which is handy for mixed Rust & C firmware:
+//!
+//! ```rust,ignore
+//! $ binsize --output symbols=size,lang,name
+//! ```
+//!
+//! To isolate size, contributed by code in some language (e.g. vendored C SDK vs Rust code), use
+//! `--lang` (`rust`, `cpp`, `c` or `other`). It can be repeated, and applies to all outputs:
+//!
+//! ```rust,ignore
+//! $ binsize --lang c --output symbols --output crates
+//! ```
+//!
 //! It is also possible to disallow a previously allowed output by using `!`:
 //!
 //! ```rust,ignore
 //! $ binsize --output !sections
 //! ```
 //!
-//! If you want to filter symbols by some pattern - use `-f`/`--filter`. Filters support regex:
+//! If you want to filter symbols by some pattern - use `-f`/`--filter`. Filters support regex, and can
+//! be repeated - then symbols matching any of them are shown:
 //!
 //! ```rust,ignore
 //! $ binsize --filter "core.+fmt"
+//! $ binsize --filter "core.+fmt" --filter "alloc::"
 //! ```
 //!
-//! For embedded projects, I really like GCC's --print-memory-usage linker flag, but using rust and
-//! cargo, I found it pretty hard to display the information about memory region usage (FLASH/RAM).
-//! So `binsize` provides a way to get that information, albeit not without user input. To get
-//! memory region usage, you must pass a path to linker script, which has a `MEMORY` declaration,
-//! like this:
+//! For richer filtering use `-w`/`--where` with an expression over symbol fields: `size`, `addr`,
+//! `retained`, `aliases` (numbers) and `name`, `crate`, `kind`, `lang`, `section` (strings).
+//! Expressions support arithmetic, comparison (`==`, `!=`, `<`, `<=`, `>`, `>=`), logical (`&&`, `||`,
+//! `!`) operators, regex match (`=~`) and parentheses. Strings are compared case-insensitively and can
+//! be given without quotes, if they aren't field names. Symbols have to pass both `--filter` and
+//! `--where`:
 //!
 //! ```rust,ignore
-//! MEMORY
-//! {
-//!   FLASH : ORIGIN = 0x8000000,  LENGTH = 64K
-//!   RAM   : ORIGIN = 0x20000000, LENGTH = 8K
-//! }
+//! $ binsize --where 'size > 1024 && crate == "serde" && kind == FUNC'
 //! ```
 //!
-//! The `--ld-memory-map`/`-l` is used to pass the path:
+//! If you want to process the results with other tools, use `--format`/`-F` with `json` or `csv`.
+//! Only enabled outputs (see `--output`) are included. `--format` can be repeated, to get both
+//! tables and machine-readable output at once - in this case machine output goes to stdout and
+//! tables go to stderr, so piping still shows tables in the terminal (use `--machine-stderr` to
+//! swap the streams):
 //!
 //! ```rust,ignore
-//! $ binsize --ld-memory-map boards/stm32l051/memory.x
+//! $ binsize --format json --format table | jq '.symbols[0]'
 //! ```
 //!
-//! After running this, you'll get a table at the very bottom of the output with columns:
-//! `Name`       - Name of memory region as defined in linker script
-//! `Address`    - Base of a region. Corresponds to ORIGIN in linker script
-//! `Used`       - How much of region is used, calculated using info from parsed program headers
-//! `Size`       - Full size of a region. Corresponds to LENGTH in linker script
-//! `Percentage` - Percentage of used against full size
+//! For very large binaries, `--format jsonl` writes one JSON object per line (symbol, crate, section,
+//! etc.) straight into the output stream, without building the whole report in memory. Each object
+//! has a `type` field (`symbol`, `crate`, `category`, `section` or `region`):
 //!
-//! Note: If ORIGIN or LENGTH contains a complex expression (arithmetics or reference to another
-//! segment), linker script parsing will fail, this is known limitation right now
+//! ```rust,ignore
+//! $ binsize --format jsonl | grep '"type":"symbol"' | jq -s 'map(.size) | add'
+//! ```
 //!
-//! ## Config
+//! To see size reports right on GitHub Actions run page, use `--format gh-summary`. It renders enabled
+//! outputs as Markdown (only the largest symbols are listed) and appends it to the file in
+//! `$GITHUB_STEP_SUMMARY` (outside of Actions it's printed like other machine output). Pass a JSON
+//! report, saved earlier with `--format json` (e.g. from the main branch), using `--baseline`, to get
+//! `Δ` columns with changes against it:
 //!
-//! `binsize` also support persistent configuration stored in `.cargo/binsize.toml`
-//! Here's an example of such config:
+//! ```rust,ignore
+//! $ binsize --format gh-summary --baseline main.json --output symbols --output crates
+//! ```
+//!
+//! For bespoke report formats, use `--template` with a path to template file. Template sees the same
+//! data as `--format json` (only enabled outputs), and supports a subset of Handlebars syntax: values
+//! (`{{stats.symbols}}`, `{{crates.0.name}}`), `{{#each}}` (with `@index`, `@first`, `@last`,
+//! `@key`), `{{#if}}` & `{{#unless}}` blocks with optional `{{else}}`, `{{! comments }}` and `~`
+//! whitespace control (`{{~#each crates~}}`). Values are not escaped. Template report is a machine
+//! output, so it replaces tables, unless `--format table` is also given:
 //!
 //! ```rust,ignore
-//! [binsize]
-//! color = true
-//! profile = "release"
-//! output = ["symbols", "segments"]
-//! file = "target/release/app"
-//! ld-file = "boards/stm32l051/memory.x"
-//! filter = "std"
-//! sort = "asc"
-//! size-threshold = [5000, 10000]
-//! percentage-threshold = [0.5, 1.0]
+//! $ binsize --template report.hbs --output crates --output stats
 //! ```
 //!
-//! Config loads automatically if `./.cargo/binsize.toml` is present. If you wish to skip config
-//! loading, use `-i`/`--ignore-config` cmdline option.
+//! Long values (mostly symbol names) that don't fit into terminal width are wrapped onto the next
+//! line by default. Use `--truncate` with `start`, `middle` or `end` to shorten them with an ellipsis
+//! instead (`wrap` restores the default):
 //!
-//! Note: command line arguments will override config values
+//! ```rust,ignore
+//! $ binsize --truncate middle
+//! ```
 //!
+//! Tables are fit into terminal width. If output isn't a terminal (e.g. in CI or when piped), `COLUMNS`
+//! environment variable is used, and 80 columns otherwise. Use `--width` to set width explicitly:
+//!
+//! ```rust,ignore
+//! $ binsize --width 200 > sizes.txt
+//! ```
+//!
+//! Large sizes are easier to read with digits grouped by three - use `--group-digits` (`1_234_567`),
+//! or `--group-digits=,` (`1,234,567`). `--precision` sets number of decimal places in percentages
+//! (2 by default, up to 16). Both only affect tables, machine-readable formats always have plain numbers:
+//!
+//! ```rust,ignore
+//! $ binsize --group-digits --precision 1
+//! ```
+//!
+//! Sizes can also be printed in hexadecimal with `--hex-sizes`, which is handy for comparing them
+//! against flash page/sector sizes from a datasheet, or `LENGTH` of memory regions in a linker script.
+//! Counts (e.g. number of symbols) are still decimal:
+//!
+//! ```rust,ignore
+//! $ binsize --hex-sizes --output crates --output segments
+//! ```
+//!
+//! Generic arguments can make names of monomorphized symbols very long. `--fold-generics` folds them
+//! into `<…>` in tables, so `hashbrown::raw::RawTable<(alloc::string::String, i64)>::reserve_rehash`
+//! is shown as `hashbrown::raw::RawTable<…>::reserve_rehash`. Machine-readable output keeps full names:
+//!
+//! ```rust,ignore
+//! $ binsize --fold-generics --output symbols
+//! ```
+//!
+//! Symbol names can be made terminal hyperlinks (OSC 8), clickable in modern terminals, with
+//! `--link-template`. `{name}`, `{crate}` and `{addr}` (hex) in the URL are replaced with values of each
+//! symbol, for example to search them on docs.rs. Like colors, links are only added with `--color`:
+//!
+//! ```rust,ignore
+//! $ binsize --link-template 'https://docs.rs/{crate}/latest/?search={name}'
+//! ```
+//!
+//! On bare-metal ARM targets interrupt vector table is a big chunk of data at the start of FLASH. It's
+//! detected by its section (`.vector_table`, `.isr_vector`, `.vectors`) or well-known symbols
+//! (`__INTERRUPTS`, `__EXCEPTIONS`, `g_pfnVectors`, etc.), its size and number of entries are printed
+//! above the symbol table, and its symbols are not listed, so they don't look like data bloat.
+//!
+//! For embedded projects RAM is usually as important as flash, so `--output ram` prints a static RAM
+//! report: writable sections (`.data`, `.bss`, TLS, etc.), RAM usage per crate and per symbol. Space,
+//! that isn't covered by symbols (stack/heap reservations, padding) is shown as `<unattributed>`:
+//!
+//! ```rust,ignore
+//! $ binsize --output ram
+//! ```
+//!
+//! Size of a symbol itself rarely tells the whole story - a small function can pull in a lot of
+//! code, that nothing else uses. `binsize` builds a reference graph from relocations, pointers in
+//! data and direct calls & address loads in code (x86, ARM Thumb & AArch64), and calculates
+//! retained size of each symbol - how much would go away along with it. It's shown in optional
+//! `retained` column of symbol tables, and `--output dominators` lists symbols sorted by retained
+//! size. Indirect calls can't be seen, so retained sizes are an estimate:
+//!
+//! ```rust,ignore
+//! $ binsize --output dominators
+//! $ binsize --output symbols=size,retained,name
+//! ```
+//!
+//! The same graph is used by `--output unreachable`, which lists symbols, that can't be reached from
+//! the entry point, exported symbols or sections, used by loader & runtime (`.init_array`, vector table
+//! and such), yet were kept in the binary. These are candidates for removal by `--gc-sections` (or by
+//! `strip`, for symbols kept only because they are exported). Symbols, used only through indirect
+//! calls or unusual addressing, can show up as false positives:
+//!
+//! ```rust,ignore
+//! $ binsize --output unreachable
+//! ```
+//!
+//! For custom analysis, the graph itself can be exported with `--graph PATH` - as GraphML, if path
+//! ends with `.graphml`, or as JSON otherwise. Nodes carry symbol name, crate, address, size, retained
+//! size and whether the node is a root, edges are references between nodes:
+//!
+//! ```rust,ignore
+//! $ binsize --graph target/binsize/graph.json
+//! $ binsize --graph target/binsize/graph.graphml
+//! ```
+//!
+//! Thread-local storage is allocated for every thread, so it's easy to overlook. `--output tls` prints
+//! the size of TLS block (initialized `.tdata` & zero-initialized `.tbss`) and thread-local symbols.
+//! Zero-initialized TLS isn't a part of any loadable segment, so it's added to region usage separately:
+//!
+//! ```rust,ignore
+//! $ binsize --output tls
+//! ```
+//!
+//! Stack & heap are often reserved by linker script without any section, so they are invisible in the
+//! binary, and RAM headroom looks bigger than it is. `binsize` recognizes common linker-provided
+//! symbols (`_stack_start`/`_stack_end`, `__StackTop`/`__StackLimit`, `__stack_size__`, `_heap_size`,
+//! `__HeapBase`/`__HeapLimit`, `_Min_Stack_Size` & co), adds reserved areas to memory region usage and
+//! shows them as `<stack>`/`<heap>` rows in `--output ram`.
+//!
+//! If only a flashed image is available, `--file` also accepts Intel HEX (`.hex`/`.ihex`) and raw binary
+//! (`.bin`) files. Images have no symbols or sections, so only memory region usage is reported, and
+//! linker script is required. HEX records carry addresses of occupied ranges, while raw binary is
+//! assumed to be flashed at the origin of the first region:
+//!
+//! ```rust,ignore
+//! $ binsize --file firmware.hex --ld-memory-map memory.x
+//! ```
+//!
+//! If linker map is available (e.g. `-C link-arg=-Wl,-Map=app.map`), pass it with `--map` to cross-check
+//! section sizes of the binary against it. Sizes from both sources and their difference are printed for
+//! each section, along with number & size of input sections, discarded by linker (`--gc-sections`), and
+//! fill bytes. Both GNU ld and LLD map formats are supported:
+//!
+//! ```rust,ignore
+//! $ binsize --map target/release/app.map
+//! ```
+//!
+//! To analyze several binaries at once (e.g. firmware variants for different boards), pass `--file`
+//! multiple times, or use `*`/`?` wildcards in file name. Each file gets a separate report, prefixed
+//! with file name. With `--compare`, a single table is printed instead, with file size, size of
+//! functions & data, their total, difference of total against the first file, and usage of each memory
+//! region (if linker script is given). Machine-readable output, budgets and `--record` still cover each
+//! file. To tell files apart, JSON reports are wrapped into an array, and each report, JSON Lines record
+//! and CSV row gets a `file` field (column) with path of the file:
+//!
+//! ```rust,ignore
+//! $ binsize --file 'target/thumbv6m-none-eabi/release/board-*' --compare
+//! ```
+//!
+//! In a workspace with several binaries, `--workspace` builds all members (`cargo build --workspace`)
+//! and prints a comparison table (as with `--compare`) of every produced binary. Build scripts are
+//! skipped. Add `--workspace-tables` to also print full tables for each binary before the summary:
+//!
+//! ```rust,ignore
+//! $ binsize --workspace --workspace-tables --output crates
+//! ```
+//!
+//! Symbols, that share the same address & size (aliases, `$local` duplicates, weak/strong pairs) are collapsed
+//! into one row, annotated with the number of aliases (`name (+2 aliases)`), so their size isn't counted
+//! several times. Alias names are listed in `aliases` field of JSON output. To list every symbol separately,
+//! use `--show-aliases`:
+//!
+//! ```rust,ignore
+//! $ binsize --show-aliases
+//! ```
+//!
+//! To focus on a single section, use `--section` (can be repeated). Only symbols from given sections are
+//! analyzed, so all symbol-based tables (symbols, crates, categories, etc.) are restricted to them, e.g.
+//! `--section .text` for code-only analysis, or `--section .bss` when hunting for RAM:
+//!
+//! ```rust,ignore
+//! $ binsize --section .data --section .bss
+//! ```
+//!
+//! Sections table can be followed by a summary of section categories - code, ro-data, rw-data, bss, debug
+//! and metadata/other (symbol tables, notes, dynamic linking info) - with their total sizes and
+//! percentages of file size (bss takes no space in the file, so it has no percentage). It's enabled with
+//! optional `categories` field of sections output:
+//!
+//! ```rust,ignore
+//! $ binsize --output sections=name,size,categories
+//! ```
+//!
+//! Symbol-based tables miss space, that no symbol accounts for - padding, alignment, literal pools, stripped
+//! local symbols. Sections table has an optional `coverage` column, showing how much of each code/data section is
+//! covered by known symbols. When it's enabled, the largest unattributed address ranges are listed after the table:
+//!
+//! ```rust,ignore
+//! $ binsize --output sections=name,size,coverage
+//! ```
+//!
+//! Another optional column of sections table is `offset` - file offset of section contents (`-` for
+//! sections, that take no space in the file, like `.bss`), which is handy along with `hexdump` or
+//! `objcopy`:
+//!
+//! ```rust,ignore
+//! $ binsize --output sections=name,addr,offset,size
+//! ```
+//!
+//! Sections table also shows `percent` - share of each code/data section in total code & data size
+//! (debug info & metadata sections have none, as they would dwarf everything else). Optional
+//! `file-percent` column shows share of file size instead (`-` for `.bss`-like sections):
+//!
+//! ```rust,ignore
+//! $ binsize --output sections=name,size,percent,file-percent
+//! ```
+//!
+//! In debug builds `.debug_*` sections take up most of the file, and dominate sections table &
+//! file-size-based percentages. `--exclude-section` (regex, can be repeated) leaves matching sections
+//! out of sections table, section categories, and file size, that percentages are calculated against:
+//!
+//! ```rust,ignore
+//! $ binsize --exclude-section '^\.debug' --output sections=name,size,file-percent
+//! ```
+//!
+//! Debug info often dominates file size, which is confusing when comparing binaries. `--output debug`
+//! lists `.debug_*` sections with their sizes, and shows how much `strip --strip-debug` (and full
+//! `strip`, which also removes symbol tables) would save. Debug info isn't loaded, so flashed size
+//! doesn't depend on it:
+//!
+//! ```rust,ignore
+//! $ binsize --output debug
+//! ```
+//!
+//! To answer release-size questions from a debug build, `--estimate-stripped` prints what the file size
+//! would be after `strip --strip-debug` and after full `strip`, by excluding sizes of debug info, symbol
+//! tables and other non-loaded sections. The file is not modified, and the estimate is approximate, as
+//! section headers & alignment are not accounted for:
+//!
+//! ```rust,ignore
+//! $ binsize --estimate-stripped
+//! ```
+//!
+//! Firmware updates and wasm modules are usually shipped compressed, so `--output compression` shows
+//! compressed size & ratio (compressed size in percent of original) of each loaded section with gzip
+//! and zstd (level 1), and of all loaded sections together (`<image>`), which approximates OTA/update
+//! payload size:
+//!
+//! ```rust,ignore
+//! $ binsize --output compression
+//! ```
+//!
+//! Position independent executables & shared libraries pay for every pointer in data with a dynamic
+//! relocation, which takes space in file (`.rela.dyn`) and time at load. `--output relocations` shows
+//! number of dynamic relocations (relative ones, that only add load address, and symbolic ones), sizes
+//! of relocation sections & `.data.rel.ro`, and which crates' symbols contain patched places (ELF only):
+//!
+//! ```rust,ignore
+//! $ binsize --output relocations
+//! ```
+//!
+//! When analyzing an archive (`.rlib` or `.a`), a table of its object files (codegen units and
+//! bundled C objects) is printed after symbols, with code & data sizes and number of defined symbols
+//! of each, so it's easy to see which one dominates. Sections of object files have no addresses, so
+//! they are laid out one after another. Use `--output members` to print only this table:
+//!
+//! ```rust,ignore
+//! $ binsize --file target/release/deps/libmylib-0123456789abcdef.rlib --output members
+//! ```
+//!
+//! For a compact overview use `--output stats`. It prints number of symbols (total, functions & data), total,
+//! mean, median & max symbol size, number of crates and file size. Symbols are filtered by `--filter`. In
+//! machine-readable formats stats are a single `stats` object (or a `stats` line in JSON Lines), which is easy
+//! to grab for dashboards:
+//!
+//! ```rust,ignore
+//! $ binsize --output stats --format json
+//! ```
+//!
+//! For shared libraries (and executables, that export symbols), `--output exports` lists symbols from dynamic
+//! symbol table, that are exported, with their sizes, so public ABI surface and its cost can be audited.
+//! `--output imports` lists imported symbols, grouped by library, that provides them (if file records it):
+//!
+//! ```rust,ignore
+//! $ binsize --file target/release/libmylib.so --output exports --output imports
+//! ```
+//!
+//! To see what an executable needs at run time, use `--output libraries`. It lists shared libraries
+//! from `DT_NEEDED` entries (or Mach-O load commands / PE import table) with the number of symbols
+//! imported from each. Optional `stubs` column estimates the size of PLT/stub code, spent on calling
+//! those imports. PE & Mach-O record the library of each import, while ELF only does it through symbol
+//! versions - imports without them are counted under `?`, and libraries, that have no versioned imports,
+//! show `-` instead of numbers:
+//!
+//! ```rust,ignore
+//! $ binsize --output libraries=all
+//! ```
+//!
+//! Not sure where to start? `--suggest` inspects the analysis and prints actionable hints: large share
+//! of formatting machinery (`core::fmt`), generic functions with many instantiations, big unwinding
+//! tables or debug info, along with the usual ways to get rid of them:
+//!
+//! ```rust,ignore
+//! $ binsize --suggest
+//! ```
+//!
+//! For a one-glance answer to "where do the bytes go", use `--output categories`. It splits total size
+//! of symbols into `local` (crates of current workspace), `dependencies`, `std` (`std`, `core`, `alloc`,
+//! `compiler_builtins` & co) and `unknown` (symbols without a crate name). Local crates and
+//! dependencies are told apart using `cargo metadata`, so outside of cargo project only `std` is
+//! recognized:
+//!
+//! ```rust,ignore
+//! $ binsize --output categories
+//! ```
+//!
+//! A good share of Rust binaries is code, that compiler generates, rather than code, written by hand.
+//! `--output generated` splits shown symbols into drop glue (`drop_in_place`), vtable shims, `fmt`
+//! trait impls, iterator adapters, closures and other code, with number of symbols & total size of
+//! each. Generic arguments don't affect the kind, so `drop_in_place<{{closure}}>` is drop glue:
+//!
+//! ```rust,ignore
+//! $ binsize --output generated
+//! ```
+//!
+//! When building a `cdylib` (or a static library for C), it's worth auditing, what is actually
+//! exposed. `--output c-abi` lists `#[no_mangle]`/`extern "C"` symbols, defined in the binary with
+//! global binding, along with their sizes, and marks ones, that are present in dynamic symbol table:
+//!
+//! ```rust,ignore
+//! $ binsize --output c-abi --file target/release/libfoo.so
+//! ```
+//!
+//! Standard library is usually noise, when hunting for bloat in your application. Use `--exclude-std` to
+//! drop symbols of `std`, `core`, `alloc`, `compiler_builtins` & other toolchain crates right after
+//! parsing. They won't show up in any table, and percentages are calculated without them:
+//!
+//! ```rust,ignore
+//! $ binsize --exclude-std --output crates
+//! ```
+//!
+//! To track how size changes over time, pass `--record`. Totals of the run (total size of symbols, size
+//! of each crate and section, and used space of memory regions, if linker script is given) are
+//! appended along with a timestamp and current git commit to `target/binsize/history.jsonl` (can be
+//! changed with `--history-file`). Recorded runs can be viewed with `binsize history`, which prints a
+//! table with the difference between consecutive runs, or an ASCII chart of total size with `--chart`
+//! (`-n` limits output to last N runs). When multiple files are analyzed, each gets its own record with
+//! `file` field, and difference is calculated between records of the same file:
+//!
+//! ```rust,ignore
+//! $ binsize --record
+//! $ binsize history --chart -n 20
+//! ```
+//!
+//! To find out what a cargo feature costs, use `--feature-impact FEATURE`. Project is built twice -
+//! without the feature (as configured) and with it, and the difference is reported: total size, crates,
+//! that changed, and changed symbols, biggest changes first (symbols are matched by name, ignoring
+//! hashes). If the feature is a default one, build without it uses `--no-default-features` with the rest
+//! of default features. `--filter`, `--where` & `--limit` apply to these tables too:
+//!
+//! ```rust,ignore
+//! $ binsize --feature-impact serde --limit symbols=30
+//! ```
+//!
+//! To quantify what LTO would save for your project, use `--lto-impact`. Project is built with
+//! `lto = false` and with `lto = "fat"` (overriding it for selected profile through
+//! `CARGO_PROFILE_*_LTO`), and the difference is reported the same way, as with `--feature-impact`:
+//!
+//! ```rust,ignore
+//! $ binsize --release --lto-impact
+//! ```
+//!
+//! To see what `panic = "abort"` would save, use `--panic-impact`. Project is built with
+//! `panic = "unwind"` and with `panic = "abort"` (overriding it through `CARGO_PROFILE_*_PANIC`), and
+//! changes of file size, total size of symbols, unwinding tables & panic machinery (`core::panicking`)
+//! are summarized, followed by the usual breakdown by crates & symbols:
+//!
+//! ```rust,ignore
+//! $ binsize --release --panic-impact
+//! ```
+//!
+//! To choose `opt-level` without guesswork, use `--opt-matrix`. Project is built with each opt-level
+//! (`0`, `s`, `z` & `3` by default, or a comma-separated list with `--opt-matrix=LEVELS`), overriding
+//! it for selected profile through `CARGO_PROFILE_*_OPT_LEVEL`, and a matrix of text (code & read-only
+//! data), data & bss sizes is printed, like `size` does. Smallest total is highlighted:
+//!
+//! ```rust,ignore
+//! $ binsize --release --opt-matrix
+//! $ binsize --release --opt-matrix=2,s,z
+//! ```
+//!
+//! To see which dependencies pull in the most code, use `binsize tree`. It prints cargo dependency tree
+//! (normal dependencies only), annotated with size of each crate and cumulative size of its subtree (a
+//! crate reachable by several paths is counted once). Dependencies are ordered by cumulative size, and
+//! heavy branches are colored & highlighted. Tree starts from the package of analyzed artifact, and
+//! takes the same options as `binsize` itself:
+//!
+//! ```rust,ignore
+//! $ binsize tree --release
+//! ```
+//!
+//! Totals of each symbol & crate are saved after every run into `target/binsize/last.json`, and the
+//! next run shows a `Δ` column in symbol & crate tables with the change since then (new symbols are
+//! counted from zero). Pass `--no-delta` (or `delta = false` in config) to hide it - such run doesn't
+//! save its totals either.
+//! Symbols are matched by demangled name with rustc hashes stripped & generic arguments normalized
+//! (`--baseline` matches them the same way), so a rebuild with changed hashes doesn't show every
+//! symbol as new:
+//!
+//! ```rust,ignore
+//! $ binsize --no-delta
+//! ```
+//!
+//! When run from a cargo project (where `target` directory exists), parsed & demangled binaries are
+//! cached in `target/binsize/cache`, keyed by path, size and modification time of the binary. So
+//! re-running `binsize` with different filters or outputs doesn't parse the whole file again. Use
+//! `--no-cache` to skip the cache:
+//!
+//! ```rust,ignore
+//! $ binsize --no-cache
+//! ```
+//!
+//! Even with nothing to rebuild, `cargo build` takes a moment. To make repeated analysis instant,
+//! use `--no-build` - it skips the build and analyzes artifacts of the last one (the list is saved
+//! to `target/binsize/artifacts.json` along with build options, and is only reused with the same
+//! options, falling back to the most recent binary in profile's target directory):
+//!
+//! ```rust,ignore
+//! $ binsize --no-build --output crates
+//! ```
+//!
+//! If target directory is relocated (shared CI caches, build farms), pass it with `--target-dir`
+//! (`target-dir` in config). It's forwarded to cargo, and used to locate artifacts with `--no-build`.
+//! `binsize` keeps its own files (cache, totals of the last run, history, list of artifacts) in
+//! `binsize` subdirectory of it too. Relative path is resolved against current directory (in config -
+//! against project root):
+//!
+//! ```rust,ignore
+//! $ binsize --release --target-dir /var/cache/ci/target
+//! ```
+//!
+//! To control build parallelism (e.g. on CI runners with limited memory), pass `--jobs`/`-j`
+//! (`jobs` in config). It's forwarded to cargo as is, so negative values are relative to the number
+//! of CPUs:
+//!
+//! ```rust,ignore
+//! $ binsize --release -j 2
+//! ```
+//!
+//! Embedded projects often rebuild `core`/`alloc` from source, which changes what ends up in the
+//! binary. `--build-std` passes `-Z build-std` to cargo (requires nightly and `--target`),
+//! optionally with a list of crates (`--build-std=core,alloc`). In config, `build-std` is either
+//! `true` or a list of crates:
+//!
+//! ```rust,ignore
+//! $ binsize --release --build-std=core,alloc -- --target thumbv6m-none-eabi
+//! ```
+//!
+//! For embedded projects, I really like GCC's --print-memory-usage linker flag, but using rust and
+//! cargo, I found it pretty hard to display the information about memory region usage (FLASH/RAM).
+//! So `binsize` provides a way to get that information, albeit not without user input. To get
+//! memory region usage, you must pass a path to linker script, which has a `MEMORY` declaration,
+//! like this:
+//!
+//! ```rust,ignore
+//! MEMORY
+//! {
+//!   FLASH : ORIGIN = 0x8000000,  LENGTH = 64K
+//!   RAM   : ORIGIN = 0x20000000, LENGTH = 8K
+//! }
+//! ```
+//!
+//! The `--ld-memory-map`/`-l` is used to pass the path:
+//!
+//! ```rust,ignore
+//! $ binsize --ld-memory-map boards/stm32l051/memory.x
+//! ```
+//!
+//! After running this, you'll get a table at the very bottom of the output with columns:
+//! `Name`       - Name of memory region as defined in linker script
+//! `Address`    - Base of a region. Corresponds to ORIGIN in linker script
+//! `Used`       - How much of region is used, calculated using info from parsed program headers
+//! `Size`       - Full size of a region. Corresponds to LENGTH in linker script
+//! `Percentage` - Percentage of used against full size
+//!
+//! Segments, that are loaded from one address and run from another (e.g. initialized `.data`, that is
+//! stored in FLASH and copied to RAM on startup), are counted against both regions: memory size against
+//! region with run address (VMA), and file size against region with load address (LMA).
+//!
+//! If `--output segments` is requested without a linker script, `binsize` prints a best-effort memory
+//! usage estimate from program headers instead: non-writable segments (and initialized part of writable
+//! ones) are counted as `flash-like`, and writable segments as `ram-like`. Region sizes are unknown in
+//! this case, so only used space and address ranges are shown. The estimate is preceded by a raw table
+//! of program headers, with their type, address, file size, memory size and `RWX` flags.
+//!
+//! To see which symbols fill each memory region, use `--output regions` along with `--ld-memory-map`.
+//! For each region a separate symbol table is printed, with symbols placed inside of region's bounds.
+//! Percentage in these tables is calculated against region size. Columns are the same as in symbol table:
+//!
+//! ```rust,ignore
+//! $ binsize --ld-memory-map memory.x --output regions=size,percent,name
+//! ```
+//!
+//! Note: If ORIGIN or LENGTH contains a complex expression (arithmetics or reference to another
+//! segment), linker script parsing will fail, this is known limitation right now
+//!
+//! ## Config
+//!
+//! `binsize` also support persistent configuration stored in `.cargo/binsize.toml`
+//! Here's an example of such config:
+//!
+//! ```rust,ignore
+//! [binsize]
+//! color = true
+//! profile = "release"
+//! output = ["symbols", "segments"]
+//! file = "target/release/app"
+//! ld-file = "boards/stm32l051/memory.x"
+//! filter = "std"
+//! sort = "asc"
+//! size-threshold = [5000, 10000]
+//! percentage-threshold = [0.5, 1.0]
+//! format = ["table", "json"]
+//! machine-stderr = false
+//! truncate = "middle"
+//! sort-by = "size:desc,name:asc"
+//! percent-of = "symbols"
+//! compare = false
+//! exclude-std = false
+//! baseline = "main.json"
+//! record = false
+//! history-file = "target/binsize/history.jsonl"
+//! cache = true
+//! artifact = "app"
+//! workspace = false
+//! workspace-tables = false
+//! cargo-args = ["--locked"]
+//! fix-profile = false
+//! show-aliases = false
+//! section = [".text"]
+//! collapse-below = "1K"
+//! limit = { symbols = 50, crates = 20 }
+//! map-file = "target/release/app.map"
+//! lang = ["rust"]
+//! suggest = false
+//! graph-file = "target/binsize/graph.json"
+//! estimate-stripped = false
+//! where = "size > 1024 && kind == FUNC"
+//! segment-threshold = [90.0, 95.0]
+//! no-build = false
+//! link-template = "https://docs.rs/{crate}/latest/?search={name}"
+//! width = 200
+//! arch = "arm64"
+//! target-dir = "target"
+//! build-std = ["core", "alloc"]
+//! jobs = 4
+//! delta = true
+//! template = "report.hbs"
+//! group-digits = "_"
+//! precision = 1
+//! hex-sizes = true
+//! fold-generics = true
+//! exclude-section = ["^\\.debug"]
+//! ```
+//!
+//! Config loads automatically if `.cargo/binsize.toml` is present in project root. Like cargo, `binsize`
+//! can be run from any subdirectory of a project: root is found by walking up to the nearest `Cargo.toml`
+//! (or to workspace `Cargo.toml`, if there is one), and config, as well as relative paths in it, are
+//! resolved against it. Paths passed on command line stay relative to current directory. If you wish
+//! to skip config loading, use `-i`/`--ignore-config` cmdline option.
+//!
+//! Settings, shared by all projects (like `color` or `theme`), can be put into user config at
+//! `$XDG_CONFIG_HOME/binsize/config.toml` (`~/.config/binsize/config.toml` by default on Linux,
+//! `~/Library/Application Support/binsize/config.toml` on macOS, `%APPDATA%\binsize\config.toml` on
+//! Windows). It's loaded first, so project config overrides it. Lists (like `output`, `format` and
+//! `file`) are replaced, instead of being merged.
+//!
+//! To load another config file instead (e.g. a separate one for each CI job), pass its path with
+//! `--config` or in `BINSIZE_CONFIG` environment variable (command line takes precedence):
+//!
+//! ```rust,ignore
+//! $ binsize --config ci/binsize-size-check.toml
+//! ```
+//!
+//! To get a starter config, run `binsize init` in project root. It writes a commented
+//! `.cargo/binsize.toml` with all options set to their defaults. If `memory.x` (or `memory.ld`) is found
+//! in project root, `ld`, `link`, `linker` or `boards` directories, it is used as `ld-file`, and if
+//! `Cargo.toml` customizes release profile, `profile` is set to `release`. Existing config is not
+//! overwritten, unless `--force` is passed:
+//!
+//! ```rust,ignore
+//! $ binsize init
+//! ```
+//!
+//! Size budgets can be set in `[binsize.budget]` config section. `total` limits total size of all
+//! symbols, `crate.NAME`, `section.NAME` & `region.NAME` limit size of a crate, size of a section and
+//! used space of a memory region (requires linker script) respectively. Limits are either a number of
+//! bytes, or a string with `B`, `K`/`KiB`, `M`/`MiB` (powers of 1024) or `KB`/`MB` (powers of 1000)
+//! suffix. Region limits can also be given in percent of region size. After analysis, `binsize`
+//! prints a table with the result of each budget, and exits with code 1, if any of them failed:
+//!
+//! ```rust,ignore
+//! [binsize.budget]
+//! total = "1MiB"
+//! crate.serde = "150KiB"
+//! section.".rodata" = "64K"
+//! region.FLASH = "95%"
+//! ```
+//!
+//! Symbol tables can be extended with derived columns, defined in `[binsize.columns]` config section.
+//! Each column is an arithmetic expression (`+`, `-`, `*`, `/`, `%`, parentheses) over symbol fields:
+//! `size`, `addr`, `percent`, `retained` & `aliases` (number of collapsed aliases). Columns are placed
+//! before symbol name in order of their names, and can be hidden along with other columns by leaving
+//! out `d/derived` field:
+//!
+//! ```rust,ignore
+//! [binsize.columns]
+//! size_kib = "size / 1024"
+//! retained_ratio = "retained / size"
+//! ```
+//!
+//! Default columns of each table (used when output is enabled without a field list) can be set
+//! in `[binsize.fields]` config section, using the same field names, as `--output OUTPUT=FIELDS`.
+//! Fields are given as an array, or as a comma-separated string:
+//!
+//! ```rust,ignore
+//! [binsize.fields]
+//! symbols = ["size", "percent", "name"]
+//! sections = "name,addr,size,offset"
+//! ```
+//!
+//! Colored output can be customized in `[binsize.theme]` config section. `stripe` sets attributes of
+//! every second table row (zebra striping), and `highlight` - attributes of rows, that exceed red size
+//! or percentage threshold, or a budget. Attributes are text modifications (`bold`, `dim`, `italic`,
+//! `underline`, `inverse`, ...), foreground colors (`black`, `red`, `green`, `yellow`, `blue`,
+//! `magenta`, `cyan`, `white`, `default`, or true color `#rrggbb`) and background colors (same colors
+//! with `bg-` prefix). `heat` takes a pair of true colors: `Size` & `Percentage` columns are colored
+//! with a gradient between them (reaching the second one at red threshold), instead of green/yellow/red:
+//!
+//! ```rust,ignore
+//! [binsize.theme]
+//! stripe = "bg-black"
+//! highlight = ["bold", "bg-red"]
+//! heat = ["#00c000", "#c00000"]
+//! ```
+//!
+//! Note: command line arguments will override config values
+//!
+
+use std::collections::{HashMap, HashSet};
+use crate::util::{SortKey, SortOrder, Stream, hprintln};
+use crate::cargo::{BuildArtifact, BuildOptions};
+use crate::table::{Row, Table, Truncate};
+use crate::exe::{ExecutableInfo, Symbol, SymbolKind};
+use crate::demangle::{DemangledSymbolKind, GeneratedKind};
+use crate::attr_str::{Attribute, AttributeString};
+use crate::format::{Baseline, MachineReport, OutputFormat};
+use crate::output::{
+    Output,
+    OutputKind,
+    PercentBase,
+    SymbolTableFields,
+    CrateTableFields,
+    SectionTableFields,
+    SegmentTableFields,
+    CategoryTableFields,
+    LibraryTableFields
+};
+
+mod cargo;
+mod exe;
+mod args;
+mod table;
+mod util;
+mod attr_str;
+mod link;
+mod output;
+mod demangle;
+mod format;
+mod init;
+mod budget;
+mod history;
+mod delta;
+mod cache;
+mod image;
+mod map;
+mod suggest;
+mod graph;
+mod compress;
+mod expr;
+mod template;
+
+/// `binsize` version
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// `binsize` config file location
+const CONFIG: &str = ".cargo/binsize.toml";
+
+/// Environment variable, that overrides config file location
+const CONFIG_ENV: &str = "BINSIZE_CONFIG";
+
+/// Width of `Bar` column in terminal columns
+const BAR_WIDTH: usize = 20;
+
+/// How many of the largest unattributed ranges are listed after sections table with `Coverage`
+/// column enabled
+const MAX_UNATTRIBUTED_RANGES: usize = 10;
+
+/// Opt-levels, that `--opt-matrix` builds with, if none were given
+const DEFAULT_OPT_LEVELS: [&str; 4] = ["0", "s", "z", "3"];
+
+/// Share of total size in percent, after which a branch of dependency tree is colored yellow
+const TREE_BRANCH_YELLOW: f32 = 10.0;
+
+/// Share of total size in percent, after which a branch of dependency tree is colored red
+/// and highlighted
+const TREE_BRANCH_RED: f32 = 25.0;
+
+/// Separator of digit groups, if `--group-digits` is given without one
+const DEFAULT_DIGIT_SEPARATOR: char = '_';
+
+/// Max number of decimal places in percentages (`--precision`), `f64` isn't precise past it
+const MAX_PRECISION: usize = 16;
+
+
+/// Helper function for applying styling to column headers
+fn attr_apply_bold(s: &mut AttributeString) {
+    s.push_attr(Attribute::TextBold);
+}
+
+/// Parses separator of digit groups. Only separators, that tables still recognize as parts of
+/// numbers (for alignment), are allowed
+fn parse_digit_separator(value: &str) -> Result<char, String> {
+    match value {
+        "_" => Ok('_'),
+        "," => Ok(','),
+        _   => Err(format!("Unknown digit separator: '{}' (possible values: _, ,)", value)),
+    }
+}
+
+/// Joins several regexes into alternation, which matches, if any of them matches
+fn any_regex<'a>(patterns: impl Iterator<Item = &'a str>) -> regex::Regex {
+    regex::Regex::new(
+        patterns
+            .map(|p| format!("(?:{})", p))
+            .collect::<Vec<_>>()
+            .join("|")
+            .as_str()
+    ).unwrap()
+}
+
+/// `binsize` Application
+struct Binsize {
+    /// Cargo build options
+    build_options: BuildOptions,
+
+    /// If `true` - `cargo build` is skipped, and artifacts of the last build are analyzed
+    no_build: bool,
+
+    /// Filter for symbol names
+    filter: regex::Regex,
+
+    /// Filter expression for symbols (`--where`)
+    where_expr: Option<expr::Expr>,
+
+    /// Linker script path with `MEMORY` declaration
+    ld_file: String,
+
+    /// Linker map path. If set - section sizes are cross-checked against it
+    map_file: String,
+
+    /// Path to export symbol reference graph to (JSON, or GraphML for `.graphml`)
+    graph_file: String,
+
+    /// Files to parse (if empty - will try to extract file from `cargo build`). Each file gets
+    /// a separate report, unless `compare` is set
+    files: Vec<String>,
+
+    /// Name of cargo artifact (binary/library) to analyze, if build produces several
+    artifact: String,
+
+    /// Architecture of universal (fat) Mach-O binary slice to analyze. If `None` - host one
+    arch: Option<String>,
+
+    /// If `true` - print a single comparison table for all `files`, instead of separate reports
+    compare: bool,
+
+    /// If `true` - whole workspace is built, and every binary is analyzed
+    workspace: bool,
+
+    /// If `true` - cargo profile settings, that strip symbols, are overridden for the build
+    /// via `CARGO_PROFILE_*` environment variables
+    fix_profile: bool,
+
+    /// If `true` - with `workspace`, full tables are printed for each binary before the summary
+    workspace_tables: bool,
+
+    /// Colorful output toggle
+    color: bool,
+
+    /// Attributes of every second row of tables (`stripe` in `[binsize.theme]`)
+    theme_stripe: Vec<Attribute>,
+
+    /// Attributes of rows, that exceed a red threshold or a budget (`highlight` in
+    /// `[binsize.theme]`)
+    theme_highlight: Vec<Attribute>,
+
+    /// Two true colors (`heat` in `[binsize.theme]`). If set, `Size` & `Percentage` columns are
+    /// colored with a gradient between them, instead of green/yellow/red
+    theme_heat: Vec<Attribute>,
+
+    /// Max rows to output in tables. 0 - no limit
+    max_rows: usize,
+
+    /// Width of tables in columns. 0 - width of terminal (or `COLUMNS`, if output isn't one)
+    width: usize,
+
+    /// Separator of digit groups in table numbers (`--group-digits`). `None` - not grouped
+    group_digits: Option<char>,
+
+    /// Number of decimal places in table percentages
+    precision: usize,
+
+    /// If `true` - sizes in tables are printed in hexadecimal
+    hex_sizes: bool,
+
+    /// If `true` - generic arguments of symbol names in tables are folded into `<…>`
+    fold_generics: bool,
+
+    /// Max rows to output in tables of specific output kind. Take precedence over `max_rows`
+    limits: HashMap<OutputKind, usize>,
+
+    /// Symbols (and crates) smaller than this are rolled into a single "other" row. Percent is
+    /// taken of the table total
+    collapse_below: Option<budget::Limit>,
+
+    /// How to shorten values, that don't fit into terminal width
+    truncate: Truncate,
+
+    /// What `Percentage` column of symbols table is calculated against
+    percent_base: PercentBase,
+
+    /// Sorting order of symbols
+    symbols_sorting_order: Option<SortOrder>,
+
+    /// Compound sorting keys for symbols. Take precedence over `symbols_sorting_order`
+    sort_keys: Vec<SortKey>,
+
+    /// Threshold in percent of total size for symbol to be colored yellow
+    percentage_threshold_yellow: f32,
+
+    /// Threshold in percent of total size for symbol to be colored red
+    percentage_threshold_red: f32,
+
+    /// Threshold in percent of memory region usage for region to be colored yellow
+    segment_threshold_yellow: f32,
+
+    /// Threshold in percent of memory region usage for region to be colored red
+    segment_threshold_red: f32,
+
+    /// Threshold in bytes for symbol to be colored yellow
+    size_threshold_yellow: usize,
+
+    /// Threshold in bytes for symbol to be colored red
+    size_threshold_red: usize,
+
+    /// If `true` - symbols of `std`, `core`, `alloc` & other toolchain crates are dropped right
+    /// after parsing, so they don't show up in any table, and don't count towards percentages
+    exclude_std: bool,
+
+    /// Sections to restrict symbols to. If empty - symbols from all sections are analyzed
+    sections: Vec<String>,
+
+    /// Sections, which names match this regex, are left out of sections table & file size, that
+    /// percentages are calculated against
+    exclude_sections: Option<regex::Regex>,
+
+    /// If `true` - size-reduction suggestions are printed after tables
+    suggest: bool,
+
+    /// If `true` - estimated file sizes after stripping are printed after tables
+    estimate_stripped: bool,
+
+    /// Languages to restrict symbols to. If empty - symbols of all languages are analyzed
+    langs: Vec<DemangledSymbolKind>,
+
+    /// If `true` - symbols with the same address & size are listed separately, instead of being
+    /// collapsed into one row with an aliases annotation
+    show_aliases: bool,
+
+    /// URL pattern for terminal hyperlinks of symbol names (`{name}`, `{crate}` & `{addr}` are
+    /// replaced with symbol's values). If `None` - names aren't links
+    link_template: Option<String>,
+
+    /// If `true` - parsed executables are cached in `binsize/cache` of target directory (only if
+    /// target directory exists, i.e. `binsize` is run from a cargo project)
+    cache: bool,
+
+    /// Output control context
+    output: Output,
+
+    /// Output formats. If empty - only tables are printed
+    formats: Vec<OutputFormat>,
+
+    /// If `true` - machine-readable output goes to stderr and tables to stdout, instead of
+    /// the other way around
+    machine_stderr: bool,
+
+    /// If `true` - several files are reported in one run, so machine-readable records carry
+    /// a path of the file they belong to
+    batch: bool,
+
+    /// JSON reports of files in `batch` mode. Printed as a single array after all files are
+    /// reported, so output stays a valid JSON document
+    json_reports: Vec<json::JsonValue>,
+
+    /// User-defined columns of symbol tables from `[binsize.columns]` config section
+    columns: Vec<expr::DerivedColumn>,
+
+    /// Size budgets from `[binsize.budget]` config section
+    budgets: Vec<budget::Budget>,
+
+    /// Set, if any of `budgets` was exceeded, `binsize` exits with non-zero code in this case
+    budget_exceeded: bool,
+
+    /// Cargo feature, size cost of which is reported instead of tables (project is built with
+    /// & without it). Disabled, if empty
+    feature_impact: String,
+
+    /// If `true` - project is built without & with fat LTO, and the difference is reported
+    /// instead of tables
+    lto_impact: bool,
+
+    /// If `true` - project is built with `panic = "unwind"` & `panic = "abort"`, and the
+    /// difference is reported instead of tables
+    panic_impact: bool,
+
+    /// Opt-levels, that the project is built with to print a matrix of sizes instead of tables.
+    /// Disabled, if empty
+    opt_matrix: Vec<String>,
+
+    /// If `true` - dependency tree with cumulative crate sizes is printed instead of tables
+    /// (`binsize tree`)
+    tree: bool,
+
+    /// If `true` - totals of this run are appended to `history_file`
+    record: bool,
+
+    /// Path to history file, used by `--record` and `binsize history`. If empty -
+    /// `history::DEFAULT_HISTORY_FILE` in target directory is used
+    history_file: String,
+
+    /// If `true` - `Δ` column with size change since the last run is shown
+    delta: bool,
+
+    /// Totals of currently loaded executable from the last run, if it was analyzed before
+    previous: Option<delta::Totals>,
+
+    /// Size changes of symbols (by address) since the last run. Only filled, if `previous`
+    /// is set
+    symbol_deltas: HashMap<usize, i64>,
+
+    /// Path of currently loaded executable, identifies it in the last run file
+    exe_path: String,
+
+    /// Path to previously saved JSON report (`--format json`) to compare against
+    baseline: String,
+
+    /// Path to template of a custom report (`--template`)
+    template: String,
+
+    /// Directory `binsize` was invoked from. Current directory is changed to project root, so
+    /// paths from command line are resolved against this one
+    invocation_dir: std::path::PathBuf,
+
+    /// Build atrifacts
+    artifacts: Vec<BuildArtifact>,
+
+    /// Retained sizes of symbols (by address), calculated from reference graph. Only filled,
+    /// if `retained` column or `dominators` output is enabled
+    retained: HashMap<usize, usize>,
+
+    /// Addresses of symbols, that aren't reachable from entry point & exports in reference
+    /// graph. Only filled, if `unreachable` output is enabled
+    unreachable: HashSet<usize>,
+
+    /// Compressed sizes of loaded sections. Only filled, if `compression` output is enabled
+    compression: Vec<compress::Compression>,
+
+    /// Crate names from `cargo metadata`, used to classify crates. Only filled, if `categories`
+    /// output is enabled
+    metadata: cargo::Metadata,
+
+    /// Executable info
+    exe: ExecutableInfo,
+}
+
+impl Default for Binsize {
+    fn default() -> Self {
+        Self {
+            build_options:               Default::default(),
+            no_build:                    false,
+            filter:                      regex::Regex::new(".+").unwrap(),
+            where_expr:                  None,
+            ld_file:                     "".to_string(),
+            map_file:                    "".to_string(),
+            graph_file:                  "".to_string(),
+            files:                       Vec::new(),
+            compare:                     false,
+            workspace:                   false,
+            fix_profile:                 false,
+            workspace_tables:            false,
+            artifact:                    "".to_string(),
+            arch:                        None,
+            color:                       false,
+            theme_stripe:                Vec::new(),
+            theme_highlight:             Vec::new(),
+            theme_heat:                  Vec::new(),
+            max_rows:                    0,
+            width:                       0,
+            group_digits:                None,
+            precision:                   2,
+            hex_sizes:                   false,
+            fold_generics:               false,
+            limits:                      HashMap::new(),
+            collapse_below:              None,
+            truncate:                    Truncate::Wrap,
+            exclude_std:                 false,
+            cache:                       true,
+            show_aliases:                false,
+            link_template:               None,
+            sections:                    Vec::new(),
+            exclude_sections:            None,
+            langs:                       Vec::new(),
+            suggest:                     false,
+            estimate_stripped:           false,
+            output:                      Output::new(),
+            formats:                     Vec::new(),
+            machine_stderr:              false,
+            batch:                       false,
+            json_reports:                Vec::new(),
+            baseline:                    "".to_string(),
+            template:                    "".to_string(),
+            budgets:                     Vec::new(),
+            columns:                     Vec::new(),
+            feature_impact:              "".to_string(),
+            lto_impact:                  false,
+            panic_impact:                false,
+            opt_matrix:                  Vec::new(),
+            tree:                        false,
+            record:                      false,
+            history_file:                "".to_string(),
+            delta:                       true,
+            previous:                    None,
+            symbol_deltas:               HashMap::new(),
+            exe_path:                    "".to_string(),
+            budget_exceeded:             false,
+            exe:                         Default::default(),
+            retained:                    HashMap::new(),
+            unreachable:                 HashSet::new(),
+            compression:                 Vec::new(),
+            metadata:                    Default::default(),
+            invocation_dir:              std::path::PathBuf::new(),
+            artifacts:                   Vec::default(),
+            percent_base:                PercentBase::Symbols,
+            symbols_sorting_order:       None,
+            sort_keys:                   Vec::new(),
+            size_threshold_yellow:       200,
+            size_threshold_red:          500,
+            percentage_threshold_yellow: 0.5,
+            percentage_threshold_red:    1.0,
+            segment_threshold_yellow:    50.0,
+            segment_threshold_red:       75.0,
+        }
+    }
+}
+
+impl Binsize {
+    /// Create new `binsize` application
+    fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns config file to load: the one given with `--config` or `BINSIZE_CONFIG` (must exist),
+    /// or `.cargo/binsize.toml`, if available. Config is loaded before arguments are parsed, so
+    /// `--config` is looked up in them separately
+    fn config_path(&self) -> Option<String> {
+        let mut args = std::env::args().skip(1).take_while(|a| a != "--");
+
+        let mut path = None;
+
+        while let Some(arg) = args.next() {
+            if arg == "--config" {
+                path = Some(args.next().expect("Missing value for --config"));
+            } else if let Some(val) = arg.strip_prefix("--config=") {
+                path = Some(val.to_string());
+            }
+        }
+
+        match path.or_else(|| std::env::var(CONFIG_ENV).ok()) {
+            Some(path) => Some(util::rebase_path(path.as_str(), &self.invocation_dir)),
+            None       => matches!(std::fs::exists(CONFIG), Ok(true)).then(|| CONFIG.to_string()),
+        }
+    }
+
+    /// Returns location of user config (`binsize/config.toml` in `$XDG_CONFIG_HOME`, or in
+    /// platform's config directory: `~/.config` on Linux, `~/Library/Application Support` on
+    /// macOS, `%APPDATA%` on Windows), if it exists
+    fn user_config_path() -> Option<String> {
+        let dir = std::env::var("XDG_CONFIG_HOME").map(std::path::PathBuf::from).ok()
+            .or_else(|| match std::env::consts::OS {
+                "windows" => std::env::var("APPDATA").map(std::path::PathBuf::from).ok(),
+                "macos"   => std::env::var("HOME").map(|home| std::path::Path::new(&home).join("Library/Application Support")).ok(),
+                _         => std::env::var("HOME").map(|home| std::path::Path::new(&home).join(".config")).ok(),
+            })?;
+
+        let path = dir.join("binsize").join("config.toml");
+
+        path.is_file().then(|| path.to_string_lossy().to_string())
+    }
+
+    /// Parse config file at `path`
+    fn parse_config(&mut self, path: &str) {
+        let config = std::fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Failed to read config file '{}': {}", path, e));
+        let cfg = toml::from_str::<toml::Table>(config.as_str()).unwrap();
+
+        if cfg.contains_key("binsize") {
+            let binsize = cfg.get("binsize")
+                .expect("Config file must contain a [binsize] section")
+                .as_table()
+                .expect("[binsize] must be a table]");
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("color") {
+                self.color = *val;
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("profile") {
+                self.build_options.profile = val.clone();
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("release") && *val {
+                self.build_options.profile = "release".to_string();
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("target-dir") {
+                self.build_options.target_dir = val.clone();
+            }
+
+            if let Some(toml::Value::Integer(val)) = binsize.get("jobs") {
+                self.build_options.jobs = Some(*val);
+            }
+
+            match binsize.get("build-std") {
+                Some(toml::Value::Boolean(val)) => {
+                    self.build_options.build_std = val.then(Vec::new);
+                }
+                Some(toml::Value::Array(val)) => {
+                    self.build_options.build_std = Some(
+                        val.iter()
+                            .map(|s| s.as_str().expect("Standard library crate should be a string").to_string())
+                            .collect()
+                    );
+                }
+                _ => {}
+            }
+
+            // Must go before `output`, so outputs without field list get configured defaults
+            if let Some(val) = binsize.get("fields") {
+                let table = val.as_table().expect("[binsize.fields] must be a table");
+
+                for (kind, fields) in table {
+                    let fields = match fields {
+                        toml::Value::String(val) => val.clone(),
+                        toml::Value::Array(val)  => val.iter()
+                            .map(|f| f.as_str().expect("Field should be a string"))
+                            .collect::<Vec<_>>()
+                            .join(","),
+                        _ => panic!("Fields of '{}' should be a string or an array of strings", kind),
+                    };
+
+                    self.output.set_default_fields(kind, &fields);
+                }
+            }
+
+            // Lists replace the ones from user config, instead of adding to them
+            if let Some(toml::Value::Array(val)) = binsize.get("output") {
+                self.output.disable(OutputKind::All);
+
+                for s in val {
+                    let str = s.as_str().expect("Output should be a string");
+
+                    self.output.apply_pattern(str.try_into().unwrap());
+                }
+            }
+
+            match binsize.get("format") {
+                Some(toml::Value::String(val)) => {
+                    self.formats = vec![OutputFormat::try_from(val.as_str()).unwrap()];
+                }
+                Some(toml::Value::Array(val)) => {
+                    self.formats.clear();
+
+                    for s in val {
+                        let str = s.as_str().expect("Format should be a string");
+
+                        self.formats.push(OutputFormat::try_from(str).unwrap());
+                    }
+                }
+                _ => {}
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("machine-stderr") {
+                self.machine_stderr = *val;
+            }
+
+            if let Some(val) = binsize.get("budget") {
+                let table = val.as_table().expect("[binsize.budget] must be a table");
+
+                self.budgets = budget::Budget::parse_table(table).unwrap();
+            }
+
+            if let Some(val) = binsize.get("theme") {
+                let table = val.as_table().expect("[binsize.theme] must be a table");
+
+                for (key, val) in table {
+                    let attrs = Attribute::parse_list(val)
+                        .unwrap_or_else(|e| panic!("Theme '{}': {}", key, e));
+
+                    match key.as_str() {
+                        "stripe"    => self.theme_stripe = attrs,
+                        "highlight" => self.theme_highlight = attrs,
+                        "heat"      => {
+                            if attrs.len() != 2 || attrs[0].blend(&attrs[1], 0.0).is_none() {
+                                panic!("Theme 'heat' must be a pair of colors like [\"#00c000\", \"#c00000\"]");
+                            }
+
+                            self.theme_heat = attrs;
+                        }
+                        _           => panic!("Unknown theme key '{}' (possible values: stripe, highlight, heat)", key),
+                    }
+                }
+            }
+
+            if let Some(val) = binsize.get("columns") {
+                let table = val.as_table().expect("[binsize.columns] must be a table");
+
+                self.columns = expr::DerivedColumn::parse_table(table).unwrap();
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("record") {
+                self.record = *val;
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("history-file") {
+                self.history_file = val.clone();
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("baseline") {
+                self.baseline = val.clone();
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("template") {
+                self.template = val.clone();
+            }
+
+            match binsize.get("file") {
+                Some(toml::Value::String(val)) => {
+                    self.files = util::expand_glob(val).unwrap();
+                }
+                Some(toml::Value::Array(val)) => {
+                    self.files.clear();
+
+                    for s in val {
+                        let str = s.as_str().expect("File should be a string");
+
+                        self.files.extend(util::expand_glob(str).unwrap());
+                    }
+                }
+                _ => {}
+            }
+
+            match binsize.get("exclude-section") {
+                Some(toml::Value::String(val)) => {
+                    self.exclude_sections = Some(regex::Regex::new(val.as_str()).unwrap());
+                }
+                Some(toml::Value::Array(val)) => {
+                    self.exclude_sections = Some(any_regex(
+                        val.iter().map(|s| s.as_str().expect("Excluded section should be a string"))
+                    ));
+                }
+                _ => {}
+            }
+
+            match binsize.get("section") {
+                Some(toml::Value::String(val)) => {
+                    self.sections = vec![val.clone()];
+                }
+                Some(toml::Value::Array(val)) => {
+                    self.sections = val.iter()
+                        .map(|s| s.as_str().expect("Section should be a string").to_string())
+                        .collect();
+                }
+                _ => {}
+            }
+
+            match binsize.get("lang") {
+                Some(toml::Value::String(val)) => {
+                    self.langs = vec![DemangledSymbolKind::try_from(val.as_str()).unwrap()];
+                }
+                Some(toml::Value::Array(val)) => {
+                    self.langs = val.iter()
+                        .map(|s| DemangledSymbolKind::try_from(s.as_str().expect("Language should be a string")).unwrap())
+                        .collect();
+                }
+                _ => {}
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("suggest") {
+                self.suggest = *val;
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("estimate-stripped") {
+                self.estimate_stripped = *val;
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("no-build") {
+                self.no_build = *val;
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("artifact") {
+                self.artifact = val.clone();
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("arch") {
+                self.arch = Some(val.clone());
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("compare") {
+                self.compare = *val;
+            }
+
+            if let Some(toml::Value::Array(val)) = binsize.get("cargo-args") {
+                self.build_options.extra_args = val.iter()
+                    .map(|s| s.as_str().expect("Cargo argument should be a string").to_string())
+                    .collect();
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("fix-profile") {
+                self.fix_profile = *val;
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("workspace") {
+                self.workspace = *val;
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("workspace-tables") {
+                self.workspace_tables = *val;
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("cache") {
+                self.cache = *val;
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("delta") {
+                self.delta = *val;
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("show-aliases") {
+                self.show_aliases = *val;
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("link-template") {
+                self.link_template = Some(val.clone());
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("exclude-std") {
+                self.exclude_std = *val;
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("filter") {
+                self.filter = regex::Regex::new(val.as_str()).unwrap();
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("where") {
+                self.where_expr = Some(expr::Expr::parse(val, expr::WHERE_FIELDS, true).unwrap());
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("ld-file") {
+                self.ld_file = val.clone();
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("map-file") {
+                self.map_file = val.clone();
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("graph-file") {
+                self.graph_file = val.clone();
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("sort") {
+                self.symbols_sorting_order = Some(
+                    SortOrder::try_from(val.as_str())
+                        .unwrap_or_else(|e| panic!("Invalid value for key 'sort': {}", e))
+                );
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("percent-of") {
+                self.percent_base = PercentBase::try_from(val.as_str()).unwrap();
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("sort-by") {
+                self.sort_keys = Self::parse_sort_keys(val);
+            }
+
+            if let Some(toml::Value::Integer(val)) = binsize.get("max-rows") {
+                self.max_rows = *val as usize;
+            }
+
+            if let Some(toml::Value::Integer(val)) = binsize.get("width") {
+                self.width = *val as usize;
+            }
+
+            match binsize.get("group-digits") {
+                Some(toml::Value::Boolean(val)) => {
+                    self.group_digits = val.then_some(DEFAULT_DIGIT_SEPARATOR);
+                }
+                Some(toml::Value::String(val)) => {
+                    self.group_digits = Some(parse_digit_separator(val).unwrap());
+                }
+                _ => {}
+            }
+
+            if let Some(toml::Value::Integer(val)) = binsize.get("precision") {
+                self.precision = usize::try_from(*val).ok()
+                    .filter(|precision| *precision <= MAX_PRECISION)
+                    .unwrap_or_else(|| panic!("Invalid value for key 'precision': expected 0..={}", MAX_PRECISION));
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("hex-sizes") {
+                self.hex_sizes = *val;
+            }
+
+            if let Some(toml::Value::Boolean(val)) = binsize.get("fold-generics") {
+                self.fold_generics = *val;
+            }
+
+            if let Some(toml::Value::Table(val)) = binsize.get("limit") {
+                for (kind, rows) in val {
+                    let rows = rows.as_integer().expect("Limit should be a number of rows");
+
+                    self.limits.insert(OutputKind::try_from(kind.as_str()).unwrap(), rows as usize);
+                }
+            }
+
+            if let Some(val) = binsize.get("collapse-below") {
+                self.collapse_below = Some(budget::Limit::parse(val).unwrap());
+            }
+
+            if let Some(toml::Value::String(val)) = binsize.get("truncate") {
+                self.truncate = Truncate::try_from(val.as_str()).unwrap();
+            }
+
+            if let Some(toml::Value::Array(val)) = binsize.get("size-threshold") {
+                self.size_threshold_yellow = val.get(0)
+                    .expect("Missing first value for key 'size-threshold'")
+                    .as_integer()
+                    .expect("Values for key 'size-threshold' must be an integer")
+                    as usize;
+
+                self.size_threshold_red = val.get(1)
+                    .expect("Missing second value for key 'size-threshold'")
+                    .as_integer()
+                    .expect("Values for key 'size-threshold' must be an integer")
+                    as usize;
+            }
+
+            if let Some(toml::Value::Array(val)) = binsize.get("percentage-threshold") {
+                self.percentage_threshold_yellow = val.get(0)
+                    .expect("Missing first value for key 'size-threshold'")
+                    .as_float()
+                    .expect("Values for key 'size-threshold' must be a float")
+                    as f32;
+
+                self.percentage_threshold_red = val.get(1)
+                    .expect("Missing second value for key 'size-threshold'")
+                    .as_float()
+                    .expect("Values for key 'size-threshold' must be a float")
+                    as f32;
+            }
+
+            if let Some(toml::Value::Array(val)) = binsize.get("segment-threshold") {
+                self.segment_threshold_yellow = val.first()
+                    .expect("Missing first value for key 'segment-threshold'")
+                    .as_float()
+                    .expect("Values for key 'segment-threshold' must be a float")
+                    as f32;
+
+                self.segment_threshold_red = val.get(1)
+                    .expect("Missing second value for key 'segment-threshold'")
+                    .as_float()
+                    .expect("Values for key 'segment-threshold' must be a float")
+                    as f32;
+            }
+        }
+    }
+
+    /// Parse command line arguments
+    fn parse_args(&mut self) {
+        let argp = args::ArgumentParser::new(
+            vec![
+                args::Argument::new_flag(
+                    "help",
+                    &["--help", "-h"],
+                    "Display help message"
+                ),
+                args::Argument::new_flag(
+                    "version",
+                    &["--version", "-v"],
+                    "Display version"
+                ),
+                args::Argument::new_value(
+                    "profile",
+                    &["--profile", "-p"],
+                    &["PROFILE"],
+                    "Cargo profile to build the project with"
+                ),
+                args::Argument::new_flag(
+                    "release",
+                    &["--release", "-r"],
+                    "Build the project with release profile (same as --profile release)"
+                ),
+                args::Argument::new_value(
+                    "target-dir",
+                    &["--target-dir"],
+                    &["DIR"],
+                    "Directory for build artifacts, passed to cargo"
+                ),
+                args::Argument::new_value(
+                    "jobs",
+                    &["--jobs", "-j"],
+                    &["N"],
+                    "Number of parallel build jobs, passed to cargo"
+                ),
+                args::Argument::new_optional(
+                    "build-std",
+                    &["--build-std"],
+                    "CRATES",
+                    "Rebuild comma-separated standard library crates with -Z build-std (nightly)"
+                ),
+                args::Argument::new_repeated(
+                    "output",
+                    &["--output", "-o"],
+                    &["OUTPUT"],
+                    "Output table with optional comma-separated list of columns (can be repeated)"
+                ),
+                args::Argument::new_repeated(
+                    "format",
+                    &["--format", "-F"],
+                    &["FORMAT"],
+                    "Output format: table, json, csv, jsonl or gh-summary. Can be repeated to get tables along with machine output"
+                ),
+                args::Argument::new_flag(
+                    "machine-stderr",
+                    &["--machine-stderr"],
+                    "Write machine output to stderr and tables to stdout (by default it's the other way around)"
+                ),
+                args::Argument::new_flag(
+                    "record",
+                    &["--record"],
+                    "Append totals of this run to history file (see 'binsize history')"
+                ),
+                args::Argument::new_value(
+                    "history-file",
+                    &["--history-file"],
+                    &["PATH"],
+                    "History file location (default target/binsize/history.jsonl)"
+                ),
+                args::Argument::new_value(
+                    "baseline",
+                    &["--baseline"],
+                    &["REPORT"],
+                    "Previously saved JSON report to compare against (used by gh-summary format)"
+                ),
+                args::Argument::new_value(
+                    "template",
+                    &["--template"],
+                    &["FILE"],
+                    "Render enabled outputs through a Handlebars-like template, instead of tables"
+                ),
+                args::Argument::new_repeated(
+                    "file",
+                    &["--file"],
+                    &["FILE"],
+                    "Provide a path to compiled binary, skipping 'cargo build'. Can be repeated, supports '*' & '?' wildcards"
+                ).positional(),
+                args::Argument::new_value(
+                    "artifact",
+                    &["--artifact"],
+                    &["NAME"],
+                    "Name of cargo artifact (binary/library) to analyze, if build produces several"
+                ),
+                args::Argument::new_value(
+                    "arch",
+                    &["--arch"],
+                    &["ARCH"],
+                    "Slice of universal Mach-O binary to analyze (e.g. arm64, x86_64), host one by default"
+                ),
+                args::Argument::new_flag(
+                    "compare",
+                    &["--compare"],
+                    "Print a single comparison table for all files, instead of separate reports"
+                ),
+                args::Argument::new_value(
+                    "feature-impact",
+                    &["--feature-impact"],
+                    &["FEATURE"],
+                    "Build with & without FEATURE, and report its size cost per crate & symbol"
+                ),
+                args::Argument::new_flag(
+                    "lto-impact",
+                    &["--lto-impact"],
+                    "Build with lto = false & lto = \"fat\", and report what LTO saves per crate & symbol"
+                ),
+                args::Argument::new_flag(
+                    "panic-impact",
+                    &["--panic-impact"],
+                    "Build with panic = \"unwind\" & panic = \"abort\", and report the difference"
+                ),
+                args::Argument::new_optional(
+                    "opt-matrix",
+                    &["--opt-matrix"],
+                    "LEVELS",
+                    "Build with each opt-level from LEVELS (default 0,s,z,3), and print a matrix of sizes"
+                ),
+                args::Argument::new_flag(
+                    "fix-profile",
+                    &["--fix-profile", "--override-profile-settings"],
+                    "Override cargo profile settings, that strip symbols, for the build"
+                ),
+                args::Argument::new_flag(
+                    "workspace",
+                    &["--workspace"],
+                    "Build whole workspace and print size summary of every binary"
+                ),
+                args::Argument::new_flag(
+                    "workspace-tables",
+                    &["--workspace-tables"],
+                    "With --workspace, also print full tables for each binary"
+                ),
+                args::Argument::new_value(
+                    "ld-memory-map",
+                    &["--ld-memory-map", "-l"],
+                    &["LD_PATH"],
+                    "Path to ld script, containing MEMORY declaration"
+                ),
+                args::Argument::new_value(
+                    "map",
+                    &["--map"],
+                    &["MAP_PATH"],
+                    "Path to linker map, to cross-check section sizes against"
+                ),
+                args::Argument::new_value(
+                    "graph",
+                    &["--graph"],
+                    &["GRAPH_PATH"],
+                    "Export symbol reference graph as JSON (or GraphML, if path ends with .graphml)"
+                ),
+                args::Argument::new_repeated(
+                    "filter",
+                    &["--filter", "-f"],
+                    &["FILTER"],
+                    "Filter symbol names by this value. Supports regex. If repeated, symbols matching any filter are shown"
+                ),
+                args::Argument::new_value(
+                    "where",
+                    &["--where", "-w"],
+                    &["EXPR"],
+                    "Filter symbols by expression over their fields (e.g. 'size > 1024 && kind == FUNC')"
+                ),
+                args::Argument::new_flag(
+                    "no-cache",
+                    &["--no-cache"],
+                    "Don't use cache of parsed executables (target/binsize/cache)"
+                ),
+                args::Argument::new_flag(
+                    "no-delta",
+                    &["--no-delta"],
+                    "Don't show size change since the last run, nor save totals of this one (target/binsize/last.json)"
+                ),
+                args::Argument::new_repeated(
+                    "section",
+                    &["--section"],
+                    &["NAME"],
+                    "Only analyze symbols from section NAME (can be repeated)"
+                ),
+                args::Argument::new_repeated(
+                    "exclude-section",
+                    &["--exclude-section"],
+                    &["REGEX"],
+                    "Leave sections matching REGEX (e.g. '^\\.debug') out of sections table & file size (can be repeated)"
+                ),
+                args::Argument::new_repeated(
+                    "lang",
+                    &["--lang"],
+                    &["LANG"],
+                    "Only analyze symbols of language LANG: rust, cpp, c, other (can be repeated)"
+                ),
+                args::Argument::new_flag(
+                    "suggest",
+                    &["--suggest"],
+                    "Print suggestions on how to reduce size, based on analysis"
+                ),
+                args::Argument::new_flag(
+                    "estimate-stripped",
+                    &["--estimate-stripped"],
+                    "Print estimated file size after strip, without modifying the file"
+                ),
+                args::Argument::new_flag(
+                    "no-build",
+                    &["--no-build"],
+                    "Don't run cargo build, analyze artifacts of the last build"
+                ),
+                args::Argument::new_flag(
+                    "show-aliases",
+                    &["--show-aliases"],
+                    "List symbols with the same address & size separately, instead of collapsing them"
+                ),
+                args::Argument::new_value(
+                    "link-template",
+                    &["--link-template"],
+                    &["URL"],
+                    "Make symbol names terminal hyperlinks to URL, with {name}, {crate} & {addr} replaced"
+                ),
+                args::Argument::new_flag(
+                    "exclude-std",
+                    &["--exclude-std"],
+                    "Exclude symbols of std, core, alloc & compiler_builtins"
+                ),
+                args::Argument::new_flag(
+                    "asc",
+                    &["--asc", "-a"],
+                    "Sort by symbol size in ascending order"
+                ),
+                args::Argument::new_flag(
+                    "desc",
+                    &["--desc", "-d"],
+                    "Sort by symbol size in descending order"
+                ),
+                args::Argument::new_value(
+                    "sort-by",
+                    &["--sort-by"],
+                    &["KEYS"],
+                    "Sort symbols, sections & segments by comma separated list of FIELD:asc|desc (fields: size, name, crate, kind, addr)"
+                ),
+                args::Argument::new_value(
+                    "percent-of",
+                    &["--percent-of"],
+                    &["BASE"],
+                    "What symbol percentage is calculated against: symbols (default), filesize or section"
+                ),
+                args::Argument::new_flag(
+                    "color",
+                    &["--color", "-c"],
+                    "Add coloring to output"
+                ),
+                args::Argument::new_value(
+                    "max-rows",
+                    &["-n", "--max-rows"],
+                    &["ROWS"],
+                    "Max rows to output. Shared between all tables"
+                ),
+                args::Argument::new_value(
+                    "width",
+                    &["--width"],
+                    &["COLS"],
+                    "Width of tables, instead of terminal width (or COLUMNS, if output isn't a terminal)"
+                ),
+                args::Argument::new_optional(
+                    "group-digits",
+                    &["--group-digits"],
+                    "SEP",
+                    "Group digits of sizes in tables by three, using SEP ('_' or ',', default '_')"
+                ),
+                args::Argument::new_value(
+                    "precision",
+                    &["--precision"],
+                    &["DIGITS"],
+                    "Number of decimal places in percentages (default 2)"
+                ),
+                args::Argument::new_flag(
+                    "hex-sizes",
+                    &["--hex-sizes"],
+                    "Print sizes in tables in hexadecimal"
+                ),
+                args::Argument::new_flag(
+                    "fold-generics",
+                    &["--fold-generics"],
+                    "Fold generic arguments of symbol names in tables into '<…>'"
+                ),
+                args::Argument::new_repeated(
+                    "limit",
+                    &["--limit"],
+                    &["KIND=ROWS"],
+                    "Max rows to output in tables of KIND (e.g. symbols=50). Can be repeated"
+                ),
+                args::Argument::new_value(
+                    "collapse-below",
+                    &["--collapse-below"],
+                    &["SIZE"],
+                    "Roll symbols & crates smaller than SIZE (bytes, e.g. 1K, or percent of total, e.g. 0.5%) into one row"
+                ),
+                args::Argument::new_value(
+                    "truncate",
+                    &["--truncate"],
+                    &["MODE"],
+                    "How to shorten values that don't fit: start, middle, end or wrap (default)"
+                ),
+                args::Argument::new_value(
+                    "size-threshold",
+                    &["--size-threshold"],
+                    &["YELLOW", "RED"],
+                    "Yellow & red size thresholds in bytes (default 200 500)"
+                ),
+                args::Argument::new_value(
+                    "percentage-threshold",
+                    &["--percentage-threshold"],
+                    &["YELLOW", "RED"],
+                    "Yellow & red size percentage thresholds (default 0.5 1.0)"
+                ),
+                args::Argument::new_value(
+                    "segment-threshold",
+                    &["--segment-threshold"],
+                    &["YELLOW", "RED"],
+                    "Yellow & red memory region usage thresholds in percent (default 50 75)"
+                ),
+                args::Argument::new_value(
+                    "config",
+                    &["--config"],
+                    &["PATH"],
+                    "Config file to load instead of .cargo/binsize.toml"
+                ),
+                args::Argument::new_flag(
+                    "ignore-config",
+                    &["-i", "--ignore-config"],
+                    "Ignore config file"
+                ),
+            ],
+            args::UnexpectedArgumentPolicy::Crash
+        );
+
+        // `binsize tree` takes the same options, so only command itself is skipped
+        let parsed = argp.parse(std::env::args().skip(if self.tree { 2 } else { 1 }));
+
+        // Everything after `--` goes to `cargo build`, replacing arguments from config
+        if !parsed.trailing.is_empty() {
+            self.build_options.extra_args = parsed.trailing.clone();
+        }
+
+        // FIXME: Is still needed?
+        // if parsed.contains_arg("output") {
+        //     self.output = Output::None as u32;
+        // }
+
+        for arg in parsed.args {
+            match arg.name.as_str() {
+                "help" => {
+                    println!("binsize - utility to provide comprehensive information about symbol sizes in compiled binaries");
+                    println!("Usage: binsize [COMMAND] [OPTIONS] [FILE]... [-- CARGO_ARGS]");
+                    println!("Commands:");
+                    println!("    init    - Write a starter config into {}", CONFIG);
+                    println!("    history - Print size trend from runs, recorded with --record");
+                    println!("    tree    - Print dependency tree with size of each crate and its subtree");
+                    println!("Options:");
+                    argp.print_help();
+                    std::process::exit(0);
+                }
+                "version" => {
+                    println!("binsize {}", VERSION);
+                    std::process::exit(0);
+                }
+                "profile" => {
+                    self.build_options.profile = arg.values.get(0)
+                        .expect("Missing value for --profile")
+                        .clone();
+                }
+                "release" => {
+                    self.build_options.profile = "release".to_string();
+                }
+                "target-dir" => {
+                    self.build_options.target_dir = util::rebase_path(
+                        arg.values.first().expect("Missing value for --target-dir"),
+                        &self.invocation_dir
+                    );
+                }
+                "jobs" => {
+                    self.build_options.jobs = Some(
+                        arg.values.first()
+                            .expect("Missing value for --jobs")
+                            .parse::<i64>()
+                            .expect("jobs must be a number")
+                    );
+                }
+                "build-std" => {
+                    self.build_options.build_std = Some(
+                        arg.values.first()
+                            .map(|crates| crates.split(',').map(|c| c.trim().to_string()).collect())
+                            .unwrap_or_default()
+                    );
+                }
+                "output" => {
+                    for val in arg.values.iter() {
+                        self.output.apply_pattern(val);
+                    }
+                }
+                "format" => {
+                    for val in arg.values.iter() {
+                        self.formats.push(OutputFormat::try_from(val.as_str()).unwrap());
+                    }
+                }
+                "machine-stderr" => {
+                    self.machine_stderr = true;
+                }
+                "record" => {
+                    self.record = true;
+                }
+                "history-file" => {
+                    self.history_file = util::rebase_path(
+                        arg.values.first().expect("Missing value for --history-file"),
+                        &self.invocation_dir
+                    );
+                }
+                "baseline" => {
+                    self.baseline = util::rebase_path(
+                        arg.values.first().expect("Missing value for --baseline"),
+                        &self.invocation_dir
+                    );
+                }
+                "template" => {
+                    self.template = util::rebase_path(
+                        arg.values.first().expect("Missing value for --template"),
+                        &self.invocation_dir
+                    );
+                }
+                "file" => {
+                    // Files from command line replace the ones from config, instead of adding to them
+                    self.files.clear();
+
+                    for val in arg.values.iter() {
+                        self.files.extend(
+                            util::expand_glob(util::rebase_path(val, &self.invocation_dir).as_str()).unwrap()
+                        );
+                    }
+                }
+                "artifact" => {
+                    self.artifact = arg.values.first()
+                        .expect("Missing value for --artifact")
+                        .clone();
+                }
+                "arch" => {
+                    self.arch = Some(arg.values.first().expect("Missing value for --arch").clone());
+                }
+                "compare" => {
+                    self.compare = true;
+                }
+                "lto-impact" => {
+                    self.lto_impact = true;
+                }
+                "panic-impact" => {
+                    self.panic_impact = true;
+                }
+                "opt-matrix" => {
+                    self.opt_matrix = arg.values.first()
+                        .map(|levels| levels.split(',').map(|l| l.trim().to_string()).collect())
+                        .unwrap_or_else(|| DEFAULT_OPT_LEVELS.iter().map(|l| l.to_string()).collect());
+                }
+                "feature-impact" => {
+                    self.feature_impact = arg.values.first().expect("Missing value for --feature-impact").clone();
+                }
+                "workspace" => {
+                    self.workspace = true;
+                }
+                "workspace-tables" => {
+                    self.workspace_tables = true;
+                }
+                "fix-profile" => {
+                    self.fix_profile = true;
+                }
+                "filter" => {
+                    // Several filters are joined into alternation, so symbol has to match any of them
+                    self.filter = any_regex(arg.values.iter().map(String::as_str));
+                }
+                "exclude-section" => {
+                    self.exclude_sections = Some(any_regex(arg.values.iter().map(String::as_str)));
+                }
+                "where" => {
+                    self.where_expr = Some(expr::Expr::parse(
+                        arg.values.first().expect("Missing value for --where"),
+                        expr::WHERE_FIELDS,
+                        true
+                    ).unwrap());
+                }
+                "ld-memory-map" => {
+                    self.ld_file = util::rebase_path(
+                        arg.values.first().expect("Missing value for --ld-memory-map"),
+                        &self.invocation_dir
+                    );
+                }
+                "map" => {
+                    self.map_file = util::rebase_path(
+                        arg.values.first().expect("Missing value for --map"),
+                        &self.invocation_dir
+                    );
+                }
+                "graph" => {
+                    self.graph_file = util::rebase_path(
+                        arg.values.first().expect("Missing value for --graph"),
+                        &self.invocation_dir
+                    );
+                }
+                "no-cache" => {
+                    self.cache = false;
+                }
+                "no-delta" => {
+                    self.delta = false;
+                }
+                "section" => {
+                    // Sections & languages from command line replace the ones from config too
+                    self.sections = arg.values.clone();
+                }
+                "lang" => {
+                    self.langs = arg.values.iter()
+                        .map(|lang| DemangledSymbolKind::try_from(lang.as_str()).unwrap())
+                        .collect();
+                }
+                "suggest" => {
+                    self.suggest = true;
+                }
+                "estimate-stripped" => {
+                    self.estimate_stripped = true;
+                }
+                "no-build" => {
+                    self.no_build = true;
+                }
+                "show-aliases" => {
+                    self.show_aliases = true;
+                }
+                "link-template" => {
+                    self.link_template = Some(arg.values.first().expect("Missing value for --link-template").clone());
+                }
+                "exclude-std" => {
+                    self.exclude_std = true;
+                }
+                "asc" => {
+                    self.symbols_sorting_order = Some(SortOrder::Ascending);
+                }
+                "desc" => {
+                    self.symbols_sorting_order = Some(SortOrder::Descending);
+                }
+                "sort-by" => {
+                    self.sort_keys = Self::parse_sort_keys(
+                        arg.values.first().expect("Missing value for --sort-by")
+                    );
+                }
+                "percent-of" => {
+                    let val = arg.values.first().expect("Missing value for --percent-of");
+                    self.percent_base = PercentBase::try_from(val.as_str()).unwrap();
+                }
+                "color" => {
+                    self.color = true;
+                }
+                "max-rows" => {
+                    self.max_rows = arg.values.get(0)
+                        .expect("Missing value ROWS for --max-rows")
+                        .parse::<usize>()
+                        .expect("max rows must be a number");
+                }
+                "width" => {
+                    self.width = arg.values.first()
+                        .expect("Missing value COLS for --width")
+                        .parse::<usize>()
+                        .expect("width must be a number");
+                }
+                "group-digits" => {
+                    self.group_digits = Some(
+                        arg.values.first()
+                            .map_or(Ok(DEFAULT_DIGIT_SEPARATOR), |sep| parse_digit_separator(sep))
+                            .unwrap()
+                    );
+                }
+                "precision" => {
+                    self.precision = arg.values.first()
+                        .expect("Missing value DIGITS for --precision")
+                        .parse::<usize>()
+                        .expect("precision must be a number");
+
+                    if self.precision > MAX_PRECISION {
+                        panic!("precision must be at most {}", MAX_PRECISION);
+                    }
+                }
+                "hex-sizes" => {
+                    self.hex_sizes = true;
+                }
+                "fold-generics" => {
+                    self.fold_generics = true;
+                }
+                "limit" => {
+                    for val in arg.values.iter() {
+                        let (kind, rows) = val.split_once('=')
+                            .unwrap_or_else(|| panic!("Invalid limit '{}', expected KIND=ROWS", val));
+
+                        self.limits.insert(
+                            OutputKind::try_from(kind).unwrap(),
+                            rows.parse::<usize>().expect("limit must be a number")
+                        );
+                    }
+                }
+                "collapse-below" => {
+                    self.collapse_below = Some(budget::Limit::parse(&toml::Value::String(
+                        arg.values.first().expect("Missing value for --collapse-below").clone()
+                    )).unwrap());
+                }
+                "truncate" => {
+                    let val = arg.values.first().expect("Missing value for --truncate");
+                    self.truncate = Truncate::try_from(val.as_str()).unwrap();
+                }
+                "size-threshold" => {
+                    self.size_threshold_yellow = arg.values.get(0)
+                        .expect("Missing value YELLOW for --size-threshold")
+                        .parse::<usize>()
+                        .expect("yellow threshold must be a number");
+
+                    self.size_threshold_red = arg.values.get(1)
+                        .expect("Missing value RED for --size-threshold")
+                        .parse::<usize>()
+                        .expect("red threshold must be a number");
+                }
+                "percentage-threshold" => {
+                    self.percentage_threshold_yellow = arg.values.get(0)
+                        .expect("Missing value YELLOW for --percentage-threshold")
+                        .parse::<f32>()
+                        .expect("yellow threshold must be a float");
+
+                    self.percentage_threshold_red = arg.values.get(1)
+                        .expect("Missing value RED for --percentage-threshold")
+                        .parse::<f32>()
+                        .expect("red threshold must be a float");
+                }
+                "segment-threshold" => {
+                    self.segment_threshold_yellow = arg.values.first()
+                        .expect("Missing value YELLOW for --segment-threshold")
+                        .parse::<f32>()
+                        .expect("yellow threshold must be a number");
+
+                    self.segment_threshold_red = arg.values.get(1)
+                        .expect("Missing value RED for --segment-threshold")
+                        .parse::<f32>()
+                        .expect("red threshold must be a number");
+                }
+                "config" => {
+                    // Already loaded by `parse_config()`
+                }
+                "ignore-config" => {
+                    // Command, directory of invocation & arguments after `--` aren't a part of
+                    // config, so they're kept
+                    *self = Self {
+                        tree:           self.tree,
+                        invocation_dir: std::mem::take(&mut self.invocation_dir),
+                        ..Default::default()
+                    };
+
+                    if !parsed.trailing.is_empty() {
+                        self.build_options.extra_args = parsed.trailing.clone();
+                    }
+                }
+                arg => {
+                    panic!("Unexpected argument: {}", arg);
+                }
+            }
+        }
+    }
+
+    /// Parses and validates symbol sorting keys
+    fn parse_sort_keys(value: &str) -> Vec<SortKey> {
+        let keys = SortKey::parse_list(value).unwrap();
+
+        for key in keys.iter() {
+            if !Symbol::SORT_FIELDS.contains(&key.field.as_str()) {
+                panic!(
+                    "Invalid sorting field: '{}' (possible values: {})",
+                    key.field, Symbol::SORT_FIELDS.join(", ")
+                );
+            }
+        }
+
+        keys
+    }
+
+    /// Load executable
+    fn load_exe(&mut self, file: Option<&str>) {
+        // If file was specified (either via config of cmdline options)
+        let path = if let Some(file) = file {
+            std::path::PathBuf::from(file)
+        } else {
+            self.artifacts = self.build(self.build_options.clone());
+
+            let (artifact, others) = cargo::select_artifact(
+                &self.artifacts,
+                (!self.artifact.is_empty()).then_some(self.artifact.as_str())
+            ).unwrap_or_else(|e| panic!("{}", e));
+
+            // Make the choice visible, as it may not be the artifact user expects
+            if !others.is_empty() {
+                eprintln!(
+                    "Build produced several artifacts, analyzing '{}' (others: {}). Use --artifact NAME to select one",
+                    artifact.name,
+                    others.join(", ")
+                );
+            }
+
+            // Extract path to binary
+            artifact.path.clone()
+        };
+
+        // Totals of this file from the last run, to show `Δ` against
+        self.exe_path = path.display().to_string();
+
+        self.previous = if self.delta {
+            delta::load(&self.target_root().join(delta::LAST_FILE)).remove(&self.exe_path)
+        } else {
+            None
+        };
+
+        if let Some(kind) = image::ImageKind::detect(&path) {
+            self.load_image(&path, kind);
+            return;
+        }
+
+        // Parse binary (or load it from cache)
+        let exe = if self.cache && self.target_root().is_dir() {
+            cache::parse(&path, self.arch.as_deref(), &self.target_root().join(cache::CACHE_DIR))
+        } else {
+            exe::parse(&path, self.arch.as_deref())
+        };
+
+        self.exe = exe
+            .unwrap_or_else(|e| panic!("Failed to parse executable '{}': {}", path.display(), e));
+
+        // Make the choice visible, as universal binary has several equally valid slices
+        if let Some(arch) = &self.exe.arch && self.exe.slices.len() > 1 {
+            eprintln!(
+                "Universal binary contains several slices, analyzing '{}' (available: {}). Use --arch ARCH to select one",
+                arch,
+                self.exe.slices.join(", ")
+            );
+        }
+
+        // Nothing to analyze without symbols, so explain why instead of printing empty tables
+        if self.exe.is_stripped() {
+            self.explain_stripped(&path, file.is_none());
+            std::process::exit(1);
+        }
+
+        // Capped sizes are still plausible, but the file is probably malformed, so it's worth knowing
+        if self.exe.capped_symbols != 0 {
+            eprintln!(
+                "Warning: {} symbol(s) in '{}' reached past the end of their section or into the next symbol, their sizes were capped",
+                self.exe.capped_symbols,
+                path.display()
+            );
+        }
+
+        // Patch missing crate names (marked "?"), by using parsed build artifacts
+        if !self.artifacts.is_empty() {
+            exe::patch_missing_crate_names(&mut self.exe, &self.artifacts);
+        }
+
+        self.compression.clear();
+
+        if self.output.enabled(OutputKind::Compression) {
+            self.compression = compress::compress_sections(&path, &self.exe)
+                .unwrap_or_else(|e| panic!("Failed to compress sections of '{}': {}", path.display(), e));
+        }
+
+        // Calculated before any filtering, as filtered out symbols still hold references
+        self.retained.clear();
+        self.unreachable.clear();
+
+        if self.retained_needed() || self.output.enabled(OutputKind::Unreachable) || !self.graph_file.is_empty() {
+            match graph::ReferenceGraph::build(&path, &self.exe) {
+                Ok(graph) => {
+                    if !self.graph_file.is_empty() {
+                        graph.export(std::path::Path::new(&self.graph_file))
+                            .unwrap_or_else(|e| panic!("Failed to export reference graph to '{}': {}", self.graph_file, e));
+                    }
+
+                    if self.retained_needed() {
+                        self.retained = graph.nodes.iter()
+                            .zip(graph.retained_sizes())
+                            .map(|(node, size)| (node.addr, size))
+                            .collect();
+                    }
+
+                    self.unreachable = graph.unreachable().into_iter()
+                        .map(|node| graph.nodes[node].addr)
+                        .collect();
+                }
+                Err(e) => {
+                    eprintln!("Warning: Failed to build reference graph of '{}': {}", path.display(), e);
+                }
+            }
+        }
+
+        // Done after patching, as crate names from artifacts can turn out to be std ones
+        if self.exclude_std {
+            self.exe.symbols.retain(|s| !cargo::is_std_crate(s.crate_name.as_str()));
+        }
+
+        if !self.sections.is_empty() {
+            let sections = &self.exe.sections;
+
+            for name in self.sections.iter().filter(|name| !sections.iter().any(|s| s.name == **name)) {
+                eprintln!("Warning: '{}' has no section named '{}'", path.display(), name);
+            }
+
+            self.exe.symbols.retain(|sym| {
+                sym.section.is_some_and(|i| self.sections.contains(&sections[i].name))
+            });
+        }
+
+        if !self.langs.is_empty() {
+            self.exe.symbols.retain(|sym| self.langs.contains(&sym.lang));
+        }
+
+        // Aliases would otherwise be counted several times towards totals & percentages
+        if !self.show_aliases {
+            self.exe.collapse_aliases();
+        }
+    }
+
+    /// Returns `true`, if symbol passes `--filter` & `--where`
+    fn shown(&self, sym: &Symbol) -> bool {
+        sym.filter(&self.filter) && self.where_expr.as_ref().is_none_or(|e| e.test(&|name| self.symbol_field(sym, name)))
+    }
+
+    /// Returns value of symbol field `name` for expressions (see `expr::WHERE_FIELDS`)
+    fn symbol_field(&self, sym: &Symbol, name: &str) -> Option<expr::Value> {
+        use expr::Value::*;
+
+        match name {
+            "size"     => Some(Number(sym.size as f64)),
+            "addr"     => Some(Number(sym.addr as f64)),
+            "retained" => self.retained.get(&sym.addr).map(|size| Number(*size as f64)),
+            "aliases"  => Some(Number(sym.aliases.len() as f64)),
+            "name"     => Some(Str(sym.name.clone())),
+            "crate"    => Some(Str(sym.crate_name.clone())),
+            "kind"     => Some(Str(sym.kind.to_string().trim().to_string())),
+            "lang"     => Some(Str(sym.lang.to_string())),
+            "section"  => Some(Str(sym.section.map(|i| self.exe.sections[i].name.clone()).unwrap_or_default())),
+            _          => Option::None,
+        }
+    }
+
+    /// Returns `true`, if retained sizes are shown anywhere, so reference graph has to be built
+    fn retained_needed(&self) -> bool {
+        use OutputKind::*;
+
+        let derived = self.columns.iter().any(|c| c.expr.uses("retained"));
+
+        self.where_expr.as_ref().is_some_and(|e| e.uses("retained")) || self.output.enabled(Dominators) || [Symbols, Regions, Ram, Tls, Unreachable].into_iter().any(|kind| {
+            self.output.enabled(kind) && (
+                self.output.field_enabled(kind, SymbolTableFields::Retained as u32) ||
+                self.output.field_enabled(kind, SymbolTableFields::Derived as u32) && derived
+            )
+        })
+    }
+
+    /// Colors memory region usage `percentage` according to `--segment-threshold`
+    fn apply_segment_color(&self, s: &mut AttributeString, percentage: f32) {
+        if percentage > self.segment_threshold_red {
+            s.push_attr(Attribute::ColorFgRed);
+        } else if percentage > self.segment_threshold_yellow {
+            s.push_attr(Attribute::ColorFgYellow);
+        } else {
+            s.push_attr(Attribute::ColorFgGreen);
+        }
+    }
+
+    /// Helper function to crate a colored attribute string, if color is enabled
+    fn colored_str(&self, str: String, color_fn: impl Fn(&mut AttributeString)) -> AttributeString {
+        let mut attr_str = AttributeString::from(str.as_str());
+
+        if self.color {
+            color_fn(&mut attr_str);
+        }
+
+        attr_str
+    }
+
+    /// Helper function to push `str` into `header`, only if output for this column/field is
+    /// enabled, and adding color, only of color enabled. Column alignment is derived by `Table`
+    /// from column contents
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - Row that represents a header in a `Table`
+    /// * `output_kind` - Kind of output (sections/segments/etc)
+    /// * `field` - Column/field bitmask
+    /// * `str` - Column name
+    /// * `color_fn` - Function/closure to call, if colorful output is enabled
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use OutputKind::*;
+    /// use SymbolTableFields::*;
+    ///
+    /// let mut header = Row::default();
+    ///
+    /// self.push_into_header_color(
+    ///     &mut header,
+    ///     Symbols, Size as u32,
+    ///     "Size ",
+    ///     |s| {
+    ///         s.push_attr(Attribute::TextBold);
+    ///     }
+    /// );
+    ///
+    /// let mut table = Table::with_header(header);
+    /// ```
+    fn push_into_header_color(
+        &self,
+        header:      &mut Row,
+        output_kind: OutputKind,
+        field:       u32,
+        str:         &str,
+        color_fn:    impl Fn(&mut AttributeString)
+    ) {
+        self.push_into_row_color(header, output_kind, field, str, color_fn);
+    }
+
+    /// Helper function to push `str` into `row` only if output for this column/field is enabled,
+    /// and adding color, only of color enabled
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - Row to append column value to
+    /// * `output_kind` - Kind of output (sections/segments/etc)
+    /// * `field` - Column/field bitmask
+    /// * `str` - Column value
+    /// * `color_fn` - Function/closure to call, if colorful output is enabled
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let table: Table = ...;
+    /// let mut row = Row::default();
+    ///
+    /// self.push_into_row_color(
+    ///     &mut row,
+    ///     Symbols, Name as u32,
+    ///     format!("{} ", sym.name).as_str(),
+    ///     |s| {
+    ///         s.push_attr(Attribute::TextBold)
+    ///     }
+    /// );
+    ///
+    /// table.push_row(row).unwrap();
+    /// ```
+    ///
+    fn push_into_row_color(
+        &self,
+        row: &mut Row,
+        output_kind: OutputKind,
+        field: u32,
+        str: &str,
+        color_fn: impl Fn(&mut AttributeString)
+    ) {
+        if !self.output.field_enabled(output_kind, field) {
+            return;
+        }
+
+        let mut attr_str = AttributeString::from(str);
+
+        if self.color {
+            color_fn(&mut attr_str);
+        }
+
+        row.push(attr_str);
+    }
+
+    /// Helper function to push `str` into `row` only if output for this column/field is enabled
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - Row to append column value to
+    /// * `output_kind` - Kind of output (sections/segments/etc)
+    /// * `field` - Column/field bitmask
+    /// * `str` - Column value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let table: Table = ...;
+    /// let mut row = Row::default();
+    ///
+    /// self.push_into_row(
+    ///     &mut row,
+    ///     Symbols, Name as u32,
+    ///     format!("{} ", sym.name).as_str()
+    /// );
+    ///
+    /// table.push_row(row).unwrap();
+    /// ```
+    fn push_into_row(
+        &self,
+        row: &mut Row,
+        output_kind: OutputKind,
+        field: u32,
+        str: &str
+    ) {
+        if !self.output.field_enabled(output_kind, field) {
+            return;
+        };
+
+        row.push(AttributeString::from(str));
+    }
+
+    /// Creates a header row from column names, that aren't controlled by output fields
+    fn plain_header(&self, names: &[&str]) -> Row {
+        let mut header = Row::default();
+
+        for name in names {
+            header.push(self.colored_str(name.to_string(), attr_apply_bold));
+        }
+
+        header
+    }
+
+    /// Creates a table with given header, and applies table-related options to it
+    fn new_table(&self, header: Row) -> Table {
+        let mut table = Table::with_header(header);
+
+        table.set_max_rows(self.max_rows);
+        table.set_truncate(self.truncate);
+
+        if self.color {
+            table.set_stripe(self.theme_stripe.clone());
+        }
+
+        table
+    }
+
+    /// Returns color of theme `heat` gradient at `ratio` (0.0 - coldest, 1.0 - hottest), if set
+    fn heat_color(&self, ratio: f32) -> Option<Attribute> {
+        match self.theme_heat.as_slice() {
+            [cold, hot] => cold.blend(hot, ratio),
+            _           => None,
+        }
+    }
+
+    /// Highlights `row` with theme `highlight` attributes, if color is enabled
+    fn highlight_row(&self, row: &mut Row) {
+        if self.color {
+            for attr in &self.theme_highlight {
+                row.push_attr(attr.clone());
+            }
+        }
+    }
+
+    /// Creates a table for output `kind` - same as `new_table()`, but row limit for this kind
+    /// (from `limits`) is applied, if set
+    fn new_table_for(&self, kind: OutputKind, header: Row) -> Table {
+        let mut table = self.new_table(header);
+
+        if let Some(rows) = self.limits.get(&kind) {
+            table.set_max_rows(*rows);
+        }
+
+        table
+    }
+
+    /// Creates a table of symbols for output `kind`. If the table doesn't fit into terminal,
+    /// symbol names are the first to be shortened, while bars are never shortened
+    fn new_symbols_table(&self, kind: OutputKind) -> Table {
+        use SymbolTableFields::*;
+
+        let header = self.symbols_header(kind);
+        let columns = header.len();
+
+        let mut table = self.new_table_for(kind, header);
+
+        // Bar goes after size, delta, retained & percentage columns
+        if self.output.field_enabled(kind, Bar as u32) {
+            let column = [Size as u32, Retained as u32, Percent as u32].into_iter()
+                .filter(|field| self.output.field_enabled(kind, *field))
+                .count() + self.delta_shown(kind, Delta as u32) as usize;
+
+            table.set_constraint(column, table::Constraint { min: Some(usize::MAX), ..Default::default() });
+        }
+
+        // Name is always the last column
+        if self.output.field_enabled(kind, Name as u32) {
+            table.set_constraint(columns - 1, table::Constraint { min: Some(24), priority: 0, ..Default::default() });
+        }
+
+        table
+    }
+
+    /// Returns `true`, if `section` is excluded by `--exclude-section`
+    fn section_excluded(&self, section: &exe::Section) -> bool {
+        self.exclude_sections.as_ref().is_some_and(|re| re.is_match(&section.name))
+    }
+
+    /// Returns file size, that percentages are calculated against - without sections, that
+    /// are excluded by `--exclude-section` (and take up space in file)
+    fn file_size(&self) -> usize {
+        let excluded = self.exe.sections.iter()
+            .filter(|s| s.offset.is_some() && self.section_excluded(s))
+            .fold(0, |r, s| r + s.size);
+
+        self.exe.file_size.saturating_sub(excluded)
+    }
+
+    /// Calculates percentage of symbol size against base, selected by `percent_base`
+    ///
+    /// # Arguments
+    ///
+    /// * `sym` - Symbol
+    /// * `total` - Total size of filtered symbols
+    ///
+    fn symbol_percentage(&self, sym: &Symbol, total: usize) -> f32 {
+        let base = match self.percent_base {
+            PercentBase::Symbols  => total,
+            PercentBase::FileSize => self.file_size(),
+            PercentBase::Section  => self.exe.section_of(sym).map_or(0, |s| s.size),
+        };
+
+        if base == 0 {
+            return 0.0;
+        }
+
+        sym.size as f32 / (base as f32 / 100.0)
+    }
+
+    /// Creates a header for a table of symbols. `kind` is an output, that the table belongs to,
+    /// its columns are controlled by `SymbolTableFields`
+    fn symbols_header(&self, kind: OutputKind) -> Row {
+        use SymbolTableFields::*;
+
+        let mut header = Row::default();
+
+        self.push_into_header_color(
+            &mut header,
+            kind, Size as u32,
+            "Size ",
+            attr_apply_bold
+        );
+
+        if self.previous.is_some() {
+            self.push_into_header_color(
+                &mut header,
+                kind, Delta as u32,
+                "Δ ",
+                attr_apply_bold
+            );
+        }
+
+        self.push_into_header_color(
+            &mut header,
+            kind, Retained as u32,
+            "Retained ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            kind, Percent as u32,
+            "Percentage ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            kind, Bar as u32,
+            " ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            kind, Kind as u32,
+            "Symbol Kind ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            kind, Lang as u32,
+            "Language ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            kind, Crate as u32,
+            "Crate Name ",
+            attr_apply_bold
+        );
+
+        for column in self.columns.iter() {
+            self.push_into_header_color(
+                &mut header,
+                kind, Derived as u32,
+                format!("{} ", column.name).as_str(),
+                attr_apply_bold
+            );
+        }
+
+        self.push_into_header_color(
+            &mut header,
+            kind, Name as u32,
+            "Symbol Name ",
+            attr_apply_bold
+        );
+
+        header
+    }
+
+    /// Creates a row for a table of symbols. `kind` is an output, that the table belongs to,
+    /// its columns are controlled by `SymbolTableFields`. `largest` is the size of the largest
+    /// symbol in the table, which gets a full bar in `Bar` column
+    fn symbol_row(&self, kind: OutputKind, sym: &Symbol, percentage: f32, largest: usize) -> Row {
+        use SymbolTableFields::*;
+
+        let mut row = Row::default();
+
+        let retained = self.retained.get(&sym.addr).copied();
+
+        // Dominators report is about retained sizes, so bars are too
+        let bar_size = match kind {
+            OutputKind::Dominators => retained.unwrap_or(sym.size),
+            _                      => sym.size,
+        };
+
+        self.push_into_row_color(
+            &mut row,
+            kind, Size as u32,
+            format!("{} ", util::format_size(sym.size)).as_str(),
+            |s| {
+                if let Some(color) = self.heat_color(sym.size as f32 / self.size_threshold_red as f32) {
+                    s.push_attr(color);
+                } else if sym.size >= self.size_threshold_red {
+                    s.push_attr(Attribute::ColorFgRed);
+                } else if sym.size >= self.size_threshold_yellow {
+                    s.push_attr(Attribute::ColorFgYellow);
+                } else {
+                    s.push_attr(Attribute::ColorFgGreen);
+                }
+            }
+        );
+
+        if self.delta_shown(kind, Delta as u32) {
+            row.push(self.delta_str(self.symbol_deltas.get(&sym.addr).copied().unwrap_or(0)));
+        }
+
+        self.push_into_row(
+            &mut row,
+            kind, Retained as u32,
+            retained.map_or("- ".to_string(), |size| format!("{} ", util::format_size(size))).as_str()
+        );
+
+        self.push_into_row_color(
+            &mut row,
+            kind, Percent as u32,
+            format!("{} ", util::format_percent(percentage)).as_str(),
+            |s| {
+                if let Some(color) = self.heat_color(percentage / self.percentage_threshold_red) {
+                    s.push_attr(color);
+                } else if percentage >= self.percentage_threshold_red {
+                    s.push_attr(Attribute::ColorFgRed);
+                } else if percentage >= self.percentage_threshold_yellow {
+                    s.push_attr(Attribute::ColorFgYellow);
+                } else {
+                    s.push_attr(Attribute::ColorFgGreen);
+                }
+            }
+        );
+
+        self.push_into_row(
+            &mut row,
+            kind, Bar as u32,
+            (self.bar(bar_size, largest) + " ").as_str()
+        );
+
+        self.push_into_row_color(
+            &mut row,
+            kind, Kind as u32,
+            format!("{} ", sym.kind).as_str(),
+            |s| {
+                match sym.kind {
+                    SymbolKind::Function => s.push_attr(Attribute::ColorFgMagenta),
+                    SymbolKind::Data     => s.push_attr(Attribute::ColorFgCyan),
+                    SymbolKind::Unknown  => {},
+                }
+            }
+        );
+
+        self.push_into_row(
+            &mut row,
+            kind, Lang as u32,
+            format!("{} ", sym.lang).as_str()
+        );
+
+        self.push_into_row(
+            &mut row,
+            kind, Crate as u32,
+            format!("{} ", sym.crate_name).as_str()
+        );
+
+        let field = |name: &str| match name {
+            "percent" => Some(expr::Value::Number(percentage as f64)),
+            _         => self.symbol_field(sym, name),
+        };
+
+        for column in self.columns.iter() {
+            self.push_into_row(
+                &mut row,
+                kind, Derived as u32,
+                format!("{} ", column.format(&field)).as_str()
+            );
+        }
+
+        let name = self.symbol_name(&sym.name);
+
+        self.push_into_row_color(
+            &mut row,
+            kind, Name as u32,
+            match sym.aliases.len() {
+                0 => format!("{} ", name),
+                1 => format!("{} (+1 alias) ", name),
+                n => format!("{} (+{} aliases) ", name, n),
+            }.as_str(),
+            |s| {
+                s.push_attr(Attribute::TextBold);
+
+                // Generic arguments are dimmed, so the path stands out
+                for range in demangle::generic_args(&name) {
+                    s.push_span(range, &[Attribute::TextDim]);
+                }
+            }
+        );
+
+        // Name is always the last column
+        if self.output.field_enabled(kind, Name as u32) && let Some(link) = self.symbol_link(sym) {
+            let last = row.len() - 1;
+
+            row[last].set_link(link);
+        }
+
+        if sym.size >= self.size_threshold_red || percentage >= self.percentage_threshold_red {
+            self.highlight_row(&mut row);
+        }
+
+        row
+    }
+
+    /// Returns symbol `name`, as it's shown in tables - with generic arguments folded, if
+    /// `--fold-generics` is set
+    fn symbol_name(&self, name: &str) -> String {
+        match self.fold_generics {
+            true  => demangle::fold_generics(name),
+            false => name.to_string(),
+        }
+    }
+
+    /// Builds hyperlink URL of `sym` from `link_template`, if it's set. Hyperlinks are escape
+    /// sequences, so like colors, they're only added if color is enabled
+    fn symbol_link(&self, sym: &Symbol) -> Option<String> {
+        let template = self.link_template.as_ref().filter(|_| self.color)?;
+
+        Some(
+            template
+                .replace("{name}", &util::url_encode(&sym.name))
+                .replace("{crate}", &util::url_encode(&sym.crate_name))
+                .replace("{addr}", &format!("{:x}", sym.addr))
+        )
+    }
+
+    /// Renders a bar for `Bar` column, proportional to `size` against `largest`
+    fn bar(&self, size: usize, largest: usize) -> String {
+        util::bar(if largest == 0 { 0.0 } else { size as f32 / largest as f32 }, BAR_WIDTH)
+    }
+
+    /// Checks whether `Δ` column (`field` of output `kind`) is shown - it needs totals from
+    /// the last run
+    fn delta_shown(&self, kind: OutputKind, field: u32) -> bool {
+        self.previous.is_some() && self.output.field_enabled(kind, field)
+    }
+
+    /// Formats size change `delta` for `Δ` column - growth is red, shrinking is green
+    fn delta_str(&self, delta: i64) -> AttributeString {
+        let str = match delta {
+            0 => "0 ".to_string(),
+            _ => format!("{} ", util::format_int(format!("{:+}", delta))),
+        };
+
+        self.colored_str(str, |s| match delta {
+            0   => s.push_attr(Attribute::TextDim),
+            ..0 => s.push_attr(Attribute::ColorFgGreen),
+            _   => s.push_attr(Attribute::ColorFgRed),
+        })
+    }
+
+    /// Dump symbols into a table
+    fn dump_symbols(&mut self) {
+        use OutputKind::*;
+
+        let total = self.exe.symbols.iter()
+            .filter(|s| self.shown(s))
+            .fold(0, |r, s| r + s.size);
+
+        let largest = self.exe.symbols.iter()
+            .filter(|s| self.shown(s))
+            .map(|s| s.size)
+            .max()
+            .unwrap_or(0);
+
+        // Vector table is reported separately, so it doesn't look like unexplained data
+        let vector_table = self.exe.vector_table();
+
+        if let Some(vectors) = &vector_table {
+            hprintln!(
+                "{}",
+                self.colored_str(
+                    format!(
+                        "Vector table: {} bytes, {} entries at 0x{:x} (not listed below)",
+                        util::format_size(vectors.size), vectors.entries(), vectors.addr
+                    ),
+                    attr_apply_bold
+                )
+            );
+            hprintln!();
+        }
+
+        let mut table = self.new_symbols_table(Symbols);
+
+        let mut others = Symbol {
+            name:       String::new(),
+            crate_name: String::new(),
+            size:       0,
+            addr:       0,
+            kind:       SymbolKind::Unknown,
+            lang:       DemangledSymbolKind::Other,
+            section:    Option::None,
+            global:     false,
+            aliases:    Vec::new(),
+        };
+
+        let mut others_count = 0;
+        let mut others_delta = 0;
+
+        for sym in &self.exe.symbols {
+            if sym.size == 0 {
+                continue;
+            }
+
+            if !self.shown(sym) {
+                continue;
+            }
+
+            if vector_table.as_ref().is_some_and(|v| v.contains(sym)) {
+                continue;
+            }
+
+            if self.collapsed(sym.size, total) {
+                others.size += sym.size;
+                others_count += 1;
+                others_delta += self.symbol_deltas.get(&sym.addr).copied().unwrap_or(0);
+                continue;
+            }
+
+            table.push_row(self.symbol_row(Symbols, sym, self.symbol_percentage(sym, total), largest)).unwrap();
+        }
+
+        if others_count != 0 {
+            others.name = format!("… {} other symbols", others_count);
+
+            let mut row = self.symbol_row(Symbols, &others, self.symbol_percentage(&others, total), largest);
+
+            // Collapsed symbols aren't in the last run under this name, so their changes are summed up
+            if self.delta_shown(Symbols, SymbolTableFields::Delta as u32) {
+                row[self.output.field_enabled(Symbols, SymbolTableFields::Size as u32) as usize] = self.delta_str(others_delta);
+            }
+
+            // Goes into footer, so the total of collapsed symbols isn't cut by row limit
+            table.set_footer(row).unwrap();
+        }
+
+        table.print();
+
+        hprintln!();
+
+        let mut fn_count = 0;
+        let mut fn_total = 0;
+
+        let mut data_count = 0;
+        let mut data_total = 0;
+
+        for sym in &self.exe.symbols {
+            match sym.kind {
+                SymbolKind::Function => {
+                    fn_count += 1;
+                    fn_total += sym.size;
+                }
+                SymbolKind::Data => {
+                    data_count += 1;
+                    data_total += sym.size;
+                }
+                _ => {}
+            }
+        }
+
+        let mut totals_table = Table::with_empty_header(5);
+
+        let mut row = Row::default();
+
+        row.push("Functions: ".into());
+        row.push(self.colored_str(format!("{} ", util::format_int(fn_count)), attr_apply_bold));
+        row.push("symbols, ".into());
+        row.push(self.colored_str(format!("{} ", util::format_size(fn_total)), attr_apply_bold));
+        row.push("bytes".into());
+
+        totals_table.push_row(row).unwrap();
+
+        row = Row::default();
+
+        row.push("Data: ".into());
+        row.push(self.colored_str(format!("{} ", util::format_int(data_count)), attr_apply_bold));
+        row.push("symbols, ".into());
+        row.push(self.colored_str(format!("{} ", util::format_size(data_total)), attr_apply_bold));
+        row.push("bytes".into());
+
+        totals_table.push_row(row).unwrap();
+
+        row = Row::default();
+
+        row.push("Total: ".into());
+        row.push(self.colored_str(format!("{} ", util::format_int(self.exe.symbols.len())), attr_apply_bold));
+        row.push("symbols, ".into());
+        row.push(self.colored_str(format!("{} ", util::format_size(total)), attr_apply_bold));
+        row.push("bytes".into());
+
+        totals_table.push_row(row).unwrap();
+
+        // Per-language summary goes along with language column
+        if self.output.field_enabled(Symbols, SymbolTableFields::Lang as u32) {
+            for lang in DemangledSymbolKind::ALL {
+                let (count, size) = self.exe.symbols.iter()
+                    .filter(|s| s.lang == lang)
+                    .fold((0, 0), |(count, size), s| (count + 1, size + s.size));
+
+                if count == 0 {
+                    continue;
+                }
+
+                row = Row::default();
+
+                row.push(format!("{}: ", lang).as_str().into());
+                row.push(self.colored_str(format!("{} ", util::format_int(count)), attr_apply_bold));
+                row.push("symbols, ".into());
+                row.push(self.colored_str(format!("{} ", util::format_size(size)), attr_apply_bold));
+                row.push("bytes".into());
+
+                totals_table.push_row(row).unwrap();
+            }
+        }
+
+        totals_table.print();
+    }
+
+    /// Sorts `(name, size)` pairs by size, if sorting order was specified
+    fn sort_sizes<T>(&self, items: &mut [(T, usize)]) {
+        if let Some(order) = self.symbols_sorting_order {
+            items.sort_by(|a, b| match order {
+                SortOrder::Ascending  => a.1.cmp(&b.1),
+                SortOrder::Descending => a.1.cmp(&b.1).reverse(),
+            });
+        }
+    }
+
+    /// Returns `true` if item of `size` should be rolled into "other" row of a table, whose
+    /// items sum up to `total`
+    fn collapsed(&self, size: usize, total: usize) -> bool {
+        match self.collapse_below {
+            Some(budget::Limit::Bytes(bytes))     => size < bytes,
+            Some(budget::Limit::Percent(percent)) => (size as f64) < total as f64 * percent as f64 / 100.0,
+            None                                  => false,
+        }
+    }
+
+    /// Returns sorting keys for sections & segments tables: `sort_keys` if set, otherwise size
+    /// in `symbols_sorting_order`. Empty, if no sorting was requested
+    fn table_sort_keys(&self) -> Vec<SortKey> {
+        if !self.sort_keys.is_empty() {
+            return self.sort_keys.clone();
+        }
+
+        self.symbols_sorting_order
+            .map(|order| vec![SortKey { field: "size".to_string(), order }])
+            .unwrap_or_default()
+    }
+
+    /// Dump crate sizes into a table
+    fn dump_crates(&mut self) {
+        use OutputKind::*;
+        use CrateTableFields::*;
+
+        hprintln!();
+
+        let mut crates = self.exe.crate_sizes();
+
+        self.sort_sizes(&mut crates);
+
+        let mut header = Row::default();
+
+        self.push_into_header_color(
+            &mut header,
+            Crates, Name as u32,
+            "Crate Name ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Crates, Size as u32,
+            "Size ",
+            attr_apply_bold
+        );
+
+        if self.previous.is_some() {
+            self.push_into_header_color(
+                &mut header,
+                Crates, Delta as u32,
+                "Δ ",
+                attr_apply_bold
+            );
+        }
+
+        self.push_into_header_color(
+            &mut header,
+            Crates, Count as u32,
+            "Symbols ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Crates, Average as u32,
+            "Average ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Crates, Flash as u32,
+            "Flash ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Crates, CrateTableFields::Ram as u32,
+            "RAM ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Crates, Bar as u32,
+            " ",
+            attr_apply_bold
+        );
+
+        let mut table = self.new_table_for(Crates, header);
+
+        let largest = crates.iter().map(|(_, size)| *size).max().unwrap_or(0);
+        let total = crates.iter().map(|(_, size)| *size).sum::<usize>();
+
+        let mut counts = HashMap::<&str, usize>::new();
+
+        for sym in self.exe.symbols.iter().filter(|s| s.size != 0) {
+            *counts.entry(sym.crate_name.as_str()).or_insert(0) += 1;
+        }
+
+        let memory = self.exe.crate_memory_sizes();
+
+        // `None` - there is no last run to compare with
+        let (crates, others) = crates.into_iter()
+            .map(|(name, size)| {
+                let delta = self.previous.as_ref().map(|p| p.crate_delta(&name, size));
+                let count = counts.get(name.as_str()).copied().unwrap_or(0);
+                let (flash, ram) = memory.get(&name).copied().unwrap_or_default();
+                (name, size, delta, count, flash, ram)
+            })
+            .partition::<Vec<_>, _>(|(_, size, ..)| !self.collapsed(*size, total));
+
+        // Collapsed crates aren't in the last run under this name, so their changes are summed up
+        let others = (!others.is_empty()).then(|| (
+            format!("… {} other crates", others.len()),
+            others.iter().map(|(_, size, ..)| *size).sum(),
+            self.previous.as_ref().map(|_| others.iter().filter_map(|(_, _, delta, ..)| *delta).sum()),
+            others.iter().map(|(_, _, _, count, ..)| *count).sum(),
+            others.iter().map(|(.., flash, _)| *flash).sum(),
+            others.iter().map(|(.., ram)| *ram).sum(),
+        ));
+
+        let shown = crates.len();
+
+        // Collapsed crates go into footer, so their total isn't cut by row limit
+        for (i, (name, size, delta, count, flash, ram)) in crates.into_iter().chain(others).enumerate() {
+            let mut row = Row::default();
+
+            self.push_into_row(
+                &mut row,
+                Crates, Name as u32,
+                (name.clone() + " ").as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Crates, Size as u32,
+                format!("{} ", util::format_size(size)).as_str()
+            );
+
+            if let Some(delta) = delta && self.output.field_enabled(Crates, Delta as u32) {
+                row.push(self.delta_str(delta));
+            }
+
+            self.push_into_row(
+                &mut row,
+                Crates, Count as u32,
+                format!("{} ", util::format_int(count)).as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Crates, Average as u32,
+                format!("{} ", util::format_size(size.checked_div(count).unwrap_or(0))).as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Crates, Flash as u32,
+                format!("{} ", util::format_size(flash)).as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Crates, CrateTableFields::Ram as u32,
+                format!("{} ", util::format_size(ram)).as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Crates, Bar as u32,
+                (self.bar(size, largest) + " ").as_str()
+            );
+
+            if i < shown {
+                table.push_row(row).unwrap();
+            } else {
+                table.set_footer(row).unwrap();
+            }
+        }
+
+        table.print();
+    }
+
+    /// Calculates summary statistics over symbols, filtered by `filter`
+    fn stats(&self) -> exe::Stats {
+        let symbols = self.exe.symbols.iter()
+            .filter(|s| s.size != 0 && self.shown(s))
+            .collect::<Vec<_>>();
+
+        exe::Stats::new(&symbols, self.exe.file_size)
+    }
+
+    /// Dump summary statistics into a table
+    fn dump_stats(&mut self) {
+        hprintln!();
+
+        let mut table = self.new_table_for(OutputKind::Stats, self.plain_header(&["Statistic ", "Value "]));
+
+        for (name, value) in self.stats().entries() {
+            let mut row = Row::default();
+
+            row.push(AttributeString::from(format!("{} ", name).as_str()));
+            // Counts are never in hex. Mean is the only fractional statistic, and it's left as is
+            let value = match (name, value.parse::<usize>()) {
+                ("symbols" | "functions" | "data" | "crates", Ok(count)) => util::format_int(count),
+                (_, Ok(size))                                            => util::format_size(size),
+                (_, Err(_))                                              => value,
+            };
+
+            row.push(self.colored_str(format!("{} ", value), attr_apply_bold));
+
+            table.push_row(row).unwrap();
+        }
+
+        table.print();
+    }
+
+    /// Dump symbols, imported from shared libraries, grouped by library
+    fn dump_imports(&mut self) {
+        hprintln!();
+
+        let mut imports = self.exe.imports.iter().collect::<Vec<_>>();
+        imports.sort_by(|a, b| (&a.library, &a.name).cmp(&(&b.library, &b.name)));
+
+        let mut table = self.new_table_for(OutputKind::Imports, self.plain_header(&["Library ", "Imported Symbol "]));
+
+        for import in imports.iter() {
+            table.push_row([
+                format!("{} ", if import.library.is_empty() { "?" } else { import.library.as_str() }),
+                format!("{} ", import.name),
+            ].into()).unwrap();
+        }
+
+        table.print();
+
+        let libraries = imports.iter()
+            .map(|i| i.library.as_str())
+            .filter(|library| !library.is_empty())
+            .collect::<std::collections::HashSet<_>>()
+            .len();
+
+        hprintln!();
+        hprintln!("Imports: {} symbols from {} libraries", imports.len(), libraries);
+    }
+
+    /// Dump symbols, exported for dynamic linking, with their sizes
+    fn dump_exports(&mut self) {
+        hprintln!();
+
+        let mut exports = self.exe.exports.iter()
+            .map(|e| (e, e.size))
+            .collect::<Vec<_>>();
+
+        self.sort_sizes(&mut exports);
+
+        let mut table = self.new_table_for(OutputKind::Exports, self.plain_header(&["Size ", "Address ", "Exported Symbol "]));
+
+        for (export, size) in exports.iter() {
+            table.push_row([
+                format!("{} ", util::format_size(*size)),
+                format!("0x{:08x} ", export.addr),
+                format!("{} ", export.name),
+            ].into()).unwrap();
+        }
+
+        table.print();
+
+        hprintln!();
+        hprintln!(
+            "Exports: {} symbols, {} bytes",
+            util::format_int(exports.len()),
+            util::format_size(exports.iter().map(|(_, size)| size).sum::<usize>())
+        );
+    }
+
+    /// Dump unmangled (`#[no_mangle]`/`extern "C"`) symbols with global binding, which make up C ABI
+    /// surface of the binary, marking ones, that are exported dynamically
+    fn dump_c_abi(&mut self) {
+        hprintln!();
+
+        let exported = self.exe.exports.iter()
+            .map(|e| e.name.as_str())
+            .collect::<std::collections::HashSet<_>>();
+
+        let mut symbols = self.exe.symbols.iter()
+            // Imports are global too, but aren't defined in any section
+            .filter(|s| s.global && s.lang == DemangledSymbolKind::C && s.section.is_some())
+            .map(|s| (s, s.size))
+            .collect::<Vec<_>>();
+
+        self.sort_sizes(&mut symbols);
+
+        let mut table = self.new_table_for(
+            OutputKind::CAbi,
+            self.plain_header(&["Size ", "Address ", "Kind ", "Dynamic ", "C ABI Symbol "])
+        );
+
+        for (sym, size) in symbols.iter() {
+            table.push_row([
+                format!("{} ", util::format_size(*size)),
+                format!("0x{:08x} ", sym.addr),
+                format!("{} ", sym.kind),
+                format!("{} ", if exported.contains(sym.name.as_str()) { "yes" } else { "no" }),
+                format!("{} ", sym.name),
+            ].into()).unwrap();
+        }
+
+        table.print();
+
+        hprintln!();
+        hprintln!(
+            "C ABI: {} symbols ({} exported dynamically), {} bytes",
+            util::format_int(symbols.len()),
+            util::format_int(symbols.iter().filter(|(s, _)| exported.contains(s.name.as_str())).count()),
+            util::format_size(symbols.iter().map(|(_, size)| size).sum::<usize>())
+        );
+    }
+
+    /// Dump shared libraries, that executable depends on at run time, with number of imports
+    /// from each, and estimated size of import stubs
+    fn dump_libraries(&mut self) {
+        use LibraryTableFields::*;
+
+        hprintln!();
+
+        let mut header = Row::default();
+
+        self.push_into_header_color(&mut header, OutputKind::Libraries, Name as u32, "Library ", attr_apply_bold);
+        self.push_into_header_color(&mut header, OutputKind::Libraries, Imports as u32, "Imports ", attr_apply_bold);
+        self.push_into_header_color(&mut header, OutputKind::Libraries, Stubs as u32, "Stub Size ", attr_apply_bold);
+
+        let mut table = self.new_table_for(OutputKind::Libraries, header);
+
+        for library in self.exe.library_usage() {
+            let mut row = Row::default();
+
+            self.push_into_row(&mut row, OutputKind::Libraries, Name as u32, format!("{} ", library.name).as_str());
+            // Unknown numbers are dashes, so they aren't mistaken for real zeros
+            self.push_into_row(
+                &mut row,
+                OutputKind::Libraries, Imports as u32,
+                format!("{} ", library.imports.map(util::format_int).unwrap_or("-".to_string())).as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                OutputKind::Libraries, Stubs as u32,
+                format!("{} ", library.stub_size.map(util::format_size).unwrap_or("-".to_string())).as_str()
+            );
+
+            table.push_row(row).unwrap();
+        }
+
+        table.print();
+    }
+
+    /// Calculates total size of crates in each `CrateCategory`, using `cargo metadata` to tell
+    /// local crates from dependencies. Without metadata (e.g. analyzing a file outside of cargo
+    /// project), only `std` crates are recognized
+    fn category_sizes(&self) -> Vec<(cargo::CrateCategory, usize)> {
+        use cargo::CrateCategory::*;
+
+        let mut categories = vec![(Local, 0), (Dependency, 0), (Std, 0), (Unknown, 0)];
+
+        for (name, size) in self.exe.crate_sizes() {
+            let category = self.metadata.category(name.as_str());
+
+            if let Some((_, total)) = categories.iter_mut().find(|(c, _)| *c == category) {
+                *total += size;
+            }
+        }
+
+        categories
+    }
+
+    /// Dump breakdown of shown symbols by kind of compiler-generated code (drop glue, closures,
+    /// etc.), so overhead of generated code can be told apart from code, written by hand
+    fn dump_generated(&mut self) {
+        hprintln!();
+
+        let mut kinds = GeneratedKind::ALL.map(|kind| (kind, 0, 0));
+
+        for sym in self.exe.symbols.iter().filter(|s| s.size != 0 && self.shown(s)) {
+            let kind = demangle::generated_kind(&sym.name);
+
+            if let Some((_, count, size)) = kinds.iter_mut().find(|(k, _, _)| *k == kind) {
+                *count += 1;
+                *size += sym.size;
+            }
+        }
+
+        let total = kinds.iter().fold(0, |r, (_, _, size)| r + size);
+        let percentage = |size: usize| if total == 0 { 0.0 } else { size as f32 / (total as f32 / 100.0) };
+
+        let mut table = self.new_table_for(
+            OutputKind::Generated,
+            self.plain_header(&["Generated Code ", "Symbols ", "Size ", "Percentage "])
+        );
+
+        for (kind, count, size) in kinds {
+            table.push_row(Row::from([
+                format!("{} ", kind),
+                format!("{} ", util::format_int(count)),
+                format!("{} ", util::format_size(size)),
+                format!("{} ", util::format_percent(percentage(size))),
+            ])).unwrap();
+        }
+
+        table.set_footer(Row::from([
+            "Total ".to_string(),
+            format!("{} ", util::format_int(kinds.iter().fold(0, |r, (_, count, _)| r + count))),
+            format!("{} ", util::format_size(total)),
+            format!("{} ", util::format_percent(percentage(total))),
+        ])).unwrap();
+
+        table.print();
+    }
+
+    /// Dump high-level breakdown of size into local crates, dependencies & std
+    fn dump_categories(&mut self) {
+        use OutputKind::*;
+        use CategoryTableFields::*;
+
+        hprintln!();
+
+        let categories = self.category_sizes();
+
+        let total = categories.iter().fold(0, |r, (_, size)| r + size);
+
+        let mut header = Row::default();
+
+        self.push_into_header_color(
+            &mut header,
+            Categories, Name as u32,
+            "Category ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Categories, Size as u32,
+            "Size ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Categories, Percent as u32,
+            "Percentage ",
+            attr_apply_bold
+        );
+
+        let mut table = self.new_table_for(Categories, header);
+
+        for (category, size) in categories {
+            let mut row = Row::default();
+
+            self.push_into_row(
+                &mut row,
+                Categories, Name as u32,
+                format!("{} ", category).as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Categories, Size as u32,
+                format!("{} ", util::format_size(size)).as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Categories, Percent as u32,
+                format!("{} ", util::format_percent(size as f32 / (total.max(1) as f32 / 100.0))).as_str()
+            );
+
+            table.push_row(row).unwrap();
+        }
+
+        table.print();
+    }
+
+    /// Dump sections into a table
+    fn dump_sections(&mut self) {
+        use OutputKind::*;
+        use SectionTableFields::*;
+
+        hprintln!();
+
+        let mut header = Row::default();
+
+        self.push_into_header_color(
+            &mut header,
+            Sections, Name as u32,
+            "Name ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Sections, Addr as u32,
+            "Address ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Sections, Offset as u32,
+            "Offset ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Sections, Size as u32,
+            "Size ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Sections, Percent as u32,
+            "Percentage ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Sections, FilePercent as u32,
+            "File Percentage ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Sections, Coverage as u32,
+            "Coverage ",
+            attr_apply_bold
+        );
+
+        let mut table = self.new_table_for(Sections, header);
+        let mut unattributed = Vec::new();
+
+        // Debug info & metadata would dwarf everything else, so percentage is of code & data only
+        let total = self.exe.sections.iter().filter(|s| s.has_symbols()).fold(0, |r, s| r + s.size);
+
+        // Sections are referenced by index from symbols, so only the order of output is changed
+        let keys = self.table_sort_keys();
+        let mut order = (0..self.exe.sections.len()).collect::<Vec<_>>();
+
+        order.sort_by(|a, b| SortKey::compare(
+            &keys, &self.exe.sections[*a], &self.exe.sections[*b], |field, a, b| a.compare(b, field)
+        ));
+
+        for index in order.into_iter().filter(|i| !self.section_excluded(&self.exe.sections[*i])) {
+            let section = &self.exe.sections[index];
+            let mut row = Row::default();
+
+            self.push_into_row(
+                &mut row,
+                Sections, Name as u32,
+                (section.name.clone() + " ").as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Sections, Addr as u32,
+                format!("0x{:08x} ", section.addr).as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Sections, Offset as u32,
+                section.offset.map_or("- ".to_string(), |offset| format!("0x{:08x} ", offset)).as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Sections, Size as u32,
+                format!("{} ", util::format_size(section.size)).as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Sections, Percent as u32,
+                match section.has_symbols() && total != 0 {
+                    true  => format!("{} ", util::format_percent(section.size as f32 / (total as f32 / 100.0))),
+                    false => "- ".to_string(),
+                }.as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Sections, FilePercent as u32,
+                match section.offset.is_some() && self.file_size() != 0 {
+                    true  => format!("{} ", util::format_percent(section.size as f32 / (self.file_size() as f32 / 100.0))),
+                    false => "- ".to_string(),
+                }.as_str()
+            );
+
+            if self.output.field_enabled(Sections, Coverage as u32) {
+                if section.has_symbols() && section.size != 0 {
+                    let (covered, gaps) = self.exe.section_coverage(index);
+                    let percentage = covered as f32 / section.size as f32 * 100.0;
+
+                    self.push_into_row_color(
+                        &mut row,
+                        Sections, Coverage as u32,
+                        format!("{} ", util::format_percent(percentage)).as_str(),
+                        |s| {
+                            if percentage < 50.0 {
+                                s.push_attr(Attribute::ColorFgRed);
+                            } else if percentage < 90.0 {
+                                s.push_attr(Attribute::ColorFgYellow);
+                            } else {
+                                s.push_attr(Attribute::ColorFgGreen);
+                            }
+                        }
+                    );
+
+                    unattributed.extend(gaps.into_iter().map(|gap| (section.name.as_str(), gap)));
+                } else {
+                    self.push_into_row(&mut row, Sections, Coverage as u32, "- ");
+                }
+            }
+
+            table.push_row(row).unwrap();
+        }
+
+        table.print();
+
+        if self.output.field_enabled(Sections, SectionTableFields::Categories as u32) {
+            self.dump_section_categories();
+        }
+
+        if unattributed.is_empty() {
+            return;
+        }
+
+        // Space, that no symbol accounts for (padding, alignment, literal pools, stripped local
+        // symbols) is invisible in symbol-based tables, so the largest chunks are listed
+        unattributed.sort_by_key(|(_, gap)| std::cmp::Reverse(gap.len()));
+        unattributed.truncate(MAX_UNATTRIBUTED_RANGES);
+
+        hprintln!();
+        hprintln!("{}", self.colored_str("Largest unattributed ranges:".to_string(), attr_apply_bold));
+
+        let mut table = self.new_table_for(Sections, self.plain_header(&["Section ", "Start ", "End ", "Size "]));
+
+        for (name, gap) in unattributed {
+            table.push_row([
+                format!("{} ", name).as_str(),
+                format!("0x{:08x} ", gap.start).as_str(),
+                format!("0x{:08x} ", gap.end).as_str(),
+                format!("{} ", util::format_size(gap.len())).as_str(),
+            ].into()).unwrap();
+        }
+
+        table.print();
+    }
+
+    /// Dump section sizes grouped by category (code, ro-data, etc.). Percentage is of file size,
+    /// so it's not shown for bss, which doesn't take space in the file
+    fn dump_section_categories(&self) {
+        hprintln!();
+        hprintln!("{}", self.colored_str("Section categories:".to_string(), attr_apply_bold));
+
+        let file_size = self.file_size();
+        let percentage = |size: usize| if file_size == 0 { 0.0 } else { size as f32 / (file_size as f32 / 100.0) };
+
+        let mut table = self.new_table_for(OutputKind::Sections, self.plain_header(&["Category ", "Size ", "Percentage "]));
+
+        let mut total = 0;
+
+        for (category, size) in self.exe.section_category_sizes(|s| self.section_excluded(s)) {
+            let percent = match category {
+                exe::SectionCategory::Bss => "- ".to_string(),
+                _                         => format!("{} ", util::format_percent(percentage(size))),
+            };
+
+            if category != exe::SectionCategory::Bss {
+                total += size;
+            }
+
+            table.push_row(Row::from([format!("{} ", category), format!("{} ", util::format_size(size)), percent])).unwrap();
+        }
+
+        table.set_footer(Row::from([
+            "Total (in file) ".to_string(),
+            format!("{} ", util::format_size(total)),
+            format!("{} ", util::format_percent(percentage(total))),
+        ])).unwrap();
+
+        table.print();
+    }
+
+    /// Dump compressed sizes of loaded sections, and of the whole loaded image
+    fn dump_compression(&mut self) {
+        hprintln!();
+
+        if self.compression.is_empty() {
+            hprintln!("{}", self.colored_str("No loaded sections to compress".to_string(), attr_apply_bold));
+            return;
+        }
+
+        let ratio = |compressed: usize, size: usize| if size == 0 { 0.0 } else { compressed as f32 / (size as f32 / 100.0) };
+
+        let mut table = self.new_table_for(
+            OutputKind::Compression,
+            self.plain_header(&["Section ", "Size ", "gzip ", "Ratio ", "zstd ", "Ratio "])
+        );
+
+        let row = |entry: &compress::Compression| Row::from([
+            format!("{} ", entry.name),
+            format!("{} ", util::format_size(entry.size)),
+            format!("{} ", util::format_size(entry.gzip)),
+            format!("{} ", util::format_percent(ratio(entry.gzip, entry.size))),
+            format!("{} ", util::format_size(entry.zstd)),
+            format!("{} ", util::format_percent(ratio(entry.zstd, entry.size))),
+        ]);
+
+        // Last entry is the whole image, so it goes into the footer
+        let (image, sections) = self.compression.split_last().unwrap();
+
+        for entry in sections {
+            table.push_row(row(entry)).unwrap();
+        }
+
+        table.set_footer(row(image)).unwrap();
+
+        table.print();
+    }
+
+    /// Dump object files of an archive (`.rlib`/`.a`) with their sizes, largest first
+    fn dump_members(&mut self) {
+        hprintln!();
+
+        let mut members = self.exe.members.iter().collect::<Vec<_>>();
+
+        members.sort_by_key(|m| std::cmp::Reverse(m.code + m.data));
+
+        let total = members.iter().fold(0, |r, m| r + m.code + m.data);
+        let percentage = |size: usize| if total == 0 { 0.0 } else { size as f32 / (total as f32 / 100.0) };
+
+        let mut table = self.new_table_for(
+            OutputKind::Members,
+            self.plain_header(&["Size ", "Percentage ", "Code ", "Data ", "Symbols ", "File Size ", "Member "])
+        );
+
+        for member in members.iter() {
+            table.push_row(Row::from([
+                format!("{} ", util::format_size(member.code + member.data)),
+                format!("{} ", util::format_percent(percentage(member.code + member.data))),
+                format!("{} ", util::format_size(member.code)),
+                format!("{} ", util::format_size(member.data)),
+                format!("{} ", util::format_int(member.symbols)),
+                format!("{} ", util::format_size(member.file_size)),
+                format!("{} ", member.name),
+            ])).unwrap();
+        }
+
+        table.set_footer(Row::from([
+            format!("{} ", util::format_size(total)),
+            format!("{} ", util::format_percent(percentage(total))),
+            format!("{} ", util::format_size(members.iter().fold(0, |r, m| r + m.code))),
+            format!("{} ", util::format_size(members.iter().fold(0, |r, m| r + m.data))),
+            format!("{} ", util::format_int(members.iter().fold(0, |r, m| r + m.symbols))),
+            format!("{} ", util::format_size(members.iter().fold(0, |r, m| r + m.file_size))),
+            "Total ".to_string(),
+        ])).unwrap();
+
+        table.print();
+    }
+
+    /// Dump cargo dependency tree, annotated with size of each crate, and cumulative size of its
+    /// subtree (crate, that is reachable by several paths, is counted once). Tree starts from the
+    /// package of analyzed artifact, or from all workspace members, if a file was given
+    fn dump_tree(&mut self) {
+        hprintln!();
+
+        let graph = cargo::dependency_graph(&self.build_options)
+            .unwrap_or_else(|e| panic!("Failed to get dependency graph: {}", e));
+
+        let sizes = self.exe.crate_sizes().into_iter().collect::<HashMap<_, _>>();
+        let total = sizes.values().sum::<usize>();
+        let percentage = |size: usize| if total == 0 { 0.0 } else { size as f32 / (total as f32 / 100.0) };
+
+        // Package size is a sum of its targets (lib & bins), that made it into the binary. Several
+        // versions of a package share crate names, so each crate is attributed to a single package,
+        // preferring ones, that workspace members actually depend on
+        let reachable = graph.members.iter()
+            .fold(HashSet::new(), |mut r, member| { r.extend(graph.subtree(*member)); r });
+
+        let mut order = (0..graph.packages.len()).collect::<Vec<_>>();
+        order.sort_by_key(|i| !reachable.contains(i));
+
+        let mut own = vec![0; graph.packages.len()];
+        let mut attributed = HashSet::new();
+
+        for i in order {
+            for name in graph.packages[i].crates.iter() {
+                if attributed.insert(name.as_str()) {
+                    own[i] += sizes.get(name).copied().unwrap_or(0);
+                }
+            }
+        }
+
+        let cumulative = (0..graph.packages.len())
+            .map(|i| graph.subtree(i).into_iter().map(|dep| own[dep]).sum::<usize>())
+            .collect::<Vec<_>>();
+
+        let artifact = self.files.is_empty()
+            .then(|| cargo::select_artifact(&self.artifacts, (!self.artifact.is_empty()).then_some(self.artifact.as_str())).ok())
+            .flatten()
+            .map(|(artifact, _)| artifact.name.clone());
+
+        let mut roots = graph.members.iter()
+            .copied()
+            .filter(|i| artifact.as_ref().is_none_or(|name| graph.packages[*i].crates.contains(name)))
+            .collect::<Vec<_>>();
+
+        roots.sort_by_key(|i| std::cmp::Reverse(cumulative[*i]));
+
+        let mut table = self.new_table(self.plain_header(&["Size ", "Cumulative ", "Percentage ", "Crate "]));
+
+        for (branch, package, repeated) in graph.lines(&roots, &cumulative) {
+            let pkg = &graph.packages[package];
+            let share = percentage(cumulative[package]);
+            let root = branch.is_empty();
+
+            let mut row = Row::default();
+
+            row.push(AttributeString::from(format!("{} ", util::format_size(own[package])).as_str()));
+            row.push(AttributeString::from(format!("{} ", util::format_size(cumulative[package])).as_str()));
+
+            // Roots hold the whole tree, so only branches are colored
+            row.push(self.colored_str(format!("{} ", util::format_percent(share)), |s| {
+                if root {
+                    return;
+                }
+
+                if let Some(color) = self.heat_color(share / 100.0) {
+                    s.push_attr(color);
+                } else if share >= TREE_BRANCH_RED {
+                    s.push_attr(Attribute::ColorFgRed);
+                } else if share >= TREE_BRANCH_YELLOW {
+                    s.push_attr(Attribute::ColorFgYellow);
+                } else {
+                    s.push_attr(Attribute::ColorFgGreen);
+                }
+            }));
+
+            row.push(self.colored_str(
+                format!("{}{} v{}{} ", branch, pkg.name, pkg.version, if repeated { " (*)" } else { "" }),
+                |s| {
+                    if root {
+                        s.push_attr(Attribute::TextBold);
+                    }
+
+                    if repeated {
+                        s.push_attr(Attribute::TextDim);
+                    }
+                }
+            ));
+
+            if !root && !repeated && share >= TREE_BRANCH_RED {
+                self.highlight_row(&mut row);
+            }
+
+            table.push_row(row).unwrap();
+        }
+
+        // Toolchain crates & crates, that couldn't be identified, aren't part of cargo's graph
+        let in_tree = roots.iter()
+            .fold(HashSet::new(), |mut r, root| { r.extend(graph.subtree(*root)); r })
+            .into_iter()
+            .map(|i| own[i])
+            .sum::<usize>();
+
+        let rest = total.saturating_sub(in_tree);
+
+        table.set_footer(Row::from([
+            format!("{} ", util::format_size(rest)),
+            "".to_string(),
+            format!("{} ", util::format_percent(percentage(rest))),
+            "std & unknown crates ".to_string(),
+        ])).unwrap();
+
+        table.print();
+    }
+
+    /// Dump sizes of debug information sections, and how much stripping would save
+    fn dump_debug(&mut self) {
+        hprintln!();
+
+        let file_size = self.exe.file_size;
+        let percentage = |size: usize| if file_size == 0 { 0.0 } else { size as f32 / (file_size as f32 / 100.0) };
+
+        let mut sections = self.exe.sections.iter()
+            .filter(|s| s.kind == exe::SectionKind::Debug)
+            .map(|s| (s.name.as_str(), s.size))
+            .collect::<Vec<_>>();
+
+        if sections.is_empty() {
+            hprintln!("{}", self.colored_str("No debug info".to_string(), attr_apply_bold));
+            return;
+        }
+
+        sections.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+
+        let total = sections.iter().fold(0, |r, (_, size)| r + size);
+
+        let mut table = self.new_table_for(OutputKind::Debug, self.plain_header(&["Section ", "Size ", "Percentage "]));
+
+        for (name, size) in sections {
+            table.push_row(Row::from([
+                format!("{} ", name),
+                format!("{} ", util::format_size(size)),
+                format!("{} ", util::format_percent(percentage(size))),
+            ])).unwrap();
+        }
+
+        table.set_footer(Row::from([
+            "Total ".to_string(),
+            format!("{} ", util::format_size(total)),
+            format!("{} ", util::format_percent(percentage(total))),
+        ])).unwrap();
+
+        table.print();
+
+        let estimate = self.exe.strip_estimate();
+
+        let saved_debug = file_size - estimate.strip_debug;
+        let saved_all = file_size - estimate.strip_all;
+
+        hprintln!();
+        hprintln!(
+            "`strip --strip-debug` would save ~{} bytes, `strip` ~{} bytes ({} of file). Flashed/loaded size is not affected",
+            util::format_size(saved_debug), util::format_size(saved_all), util::format_percent(percentage(saved_all))
+        );
+    }
+
+    /// Dump dynamic relocation statistics - sizes of relocation sections, and number of
+    /// relocations per crate, whose symbols contain patched places
+    fn dump_relocations(&mut self) {
+        hprintln!();
+
+        if self.exe.relocations.is_empty() {
+            hprintln!("{}", self.colored_str("No dynamic relocations".to_string(), attr_apply_bold));
+            return;
+        }
+
+        let file_size = self.file_size();
+        let percentage = |size: usize, of: usize| if of == 0 { 0.0 } else { size as f32 / (of as f32 / 100.0) };
+
+        let total = self.exe.relocations.len();
+        let symbolic = self.exe.relocations.iter().filter(|r| r.symbolic).count();
+
+        hprintln!("{}", self.colored_str(
+            format!(
+                "Dynamic relocations: {} ({} relative, {} symbolic)",
+                util::format_int(total), util::format_int(total - symbolic), util::format_int(symbolic)
+            ),
+            attr_apply_bold
+        ));
+        hprintln!();
+
+        let mut sections = self.exe.relocation_sections();
+
+        sections.retain(|s| !self.section_excluded(s));
+
+        let mut table = self.new_table_for(OutputKind::Relocations, self.plain_header(&["Section ", "Size ", "Percentage "]));
+
+        for section in sections.iter() {
+            table.push_row(Row::from([
+                format!("{} ", section.name),
+                format!("{} ", util::format_size(section.size)),
+                format!("{} ", util::format_percent(percentage(section.size, file_size))),
+            ])).unwrap();
+        }
+
+        let sections_total = sections.iter().fold(0, |r, s| r + s.size);
+
+        table.set_footer(Row::from([
+            "Total ".to_string(),
+            format!("{} ", util::format_size(sections_total)),
+            format!("{} ", util::format_percent(percentage(sections_total, file_size))),
+        ])).unwrap();
+
+        table.print();
+
+        hprintln!();
+
+        let mut table = self.new_table_for(
+            OutputKind::Relocations,
+            self.plain_header(&["Crate Name ", "Relocations ", "Relative ", "Symbolic ", "Percentage "])
+        );
+
+        let mut outside = None;
+
+        for crate_relocs in self.exe.relocations_by_crate() {
+            if crate_relocs.crate_name.is_empty() {
+                outside = Some(crate_relocs);
+                continue;
+            }
+
+            table.push_row(Row::from([
+                format!("{} ", crate_relocs.crate_name),
+                format!("{} ", util::format_int(crate_relocs.count)),
+                format!("{} ", util::format_int(crate_relocs.count - crate_relocs.symbolic)),
+                format!("{} ", util::format_int(crate_relocs.symbolic)),
+                format!("{} ", util::format_percent(percentage(crate_relocs.count, total))),
+            ])).unwrap();
+        }
+
+        // GOT, `.init_array` & co aren't covered by symbols
+        if let Some(outside) = outside {
+            table.set_footer(Row::from([
+                "not in any symbol ".to_string(),
+                format!("{} ", util::format_int(outside.count)),
+                format!("{} ", util::format_int(outside.count - outside.symbolic)),
+                format!("{} ", util::format_int(outside.symbolic)),
+                format!("{} ", util::format_percent(percentage(outside.count, total))),
+            ])).unwrap();
+        }
+
+        table.print();
+    }
+
+    /// Dump best-effort memory usage estimated from segment permissions, similar to GCC's
+    /// `--print-memory-usage`. Used when there is no linker script to get memory regions from
+    fn dump_memory_usage_estimate(&mut self) {
+        hprintln!();
+
+        hprintln!(
+            "{}",
+            self.colored_str("Memory usage (estimated from segments, no linker script given):".to_string(), attr_apply_bold)
+        );
+
+        let mut table = self.new_table_for(OutputKind::Segments, self.plain_header(&["Region ", "Used ", "Start ", "End ", "Segments "]));
+
+        // Non-writable segments (code, constants) are stored in flash and writable segments are
+        // placed in RAM. Initialized part of writable segments also takes space in flash, as it
+        // needs to be copied into RAM at startup
+        let loaded = self.exe.segments.iter()
+            .filter(|s| s.load && s.flags.any())
+            .collect::<Vec<_>>();
+
+        let flash = loaded.iter()
+            .map(|s| (s, if s.flags.write { s.file_size } else { s.size }))
+            .filter(|(_, size)| *size != 0)
+            .collect::<Vec<_>>();
+
+        let ram = loaded.iter()
+            .filter(|s| s.flags.write)
+            .map(|s| (s, s.size))
+            .collect::<Vec<_>>();
+
+        for (name, segments) in [("flash-like ", flash), ("ram-like ", ram)] {
+            if segments.is_empty() {
+                continue;
+            }
+
+            let used = segments.iter().fold(0, |r, (_, size)| r + size);
+            let start = segments.iter().map(|(s, _)| s.addr).min().unwrap_or(0);
+            let end = segments.iter().map(|(s, size)| s.addr + size).max().unwrap_or(0);
+
+            table.push_row(Row::from([
+                name.to_string(),
+                format!("{} ", util::format_size(used)),
+                format!("0x{:08x} ", start),
+                format!("0x{:08x} ", end),
+                format!("{} ", util::format_int(segments.len())),
+            ])).unwrap();
+        }
+
+        table.print();
+    }
+
+    /// Dump raw program headers (segments) into a table
+    fn dump_program_headers(&mut self) {
+        use OutputKind::*;
+        use SegmentTableFields::*;
+
+        hprintln!();
+
+        let mut header = Row::default();
+
+        self.push_into_header_color(
+            &mut header,
+            Segments, Type as u32,
+            "Type ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Segments, Addr as u32,
+            "Address ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Segments, FileSize as u32,
+            "File Size ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Segments, Size as u32,
+            "Size ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Segments, Flags as u32,
+            "Flags ",
+            attr_apply_bold
+        );
+
+        let mut table = self.new_table_for(Segments, header);
+
+        let keys = self.table_sort_keys();
+        let mut segments = self.exe.segments.iter().collect::<Vec<_>>();
+
+        segments.sort_by(|a, b| SortKey::compare(&keys, *a, *b, |field, a, b| a.compare(b, field)));
+
+        for seg in segments {
+            let mut row = Row::default();
+
+            self.push_into_row(
+                &mut row,
+                Segments, Type as u32,
+                (seg.kind.clone() + " ").as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Segments, Addr as u32,
+                format!("0x{:08x} ", seg.addr).as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Segments, FileSize as u32,
+                format!("{} ", util::format_size(seg.file_size)).as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Segments, Size as u32,
+                format!("{} ", util::format_size(seg.size)).as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Segments, Flags as u32,
+                format!("{} ", seg.flags).as_str()
+            );
+
+            table.push_row(row).unwrap();
+        }
+
+        table.print();
+    }
+
+    /// Dump memory regions into a table, if `ld_file` is set, otherwise dump raw program headers
+    /// and estimated memory usage
+    fn dump_segments(&mut self) {
+        use OutputKind::*;
+        use SegmentTableFields::*;
+
+        if self.ld_file.is_empty() {
+            if !self.exe.segments.is_empty() {
+                self.dump_program_headers();
+                self.dump_memory_usage_estimate();
+            }
+
+            return;
+        }
+
+        hprintln!();
+
+        let mut header = Row::default();
+
+        self.push_into_header_color(
+            &mut header,
+            Segments, Name as u32,
+            "Name ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Segments, Addr as u32,
+            "Address ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Segments, Used as u32,
+            "Used ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Segments, Size as u32,
+            "Size ",
+            attr_apply_bold
+        );
+
+        self.push_into_header_color(
+            &mut header,
+            Segments, Percent as u32,
+            "Percentage ",
+            attr_apply_bold
+        );
+
+        let mut table = self.new_table_for(Segments, header);
+
+        for reg in self.load_regions().iter() {
+            let mut row = Row::default();
+
+            self.push_into_row(
+                &mut row,
+                Segments, Name as u32,
+                (reg.name.clone() + " ").as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Segments, Addr as u32,
+                format!("0x{:08x} ", reg.origin).as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Segments, Used as u32,
+                format!("{} ", util::format_size(reg.used)).as_str()
+            );
+
+            self.push_into_row(
+                &mut row,
+                Segments, Size as u32,
+                format!("{} ", util::format_size(reg.length)).as_str()
+            );
+
+            self.push_into_row_color(
+                &mut row,
+                Segments, Percent as u32,
+                format!("{} ", util::format_percent(reg.used_percentage)).as_str(),
+                |s| self.apply_segment_color(s, reg.used_percentage)
+            );
+
+            table.push_row(row).unwrap()
+        }
+
+        table.print();
+    }
+
+    /// Dump static RAM usage (`.data`, `.bss` & other writable sections) per section, crate and
+    /// symbol. Stack & heap, reserved by linker script outside of sections, are shown as separate
+    /// rows. Other space without symbols (e.g. padding, reservations inside sections) is shown
+    /// as unattributed
+    fn dump_ram(&mut self) {
+        hprintln!();
+
+        let ram_sections = self.exe.sections.iter()
+            .filter(|s| s.kind.is_ram() && s.size != 0)
+            .collect::<Vec<_>>();
+
+        let reserved = self.exe.reservations.iter().fold(0, |r, res| r + res.size);
+
+        let total = ram_sections.iter().fold(0, |r, s| r + s.size) + reserved;
+
+        let percentage = |size: usize| if total == 0 { 0.0 } else { size as f32 / (total as f32 / 100.0) };
+
+        hprintln!("{}", self.colored_str(format!("Static RAM usage: {} bytes", util::format_size(total)), attr_apply_bold));
+        hprintln!();
+
+        let mut table = self.new_table_for(OutputKind::Ram, self.plain_header(&["Section ", "Kind ", "Size ", "Percentage "]));
+
+        for section in ram_sections.iter() {
+            table.push_row(Row::from([
+                section.name.clone() + " ",
+                format!("{} ", section.kind),
+                format!("{} ", util::format_size(section.size)),
+                format!("{} ", util::format_percent(percentage(section.size))),
+            ])).unwrap();
+        }
+
+        for reservation in self.exe.reservations.iter() {
+            table.push_row(Row::from([
+                format!("<{}> ", reservation.name),
+                "RESERVED ".to_string(),
+                format!("{} ", util::format_size(reservation.size)),
+                format!("{} ", util::format_percent(percentage(reservation.size))),
+            ])).unwrap();
+        }
+
+        table.print();
+
+        hprintln!();
+
+        let ram_symbols = self.exe.symbols.iter()
+            .filter(|s| s.size != 0 && self.exe.section_of(s).is_some_and(|sec| sec.kind.is_ram()))
+            .collect::<Vec<_>>();
+
+        let mut crates: HashMap<&String, usize> = HashMap::new();
+
+        for sym in ram_symbols.iter() {
+            *crates.entry(&sym.crate_name).or_insert(0) += sym.size;
+        }
+
+        let attributed = crates.values().sum::<usize>();
+
+        let mut crates = crates.into_iter().collect::<Vec<_>>();
+
+        self.sort_sizes(&mut crates);
 
-use std::collections::HashMap;
-use crate::util::SortOrder;
-use crate::cargo::{BuildArtifact, BuildOptions};
-use crate::table::{Padding, Row, Table};
-use crate::exe::{ExecutableInfo, SymbolKind};
-use crate::attr_str::{Attribute, AttributeString};
-use crate::output::{
-    Output,
-    OutputKind,
-    SymbolTableFields,
-    CrateTableFields,
-    SectionTableFields,
-    SegmentTableFields
-};
-
-mod cargo;
-mod exe;
-mod args;
-mod table;
-mod util;
-mod attr_str;
-mod link;
-mod output;
-mod demangle;
-
-/// `binsize` version
-const VERSION: &str = env!("CARGO_PKG_VERSION");
-
-/// `binsize` config file location
-const CONFIG: &str = ".cargo/binsize.toml";
-
-
-/// Helper function for applying styling to column headers
-fn attr_apply_bold(s: &mut AttributeString) {
-    s.push_attr(Attribute::TextBold);
-}
-
-/// `binsize` Application
-struct Binsize {
-    /// Cargo build options
-    build_options: BuildOptions,
-
-    /// Filter for symbol names
-    filter: regex::Regex,
-
-    /// Linker script path with `MEMORY` declaration
-    ld_file: String,
-
-    /// File to parse (if `None` - will try to extract file from `cargo build`)
-    file: String,
-
-    /// Colorful output toggle
-    color: bool,
-
-    /// Max rows to output in tables. 0 - no limit
-    max_rows: usize,
-
-    /// Sorting order of symbols
-    symbols_sorting_order: Option<SortOrder>,
-
-    /// Threshold in percent of total size for symbol to be colored yellow
-    percentage_threshold_yellow: f32,
-
-    /// Threshold in percent of total size for symbol to be colored red
-    percentage_threshold_red: f32,
-
-    /// Threshold in bytes for symbol to be colored yellow
-    size_threshold_yellow: usize,
-
-    /// Threshold in bytes for symbol to be colored red
-    size_threshold_red: usize,
-
-    /// Output control context
-    output: Output,
-
-    /// Build atrifacts
-    artifacts: Vec<BuildArtifact>,
-
-    /// Executable info
-    exe: ExecutableInfo,
-}
-
-impl Default for Binsize {
-    fn default() -> Self {
-        Self {
-            build_options:               Default::default(),
-            filter:                      regex::Regex::new(".+").unwrap(),
-            ld_file:                     "".to_string(),
-            file:                        "".to_string(),
-            color:                       false,
-            max_rows:                    0,
-            output:                      Output::new(),
-            exe:                         Default::default(),
-            artifacts:                   Vec::default(),
-            symbols_sorting_order:       None,
-            size_threshold_yellow:       200,
-            size_threshold_red:          500,
-            percentage_threshold_yellow: 0.5,
-            percentage_threshold_red:    1.0,
-        }
-    }
-}
-
-impl Binsize {
-    /// Create new `binsize` application
-    fn new() -> Self {
-        Default::default()
-    }
-
-    /// Parse config in `.cargo/binsize.toml`, if available
-    fn parse_config(&mut self) {
-        if !matches!(std::fs::exists(CONFIG), Ok(true)) {
-            return;
-        }
-
-        let config = std::fs::read_to_string(CONFIG).expect("Failed to read config file");
-        let cfg = toml::from_str::<toml::Table>(config.as_str()).unwrap();
-
-        if cfg.contains_key("binsize") {
-            let binsize = cfg.get("binsize")
-                .expect("Config file must contain a [binsize] section")
-                .as_table()
-                .expect("[binsize] must be a table]");
-
-            if let Some(toml::Value::Boolean(val)) = binsize.get("color") {
-                self.color = *val;
-            }
-
-            if let Some(toml::Value::String(val)) = binsize.get("profile") {
-                self.build_options.profile = val.clone();
-            }
-
-            if let Some(toml::Value::Array(val)) = binsize.get("output") {
-                for s in val {
-                    let str = s.as_str().expect("Output should be a string");
-
-                    self.output.apply_pattern(str.try_into().unwrap());
-                }
-            }
-
-            if let Some(toml::Value::String(val)) = binsize.get("file") {
-                self.file = val.clone();
-            }
-
-            if let Some(toml::Value::String(val)) = binsize.get("filter") {
-                self.filter = regex::Regex::new(val.as_str()).unwrap();
-            }
-
-            if let Some(toml::Value::String(val)) = binsize.get("ld-file") {
-                self.ld_file = val.clone();
-            }
-
-            if let Some(toml::Value::String(val)) = binsize.get("sort") {
-                match val.as_str() {
-                    "asc" => {
-                        self.symbols_sorting_order = Some(SortOrder::Ascending);
-                    }
-                    "desc" => {
-                        self.symbols_sorting_order = Some(SortOrder::Descending);
-                    }
-                    _ => {
-                        panic!("Invalid value for key 'sort': '{} (possible values: asc, desc)'", val);
-                    }
-                }
-            }
-
-            if let Some(toml::Value::Integer(val)) = binsize.get("max-rows") {
-                self.max_rows = *val as usize;
+        let mut table = self.new_table_for(OutputKind::Ram, self.plain_header(&["Crate Name ", "Size ", "Percentage "]));
+
+        for (name, size) in crates {
+            table.push_row(Row::from([
+                name.clone() + " ",
+                format!("{} ", util::format_size(size)),
+                format!("{} ", util::format_percent(percentage(size))),
+            ])).unwrap();
+        }
+
+        for reservation in self.exe.reservations.iter() {
+            table.push_row(Row::from([
+                format!("<{}> ", reservation.name),
+                format!("{} ", util::format_size(reservation.size)),
+                format!("{} ", util::format_percent(percentage(reservation.size))),
+            ])).unwrap();
+        }
+
+        // Space, that isn't covered by any symbol - usually padding and reservations, declared
+        // as sections
+        if total > attributed + reserved {
+            table.push_row(Row::from([
+                "<unattributed> ".to_string(),
+                format!("{} ", util::format_size(total - attributed - reserved)),
+                format!("{} ", util::format_percent(percentage(total - attributed - reserved))),
+            ])).unwrap();
+        }
+
+        table.print();
+
+        hprintln!();
+
+        let mut table = self.new_symbols_table(OutputKind::Ram);
+
+        let ram_symbols = ram_symbols.into_iter()
+            .filter(|s| self.shown(s))
+            .collect::<Vec<_>>();
+
+        let largest = ram_symbols.iter().map(|s| s.size).max().unwrap_or(0);
+
+        for sym in ram_symbols {
+            table.push_row(self.symbol_row(OutputKind::Ram, sym, percentage(sym.size), largest)).unwrap();
+        }
+
+        table.print();
+    }
+
+    /// Dump thread-local storage usage (`.tdata` & `.tbss`) in total and per symbol. TLS block
+    /// is allocated for each thread, so the total is multiplied by number of threads at run time
+    fn dump_tls(&mut self) {
+        hprintln!();
+
+        let usage = self.exe.tls_usage();
+        let total = usage.total();
+
+        hprintln!("{}", self.colored_str(format!("Thread-local storage: {} bytes per thread", util::format_size(total)), attr_apply_bold));
+        hprintln!();
+
+        let mut table = self.new_table_for(OutputKind::Tls, self.plain_header(&["Kind ", "Size "]));
+
+        table.push_row(Row::from(["initialized (.tdata) ".to_string(), format!("{} ", util::format_size(usage.data))])).unwrap();
+        table.push_row(Row::from(["zero-initialized (.tbss) ".to_string(), format!("{} ", util::format_size(usage.bss))])).unwrap();
+
+        table.print();
+
+        hprintln!();
+
+        let tls_symbols = self.exe.symbols.iter()
+            .filter(|s| s.size != 0 && self.shown(s))
+            .filter(|s| self.exe.section_of(s).is_some_and(|sec| sec.kind == exe::SectionKind::Tls))
+            .collect::<Vec<_>>();
+
+        let percentage = |size: usize| if total == 0 { 0.0 } else { size as f32 / (total as f32 / 100.0) };
+
+        let largest = tls_symbols.iter().map(|s| s.size).max().unwrap_or(0);
+
+        let mut table = self.new_symbols_table(OutputKind::Tls);
+
+        for sym in tls_symbols {
+            table.push_row(self.symbol_row(OutputKind::Tls, sym, percentage(sym.size), largest)).unwrap();
+        }
+
+        table.print();
+    }
+
+    /// Dump symbols sorted by retained size - size, that would go away along with the symbol.
+    /// Percentage is of retained size against total size of symbols
+    fn dump_dominators(&mut self) {
+        hprintln!();
+
+        if self.retained.is_empty() {
+            hprintln!("{}", self.colored_str("No reference graph, retained sizes are unavailable".to_string(), attr_apply_bold));
+            return;
+        }
+
+        let total = self.exe.symbols.iter()
+            .filter(|s| self.shown(s))
+            .fold(0, |r, s| r + s.size);
+
+        let mut symbols = self.exe.symbols.iter()
+            .filter(|s| s.size != 0 && self.shown(s))
+            .map(|s| (s, self.retained.get(&s.addr).copied().unwrap_or(s.size)))
+            .collect::<Vec<_>>();
+
+        symbols.sort_by_key(|(_, retained)| std::cmp::Reverse(*retained));
+
+        let largest = symbols.first().map_or(0, |(_, retained)| *retained);
+
+        let percentage = |size: usize| if total == 0 { 0.0 } else { size as f32 / (total as f32 / 100.0) };
+
+        let mut table = self.new_symbols_table(OutputKind::Dominators);
+
+        for (sym, retained) in symbols {
+            table.push_row(self.symbol_row(OutputKind::Dominators, sym, percentage(retained), largest)).unwrap();
+        }
+
+        table.print();
+    }
+
+    /// Dump symbols, that aren't reachable from entry point or exports, but were kept in the
+    /// binary. These are candidates for removal by `--gc-sections` (or `strip` for exports)
+    fn dump_unreachable(&mut self) {
+        hprintln!();
+
+        let symbols = self.exe.symbols.iter()
+            .filter(|s| s.size != 0 && self.shown(s))
+            .filter(|s| self.unreachable.contains(&s.addr))
+            .collect::<Vec<_>>();
+
+        let total = symbols.iter().fold(0, |r, s| r + s.size);
+
+        hprintln!(
+            "{}",
+            self.colored_str(
+                format!("Unreachable: {} symbols, {} bytes (candidates for --gc-sections)", util::format_int(symbols.len()), util::format_size(total)),
+                attr_apply_bold
+            )
+        );
+
+        if symbols.is_empty() {
+            return;
+        }
+
+        hprintln!("Only direct references are seen, so symbols, used through indirect calls, can be listed");
+        hprintln!();
+
+        let percentage = |size: usize| if total == 0 { 0.0 } else { size as f32 / (total as f32 / 100.0) };
+
+        let largest = symbols.iter().map(|s| s.size).max().unwrap_or(0);
+
+        let mut table = self.new_symbols_table(OutputKind::Unreachable);
+
+        for sym in symbols {
+            table.push_row(self.symbol_row(OutputKind::Unreachable, sym, percentage(sym.size), largest)).unwrap();
+        }
+
+        table.print();
+    }
+
+    /// Dump symbols of each memory region (from `ld_file`) into a separate table
+    fn dump_region_symbols(&mut self) {
+        if self.ld_file.is_empty() {
+            return;
+        }
+
+        for reg in self.load_regions().iter() {
+            hprintln!();
+
+            let (start, end) = reg.bounds();
+
+            hprintln!(
+                "{}",
+                self.colored_str(format!("{} (0x{:08x} - 0x{:08x}):", reg.name, start, end), attr_apply_bold)
+            );
+
+            let mut table = self.new_symbols_table(OutputKind::Regions);
+
+            let symbols = self.exe.symbols.iter()
+                .filter(|s| s.size != 0 && reg.contains(s.addr) && self.shown(s))
+                .collect::<Vec<_>>();
+
+            let largest = symbols.iter().map(|s| s.size).max().unwrap_or(0);
+
+            for sym in symbols {
+                // Percentage is calculated against region size, to show how much of the
+                // region each symbol takes
+                let percentage = if reg.length == 0 {
+                    0.0
+                } else {
+                    sym.size as f32 / (reg.length as f32 / 100.0)
+                };
+
+                table.push_row(self.symbol_row(OutputKind::Regions, sym, percentage, largest)).unwrap();
             }
 
-            if let Some(toml::Value::Array(val)) = binsize.get("size-threshold") {
-                self.size_threshold_yellow = val.get(0)
-                    .expect("Missing first value for key 'size-threshold'")
-                    .as_integer()
-                    .expect("Values for key 'size-threshold' must be an integer")
-                    as usize;
+            table.print();
+        }
+    }
 
-                self.size_threshold_red = val.get(1)
-                    .expect("Missing second value for key 'size-threshold'")
-                    .as_integer()
-                    .expect("Values for key 'size-threshold' must be an integer")
-                    as usize;
+    /// Print estimated file sizes after stripping (see `ExecutableInfo::strip_estimate`)
+    fn dump_strip_estimate(&mut self) {
+        hprintln!();
+        hprintln!("{}", self.colored_str("Estimated file size after stripping:".to_string(), attr_apply_bold));
+
+        let estimate = self.exe.strip_estimate();
+        let file_size = self.exe.file_size;
+
+        let percentage = |size: usize| if file_size == 0 { 0.0 } else { size as f32 / (file_size as f32 / 100.0) };
+
+        let mut table = self.new_table(self.plain_header(&["Command ", "File Size ", "Saved ", "Percentage "]));
+
+        for (command, size) in [("(as is) ", file_size), ("strip --strip-debug ", estimate.strip_debug), ("strip ", estimate.strip_all)] {
+            table.push_row(Row::from([
+                command.to_string(),
+                format!("{} ", util::format_size(size)),
+                format!("{} ", util::format_size(file_size - size)),
+                format!("{} ", util::format_percent(percentage(file_size - size))),
+            ])).unwrap();
+        }
+
+        table.print();
+    }
+
+    /// Print size-reduction suggestions (see `suggest::suggest`)
+    fn dump_suggestions(&mut self) {
+        hprintln!();
+
+        let suggestions = suggest::suggest(&self.exe);
+
+        if suggestions.is_empty() {
+            hprintln!("{}", self.colored_str("No suggestions, nothing obviously bloated".to_string(), attr_apply_bold));
+            return;
+        }
+
+        hprintln!("{}", self.colored_str("Suggestions:".to_string(), attr_apply_bold));
+
+        for suggestion in suggestions {
+            hprintln!();
+            hprintln!(" * {}", self.colored_str(suggestion.finding, |s| s.push_attr(Attribute::ColorFgYellow)));
+            hprintln!("   {}", suggestion.hint);
+        }
+    }
+
+    /// Cross-check section sizes against linker map (`map_file`). Allocated sections are listed
+    /// with sizes from both sources, and differences are highlighted. Input sections, discarded
+    /// by linker, and fill bytes are summarized, as they explain where map & binary differ
+    fn dump_map_check(&mut self) {
+        hprintln!();
+
+        let map = map::LinkerMap::from_file(std::path::Path::new(&self.map_file))
+            .unwrap_or_else(|e| panic!("Failed to parse linker map '{}': {}", self.map_file, e));
+
+        hprintln!("{}", self.colored_str(format!("Linker map cross-check ({})", self.map_file), attr_apply_bold));
+        hprintln!();
+
+        let mut table = self.new_table(self.plain_header(&["Section ", "Binary Size ", "Map Size ", "Diff "]));
+
+        let mut matched = 0;
+        let mut mismatched = 0;
+
+        let mut push_row = |table: &mut Table, name: &str, binary: Option<usize>, mapped: Option<usize>| {
+            let (diff, ok) = match (binary, mapped) {
+                (Some(binary), Some(mapped)) => (format!("{} ", util::format_int(format!("{:+}", binary as i64 - mapped as i64))), binary == mapped),
+                (Some(_), Option::None)      => ("not in map ".to_string(), false),
+                _                            => ("not in binary ".to_string(), false),
+            };
+
+            if ok { matched += 1 } else { mismatched += 1 }
+
+            let size = |size: Option<usize>| size.map(|s| format!("{} ", util::format_size(s))).unwrap_or("- ".to_string());
+
+            let mut row = Row::default();
+
+            row.push(format!("{} ", name).as_str().into());
+            row.push(size(binary).as_str().into());
+            row.push(size(mapped).as_str().into());
+            row.push(self.colored_str(diff, |s| s.push_attr(if ok { Attribute::ColorFgGreen } else { Attribute::ColorFgRed })));
+
+            table.push_row(row).unwrap();
+        };
+
+        // Non-allocated sections (symbol & string tables) are added after linking, so map
+        // doesn't know about them
+        for section in self.exe.sections.iter().filter(|s| s.addr != 0 || map.section(&s.name).is_some()) {
+            push_row(&mut table, &section.name, Some(section.size), map.section(&section.name).map(|s| s.size));
+        }
+
+        // Empty output sections are dropped from the binary, so they aren't a discrepancy
+        for section in map.sections.iter().filter(|s| s.size != 0 && !self.exe.sections.iter().any(|sec| sec.name == s.name)) {
+            push_row(&mut table, &section.name, Option::None, Some(section.size));
+        }
+
+        table.print();
+
+        hprintln!();
+        hprintln!("Matching sections: {}, mismatching: {}", matched, mismatched);
+        hprintln!("Discarded input sections: {} ({} bytes)", map.discarded_count, util::format_size(map.discarded_size));
+        hprintln!("Fill bytes: {}", util::format_size(map.fill));
+    }
+
+    /// Parse memory regions from `ld_file` and calculate their usage
+    fn load_regions(&self) -> Vec<link::MemoryRegion> {
+        // TODO: Shouldn't clone() ld_file
+        let mut regions = link::MemoryRegion::from_file(&self.ld_file.clone().into())
+            .expect("Failed to open LD file");
+
+        link::MemoryRegion::use_segments_data(&mut regions, &self.exe.segments);
+        link::MemoryRegion::use_reservations(&mut regions, &self.exe.reservations);
+
+        regions
+    }
+
+    /// Runs `cargo build` with `options` and returns produced artifacts. With `--no-build`,
+    /// artifacts of the last build are returned instead
+    fn build(&self, options: BuildOptions) -> Vec<BuildArtifact> {
+        if self.no_build {
+            return cargo::last_artifacts(&options).unwrap_or_else(|e| panic!("{}", e));
+        }
+
+        // Run `cargo build` to get freshly compiled executable
+        if let Err(error) = cargo::build(options.clone()) {
+            self.report_build_error(&error);
+            std::process::exit(1);
+        }
+
+        // Run `cargo built --message-format=json` to gather info about artifacts produced
+        // by build
+        let artifacts = cargo::artifacts(options.clone());
+
+        // Failing to save artifacts list only makes `--no-build` fall back to target dir scan
+        if let Err(e) = cargo::save_artifacts(&options, &artifacts) {
+            eprintln!("Warning: Failed to save '{}': {}", cargo::artifacts_file(&options).display(), e);
+        }
+
+        artifacts
+    }
+
+    /// Prints compiler messages & cargo errors of a failed build to stderr, so they don't mix
+    /// with the report. Message headers are styled by level, the rest is printed as rendered by
+    /// compiler
+    fn report_build_error(&self, error: &cargo::BuildError) {
+        eprintln!("{}", self.colored_str("Build failed".to_string(), |s| {
+            s.push_attr(Attribute::TextBold);
+            s.push_attr(Attribute::ColorFgRed);
+        }));
+
+        for diagnostic in error.diagnostics.iter() {
+            let (header, body) = diagnostic.rendered.split_once('\n').unwrap_or((&diagnostic.rendered, ""));
+
+            let color = match diagnostic.level.as_str() {
+                "error" | "error: internal compiler error" => Some(Attribute::ColorFgRed),
+                "warning"                                   => Some(Attribute::ColorFgYellow),
+                "note" | "help"                             => Some(Attribute::ColorFgCyan),
+                _                                           => None,
+            };
+
+            eprintln!();
+            eprintln!("{}", self.colored_str(header.to_string(), |s| {
+                s.push_attr(Attribute::TextBold);
+
+                if let Some(color) = &color {
+                    s.push_attr(color.clone());
+                }
+            }));
+
+            if !body.is_empty() {
+                eprintln!("{}", body);
+            }
+        }
+
+        if !error.stderr.is_empty() {
+            eprintln!();
+            eprintln!("{}", error.stderr);
+        }
+    }
+
+    /// Builds whole workspace, and returns names & paths of all produced binaries. Build
+    /// artifacts are kept, so missing crate names can be patched for each binary
+    fn workspace_binaries(&mut self) -> Vec<(String, String)> {
+        let mut options = self.build_options.clone();
+        options.workspace = true;
+
+        self.artifacts = self.build(options);
+
+        let binaries = self.artifacts.iter()
+            .filter(|a| a.kind == cargo::BuildArtifactKind::Binary)
+            .map(|a| (a.name.clone(), a.path.to_string_lossy().to_string()))
+            .collect::<Vec<_>>();
+
+        if binaries.is_empty() {
+            panic!("Workspace build produced no binaries");
+        }
+
+        binaries
+    }
+
+    /// Dump a single table, comparing all `files` (pairs of display name & path) by file size,
+    /// size of functions & data (symbols are filtered by `filter`) and memory regions usage,
+    /// if `ld_file` is set. `Diff` column shows the difference of `Total` against the first file.
+    /// Each file is still reported in `machine_formats`, checked against budgets and recorded.
+    /// With `workspace_tables`, full tables of each file are printed before the summary
+    fn dump_comparison(
+        &mut self,
+        files:           Vec<(String, String)>,
+        tables:          bool,
+        machine_formats: &[OutputFormat],
+        machine_stream:  Stream
+    ) {
+        if files.is_empty() {
+            panic!("--compare requires at least one --file");
+        }
+
+        let mut rows = Vec::new();
+        let mut region_names = Vec::new();
+
+        // Output is adjusted for each file (e.g. by `hide_missing_crates()`), so each report
+        // starts with output configured by the user
+        let output = self.output.clone();
+        let file_tables = tables && self.workspace_tables;
+
+        for (name, file) in files.iter() {
+            self.output = output.clone();
+
+            if file_tables {
+                hprintln!();
+                hprintln!("{}", self.colored_str(format!("Binary: {}", name), attr_apply_bold));
+            }
+
+            self.load_exe(Some(file.as_str()));
+
+            let (code, data) = self.exe.symbols.iter()
+                .filter(|s| s.size != 0 && self.shown(s))
+                .fold((0, 0), |(code, data), s| match s.kind {
+                    SymbolKind::Function => (code + s.size, data),
+                    _                    => (code, data + s.size),
+                });
+
+            let regions = if self.ld_file.is_empty() {
+                Vec::new()
+            } else {
+                self.load_regions()
+            };
+
+            region_names = regions.iter().map(|r| r.name.clone()).collect();
+
+            rows.push((
+                name.clone(),
+                self.exe.file_size,
+                code,
+                data,
+                regions.iter().map(|r| (r.used, r.used_percentage)).collect::<Vec<_>>()
+            ));
+
+            self.report(file_tables, machine_formats, machine_stream);
+        }
+
+        if !tables {
+            return;
+        }
+
+        let mut names = vec![
+            "File ".to_string(),
+            "File Size ".to_string(),
+            "Code ".to_string(),
+            "Data ".to_string(),
+            "Total ".to_string(),
+            "Diff ".to_string(),
+        ];
+
+        names.extend(region_names.iter().map(|name| format!("{} ", name)));
+
+        let mut table = self.new_table(
+            self.plain_header(&names.iter().map(|n| n.as_str()).collect::<Vec<_>>())
+        );
+
+        let base = rows[0].2 + rows[0].3;
+
+        for (file, file_size, code, data, regions) in rows {
+            let total = code + data;
+
+            let mut row = Row::default();
+
+            row.push(AttributeString::from(format!("{} ", file).as_str()));
+            row.push(AttributeString::from(format!("{} ", util::format_size(file_size)).as_str()));
+            row.push(AttributeString::from(format!("{} ", util::format_size(code)).as_str()));
+            row.push(AttributeString::from(format!("{} ", util::format_size(data)).as_str()));
+            row.push(AttributeString::from(format!("{} ", util::format_size(total)).as_str()));
+            row.push(AttributeString::from(format!("{} ", util::format_int(format!("{:+}", total as i64 - base as i64))).as_str()));
+
+            for (used, percentage) in regions {
+                row.push(self.colored_str(
+                    format!("{} ({}) ", util::format_size(used), util::format_percent(percentage)),
+                    |s| self.apply_segment_color(s, percentage)
+                ));
+            }
+
+            table.push_row(row).unwrap();
+        }
+
+        hprintln!();
+
+        table.print();
+    }
+
+    /// Builds the project with `options` & loads the artifact, returning it (`exe` is left empty).
+    /// Used by modes, that compare several builds
+    fn load_variant(&mut self, options: BuildOptions) -> ExecutableInfo {
+        if self.no_build {
+            panic!("Comparing builds requires building the project, it can't be combined with --no-build");
+        }
+
+        self.build_options = options;
+        self.load_exe(None);
+
+        std::mem::take(&mut self.exe)
+    }
+
+    /// Builds the project with & without `feature_impact` feature, and reports, how much it costs
+    fn dump_feature_impact(&mut self) {
+        let mut without = self.build_options.clone();
+
+        let mut with = without.clone();
+        with.extra_args.extend(["--features".to_string(), self.feature_impact.clone()]);
+
+        // Default feature would be in both builds, so baseline is built without default features,
+        // enabling the rest of them explicitly
+        if !without.extra_args.iter().any(|arg| arg == "--no-default-features") {
+            let table = cargo::features(&self.build_options)
+                .unwrap_or_else(|e| panic!("Failed to get features of the package: {}", e));
+
+            let defaults = table.get("default").cloned().unwrap_or_default();
+
+            if cargo::enabled_features(&table, &defaults).contains(&self.feature_impact) {
+                let rest = defaults.into_iter()
+                    .filter(|feature| *feature != self.feature_impact)
+                    .collect::<Vec<_>>();
+
+                if cargo::enabled_features(&table, &rest).contains(&self.feature_impact) {
+                    panic!(
+                        "Feature '{}' is enabled by other default features ({}), so it can't be turned off separately",
+                        self.feature_impact,
+                        rest.join(", ")
+                    );
+                }
+
+                without.extra_args.push("--no-default-features".to_string());
+
+                if !rest.is_empty() {
+                    without.extra_args.extend(["--features".to_string(), rest.join(",")]);
+                }
             }
+        }
+
+        let without = self.load_variant(without);
+        let with = self.load_variant(with);
+
+        self.dump_impact(&format!("Feature '{}'", self.feature_impact), ("Without ", &without), ("With ", &with));
+    }
+
+    /// Builds the project with LTO disabled & with fat LTO (overriding `lto` of the profile through
+    /// environment), and reports, how much LTO saves
+    fn dump_lto_impact(&mut self) {
+        let env = cargo::profile_env(&self.build_options.profile, "lto");
+
+        let mut without = self.build_options.clone();
+        without.env.push((env.clone(), "false".to_string()));
+
+        let mut with = self.build_options.clone();
+        with.env.push((env, "fat".to_string()));
+
+        let without = self.load_variant(without);
+        let with = self.load_variant(with);
+
+        self.dump_impact("LTO", ("lto = false ", &without), ("lto = \"fat\" ", &with));
+    }
+
+    /// Builds the project with `panic = "unwind"` & `panic = "abort"` (overriding `panic` of the
+    /// profile through environment), and reports the difference. Besides the usual breakdown,
+    /// changes of unwinding tables & panic machinery (`core::panicking`) are summarized
+    fn dump_panic_impact(&mut self) {
+        let env = cargo::profile_env(&self.build_options.profile, "panic");
+
+        let mut unwind = self.build_options.clone();
+        unwind.env.push((env.clone(), "unwind".to_string()));
+
+        let mut abort = self.build_options.clone();
+        abort.env.push((env, "abort".to_string()));
+
+        let unwind = self.load_variant(unwind);
+        let abort = self.load_variant(abort);
+
+        // Panic machinery is matched by normalized name, as `core` may come with hashes
+        let panicking = |exe: &ExecutableInfo| exe.symbols.iter()
+            .filter(|s| demangle::normalize(&s.name).starts_with("core::panicking::"))
+            .fold(0, |r, s| r + s.size);
+
+        let rows = [
+            ("File Size ",       unwind.file_size,                                   abort.file_size),
+            ("Symbols ",         unwind.symbols.iter().fold(0, |r, s| r + s.size),   abort.symbols.iter().fold(0, |r, s| r + s.size)),
+            ("Unwind Tables ",   unwind.unwind_size(),                               abort.unwind_size()),
+            ("core::panicking ", panicking(&unwind),                                 panicking(&abort)),
+        ];
+
+        hprintln!();
+
+        let mut table = self.new_table(self.plain_header(&[" ", "panic = \"unwind\" ", "panic = \"abort\" ", "Δ "]));
+
+        for (name, unwind, abort) in rows {
+            let mut row = Row::default();
+
+            row.push(self.colored_str(name.to_string(), attr_apply_bold));
+            row.push(AttributeString::from(format!("{} ", util::format_size(unwind)).as_str()));
+            row.push(AttributeString::from(format!("{} ", util::format_size(abort)).as_str()));
+            row.push(self.delta_str(abort as i64 - unwind as i64));
+
+            table.push_row(row).unwrap();
+        }
+
+        table.print();
+
+        self.dump_impact("panic = \"abort\"", ("Unwind ", &unwind), ("Abort ", &abort));
+    }
+
+    /// Builds the project with each opt-level from `opt_matrix` (overriding it for the profile
+    /// through environment), and prints a matrix of text (code & read-only data), data & bss
+    /// sizes, like `size` does. Smallest total is highlighted
+    fn dump_opt_matrix(&mut self) {
+        use exe::SectionKind::*;
+
+        let base = self.build_options.clone();
+        let env = cargo::profile_env(&base.profile, "opt_level");
+
+        let mut rows = Vec::new();
+
+        for level in self.opt_matrix.clone() {
+            let mut options = base.clone();
+            options.env.push((env.clone(), level.clone()));
+
+            let exe = self.load_variant(options);
+
+            let size = |kinds: &[exe::SectionKind]| exe.sections.iter()
+                .filter(|s| kinds.contains(&s.kind))
+                .fold(0, |r, s| r + s.size);
+
+            rows.push((level, size(&[Code, ReadOnlyData]), size(&[Data, Tls]), size(&[Bss])));
+        }
 
-            if let Some(toml::Value::Array(val)) = binsize.get("percentage-threshold") {
-                self.percentage_threshold_yellow = val.get(0)
-                    .expect("Missing first value for key 'size-threshold'")
-                    .as_float()
-                    .expect("Values for key 'size-threshold' must be a float")
-                    as f32;
+        let totals = rows.iter().map(|(_, text, data, bss)| text + data + bss).collect::<Vec<_>>();
+        let smallest = totals.iter().copied().min().unwrap_or(0);
 
-                self.percentage_threshold_red = val.get(1)
-                    .expect("Missing second value for key 'size-threshold'")
-                    .as_float()
-                    .expect("Values for key 'size-threshold' must be a float")
-                    as f32;
+        hprintln!();
+
+        let mut table = self.new_table(self.plain_header(&["Opt Level ", "Text ", "Data ", "Bss ", "Total ", "Δ "]));
+
+        for ((level, text, data, bss), total) in rows.into_iter().zip(totals.iter().copied()) {
+            let mut row = Row::default();
+
+            row.push(self.colored_str(format!("{} ", level), attr_apply_bold));
+            row.push(AttributeString::from(format!("{} ", util::format_size(text)).as_str()));
+            row.push(AttributeString::from(format!("{} ", util::format_size(data)).as_str()));
+            row.push(AttributeString::from(format!("{} ", util::format_size(bss)).as_str()));
+            row.push(self.colored_str(format!("{} ", util::format_size(total)), |s| {
+                if total == smallest {
+                    s.push_attr(Attribute::TextBold);
+                    s.push_attr(Attribute::ColorFgGreen);
+                }
+            }));
+            row.push(self.delta_str(total as i64 - totals[0] as i64));
+
+            if total == smallest {
+                self.highlight_row(&mut row);
             }
+
+            table.push_row(row).unwrap();
         }
+
+        table.print();
     }
 
-    /// Parse command line arguments
-    fn parse_args(&mut self) {
-        let argp = args::ArgumentParser::new(
-            vec![
-                args::Argument::new_flag(
-                    "help",
-                    &["--help", "-h"],
-                    "Display help message"
-                ),
-                args::Argument::new_flag(
-                    "version",
-                    &["--version", "-v"],
-                    "Display version"
-                ),
-                args::Argument::new_value(
-                    "profile",
-                    &["--profile", "-p"],
-                    &["PROFILE"],
-                    "Cargo profile to build the project with"
-                ),
-                args::Argument::new_value(
-                    "output",
-                    &["--output", "-o"],
-                    &["OUTPUT"],
-                    "Comma separated list of output values with optional comma-separated list of columns"
-                ),
-                args::Argument::new_value(
-                    "file",
-                    &["--file"],
-                    &["FILE"],
-                    "Provide a path to compiled binary, skipping 'cargo build'"
-                ),
-                args::Argument::new_value(
-                    "ld-memory-map",
-                    &["--ld-memory-map", "-l"],
-                    &["LD_PATH"],
-                    "Path to ld script, containing MEMORY declaration"
-                ),
-                args::Argument::new_value(
-                    "filter",
-                    &["--filter", "-f"],
-                    &["FILTER"],
-                    "Filter symbol names by this value. Supports regex"
-                ),
-                args::Argument::new_flag(
-                    "asc",
-                    &["--asc", "-a"],
-                    "Sort by symbol size in ascending order"
-                ),
-                args::Argument::new_flag(
-                    "desc",
-                    &["--desc", "-d"],
-                    "Sort by symbol size in descending order"
-                ),
-                args::Argument::new_flag(
-                    "color",
-                    &["--color", "-c"],
-                    "Add coloring to output"
-                ),
-                args::Argument::new_value(
-                    "max-rows",
-                    &["-n", "--max-rows"],
-                    &["ROWS"],
-                    "Max rows to output. Shared between all tables"
-                ),
-                args::Argument::new_value(
-                    "size-threshold",
-                    &["--size-threshold"],
-                    &["YELLOW", "RED"],
-                    "Yellow & red size thresholds in bytes (default 200 500)"
-                ),
-                args::Argument::new_value(
-                    "percentage-threshold",
-                    &["--percentage-threshold"],
-                    &["YELLOW", "RED"],
-                    "Yellow & red size percentage thresholds (default 0.5 1.0)"
-                ),
-                args::Argument::new_flag(
-                    "ignore-config",
-                    &["-i", "--ignore-config"],
-                    "Ignore config file"
+    /// Dump size difference between two builds - `before` & `after` (pairs of column name &
+    /// executable) - as a summary line, table of crates and table of symbols, that changed.
+    /// Symbols are matched by name, ignoring hashes (see `delta::keyed`)
+    fn dump_impact(&self, title: &str, before: (&str, &ExecutableInfo), after: (&str, &ExecutableInfo)) {
+        let before_symbols = before.1.symbols.iter()
+            .filter(|s| s.size != 0 && self.shown(s))
+            .collect::<Vec<_>>();
+
+        let after_symbols = after.1.symbols.iter()
+            .filter(|s| s.size != 0 && self.shown(s))
+            .collect::<Vec<_>>();
+
+        let total = |symbols: &[&Symbol]| symbols.iter().fold(0, |r, s| r + s.size);
+        let (before_total, after_total) = (total(&before_symbols), total(&after_symbols));
+
+        hprintln!();
+        hprintln!(
+            "{}",
+            self.colored_str(
+                format!(
+                    "{}: {} -> {} bytes ({:+})",
+                    title, before_total, after_total, after_total as i64 - before_total as i64
                 ),
-            ],
-            args::UnexpectedArgumentPolicy::Crash
+                attr_apply_bold
+            )
         );
 
-        let parsed = argp.parse(std::env::args().skip(1));
+        // Sizes of each crate/symbol (name -> (before, after)), absent side has size of 0. Crate
+        // sizes are summed up from filtered symbols, so they agree with totals above
+        let mut crates = HashMap::<String, (usize, usize)>::new();
 
-        // FIXME: Is still needed?
-        // if parsed.contains_arg("output") {
-        //     self.output = Output::None as u8;
-        // }
+        for sym in before_symbols.iter() {
+            crates.entry(sym.crate_name.clone()).or_default().0 += sym.size;
+        }
 
-        for arg in parsed.args {
-            match arg.name.as_str() {
-                "help" => {
-                    println!("binsize - utility to provide comprehensive information about symbol sizes in compiled binaries");
-                    println!("Options:");
-                    argp.print_help();
-                    std::process::exit(0);
-                }
-                "version" => {
-                    println!("binsize {}", VERSION);
-                    std::process::exit(0);
-                }
-                "profile" => {
-                    self.build_options.profile = arg.values.get(0)
-                        .expect("Missing value for --profile")
-                        .clone();
-                }
-                "output" => {
-                    let val = arg.values.get(0).expect("Missing value for --output");
-                    self.output.apply_pattern(val);
-                }
-                "file" => {
-                    self.file = arg.values.get(0)
-                            .expect("Missing value for --file")
-                            .clone();
-                }
-                "filter" => {
-                    self.filter = regex::Regex::new(arg.values.get(0)
-                        .expect("Missing value for --filter")
-                        .clone()
-                        .as_str()
-                    ).unwrap();
-                }
-                "ld-memory-map" => {
-                    self.ld_file = arg.values.get(0)
-                        .expect("Missing value for --ld-memory-map")
-                        .clone();
-                }
-                "asc" => {
-                    self.symbols_sorting_order = Some(SortOrder::Ascending);
-                }
-                "desc" => {
-                    self.symbols_sorting_order = Some(SortOrder::Descending);
-                }
-                "color" => {
-                    self.color = true;
-                }
-                "max-rows" => {
-                    self.max_rows = arg.values.get(0)
-                        .expect("Missing value ROWS for --max-rows")
-                        .parse::<usize>()
-                        .expect("max rows must be a number");
-                }
-                "size-threshold" => {
-                    self.size_threshold_yellow = arg.values.get(0)
-                        .expect("Missing value YELLOW for --size-threshold")
-                        .parse::<usize>()
-                        .expect("yellow threshold must be a number");
+        for sym in after_symbols.iter() {
+            crates.entry(sym.crate_name.clone()).or_default().1 += sym.size;
+        }
 
-                    self.size_threshold_red = arg.values.get(1)
-                        .expect("Missing value RED for --size-threshold")
-                        .parse::<usize>()
-                        .expect("red threshold must be a number");
-                }
-                "percentage-threshold" => {
-                    self.percentage_threshold_yellow = arg.values.get(0)
-                        .expect("Missing value YELLOW for --percentage-threshold")
-                        .parse::<f32>()
-                        .expect("yellow threshold must be a float");
+        let mut symbols = HashMap::<String, (String, usize, usize)>::new();
 
-                    self.percentage_threshold_red = arg.values.get(1)
-                        .expect("Missing value RED for --percentage-threshold")
-                        .parse::<f32>()
-                        .expect("red threshold must be a float");
-                }
-                "ignore-config" => {
-                    *self = Default::default();
-                }
-                arg => {
-                    panic!("Unexpected argument: {}", arg);
-                }
-            }
+        for (sym, key) in delta::keyed(before_symbols.iter().map(|s| (s.name.as_str(), s.addr, *s)).collect()) {
+            symbols.entry(key).or_insert_with(|| (sym.name.clone(), 0, 0)).1 = sym.size;
         }
-    }
 
-    /// Load executable
-    fn load_exe(&mut self) {
-        // If file was specified (either via config of cmdline options)
-        let path = if !self.file.is_empty() {
-            std::path::PathBuf::from(&self.file)
-        } else {
-            // Run `cargo build` to get freshly compiled executable
-            if let Err(stderr) = cargo::build(self.build_options.clone()) {
-                println!("{}", stderr);
-                std::process::exit(1);
-            }
+        for (sym, key) in delta::keyed(after_symbols.iter().map(|s| (s.name.as_str(), s.addr, *s)).collect()) {
+            symbols.entry(key).or_insert_with(|| (sym.name.clone(), 0, 0)).2 = sym.size;
+        }
 
-            // Run `cargo built --message-format=json` to gather info about artifacts produced
-            // by build
-            self.artifacts = cargo::artifacts(self.build_options.clone());
+        let delta = |before: usize, after: usize| after as i64 - before as i64;
 
-            // Last artifact should be a `top crate` - executable or a library, for which
-            // a binary would be generated
-            let top_crate = self.artifacts.last()
-                .expect("No top crate");
+        // Biggest changes go first, either way
+        let mut crates = crates.into_iter()
+            .filter(|(_, (before, after))| before != after)
+            .collect::<Vec<_>>();
 
-            // Extract path to binary
-            top_crate.path.clone()
-        };
+        crates.sort_by_key(|(name, (before, after))| (std::cmp::Reverse(delta(*before, *after).abs()), name.clone()));
 
-        // Parse binary
-        self.exe = exe::parse(&path)
-            .expect("Failed to parse executable");
+        let mut symbols = symbols.into_values()
+            .filter(|(_, before, after)| before != after)
+            .collect::<Vec<_>>();
 
-        // Patch missing crate names (marked "?"), by using parsed build artifacts
-        if !self.artifacts.is_empty() {
-            exe::patch_missing_crate_names(&mut self.exe, &self.artifacts);
+        symbols.sort_by_key(|(name, before, after)| (std::cmp::Reverse(delta(*before, *after).abs()), name.clone()));
+
+        hprintln!();
+
+        let mut table = self.new_table_for(OutputKind::Crates, self.plain_header(&["Crate Name ", before.0, after.0, "Δ "]));
+
+        for (name, (before, after)) in crates {
+            let mut row = Row::default();
+
+            row.push(AttributeString::from(format!("{} ", name).as_str()));
+            row.push(AttributeString::from(format!("{} ", util::format_size(before)).as_str()));
+            row.push(AttributeString::from(format!("{} ", util::format_size(after)).as_str()));
+            row.push(self.delta_str(delta(before, after)));
+
+            table.push_row(row).unwrap();
         }
-    }
 
-    /// Helper function to crate a colored attribute string, if color is enabled
-    fn colored_str(&self, str: String, color_fn: impl Fn(&mut AttributeString)) -> AttributeString {
-        let mut attr_str = AttributeString::from(str.as_str());
+        table.print();
 
-        if self.color {
-            color_fn(&mut attr_str);
+        hprintln!();
+
+        let mut table = self.new_table_for(OutputKind::Symbols, self.plain_header(&["Δ ", before.0, after.0, "Symbol Name "]));
+
+        // Name is always the last column
+        table.set_constraint(3, table::Constraint { min: Some(24), priority: 0, ..Default::default() });
+
+        for (name, before, after) in symbols {
+            let mut row = Row::default();
+
+            row.push(self.delta_str(delta(before, after)));
+            row.push(AttributeString::from(format!("{} ", util::format_size(before)).as_str()));
+            row.push(AttributeString::from(format!("{} ", util::format_size(after)).as_str()));
+            row.push(self.colored_str(format!("{} ", self.symbol_name(&name)), attr_apply_bold));
+
+            table.push_row(row).unwrap();
         }
 
-        attr_str
+        table.print();
     }
 
-    /// Helper function to push `str` into `header` and `padding` into `paddings`, only if output
-    /// for this column/field is enabled, and adding color, only of color enabled
-    ///
-    /// # Arguments
-    ///
-    /// * `header` - Row that represents a header in a `Table`
-    /// * `paddings` - Vec of `Padding`, stores padding for each column
-    /// * `output_kind` - Kind of output (sections/segments/etc)
-    /// * `field` - Column/field bitmask
-    /// * `str` - Column name
-    /// * `padding` - Column padding
-    /// * `color_fn` - Function/closure to call, if colorful output is enabled
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use OutputKind::*;
-    /// use SymbolTableFields::*;
-    ///
-    /// let mut header = Row::default();
-    /// let mut paddings = Vec::new();
-    ///
-    /// self.push_into_header_and_padding_color(
-    ///     &mut header, &mut paddings,
-    ///     Symbols, Size as u8,
-    ///     "Size ", Padding::Right,
-    ///     |s| {
-    ///         s.push_attr(Attribute::TextBold);
-    ///     }
-    /// );
-    ///
-    /// let mut table = Table::with_header_and_padding(
-    ///     header,
-    ///     paddings.as_slice()
-    /// );
-    /// ```
-    fn push_into_header_and_padding_color(
-        &self,
-        header:      &mut Row,
-        paddings:    &mut Vec<Padding>,
-        output_kind: OutputKind,
-        field:       u8,
-        str:         &str,
-        padding:     Padding,
-        color_fn:    impl Fn(&mut AttributeString)
-    ) {
-        if !self.output.field_enabled(output_kind, field) {
-            return;
+    /// Dump enabled tables in machine-readable `format` into `stream`
+    fn dump_machine(&mut self, format: OutputFormat, stream: Stream) {
+        use OutputKind::*;
+
+        let mut report = MachineReport {
+            file: self.batch.then_some(self.exe_path.as_str()),
+            ..Default::default()
+        };
+
+        if self.output.enabled(Symbols) {
+            let symbols = self.exe.symbols.iter()
+                .filter(|s| s.size != 0 && self.shown(s))
+                .collect::<Vec<_>>();
+
+            let total = symbols.iter().fold(0, |r, s| r + s.size);
+
+            report.symbols = Some(
+                symbols.into_iter()
+                    .map(|s| (s, self.symbol_percentage(s, total)))
+                    .collect()
+            );
+        }
+
+        if self.output.enabled(Crates) {
+            report.crates = Some(self.exe.crate_sizes());
+        }
+
+        if self.output.enabled(Categories) {
+            report.categories = Some(
+                self.category_sizes()
+                    .into_iter()
+                    .map(|(category, size)| (category.to_string(), size))
+                    .collect()
+            );
+        }
+
+        if self.output.enabled(Stats) {
+            report.stats = Some(self.stats());
+        }
+
+        if self.output.enabled(Imports) {
+            report.imports = Some(&self.exe.imports);
         }
 
-        paddings.push(padding);
+        if self.output.enabled(Exports) {
+            report.exports = Some(&self.exe.exports);
+        }
 
-        self.push_into_row_color(header, output_kind, field, str, color_fn);
-    }
+        if self.output.enabled(Libraries) {
+            report.libraries = Some(self.exe.library_usage());
+        }
+
+        if self.output.enabled(Tls) {
+            report.tls = Some(self.exe.tls_usage());
+        }
+
+        if self.output.enabled(Sections) {
+            report.sections = Some(&self.exe.sections);
+        }
+
+        let regions;
+
+        if self.output.enabled(Segments) && !self.ld_file.is_empty() {
+            regions = self.load_regions();
+            report.regions = Some(&regions);
+        }
+
+        let baseline;
+
+        if format == OutputFormat::GhSummary && !self.baseline.is_empty() {
+            baseline = Baseline::from_file(std::path::Path::new(&self.baseline))
+                .unwrap_or_else(|e| panic!("Failed to load baseline '{}': {}", self.baseline, e));
+
+            report.baseline = Some(&baseline);
+        }
+
+        // JSON Lines are written directly into the stream, line by line
+        if format == OutputFormat::Jsonl {
+            let result = match stream {
+                Stream::Stdout => report.write_jsonl(&mut std::io::stdout().lock()),
+                Stream::Stderr => report.write_jsonl(&mut std::io::stderr().lock()),
+            };
+
+            // Reader (e.g. `head`) is allowed to stop reading early
+            if let Err(e) = result && e.kind() != std::io::ErrorKind::BrokenPipe {
+                panic!("Failed to write JSON Lines output: {}", e);
+            }
 
-    /// Helper function to push `str` into `row` only if output for this column/field is enabled,
-    /// and adding color, only of color enabled
-    ///
-    /// # Arguments
-    ///
-    /// * `row` - Row to append column value to
-    /// * `output_kind` - Kind of output (sections/segments/etc)
-    /// * `field` - Column/field bitmask
-    /// * `str` - Column value
-    /// * `color_fn` - Function/closure to call, if colorful output is enabled
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// let table: Table = ...;
-    /// let mut row = Row::default();
-    ///
-    /// self.push_into_row_color(
-    ///     &mut row,
-    ///     Symbols, Name as u8,
-    ///     format!("{} ", sym.name).as_str(),
-    ///     |s| {
-    ///         s.push_attr(Attribute::TextBold)
-    ///     }
-    /// );
-    ///
-    /// table.push_row(row).unwrap();
-    /// ```
-    ///
-    fn push_into_row_color(
-        &self,
-        row: &mut Row,
-        output_kind: OutputKind,
-        field: u8,
-        str: &str,
-        color_fn: impl Fn(&mut AttributeString)
-    ) {
-        if !self.output.field_enabled(output_kind, field) {
             return;
         }
 
-        let mut attr_str = AttributeString::from(str);
+        // Reports of several files are printed together, once all of them are ready
+        if format == OutputFormat::Json && self.batch {
+            let json = report.to_json();
 
-        if self.color {
-            color_fn(&mut attr_str);
+            self.json_reports.push(json);
+            return;
         }
 
-        row.push(attr_str);
+        let rendered = match format {
+            OutputFormat::Template => {
+                let template = std::fs::read_to_string(&self.template)
+                    .map_err(|e| e.to_string())
+                    .and_then(|source| template::Template::parse(&source))
+                    .unwrap_or_else(|e| panic!("Failed to load template '{}': {}", self.template, e));
+
+                report.render_template(&template)
+            }
+            _ => report.render(format),
+        };
+
+        // On GitHub Actions, summary is appended to the job summary file, instead of the stream
+        if format == OutputFormat::GhSummary && let Ok(path) = std::env::var("GITHUB_STEP_SUMMARY") {
+            use std::io::Write;
+
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .and_then(|mut file| writeln!(file, "{}", rendered))
+                .unwrap_or_else(|e| panic!("Failed to write summary into '{}': {}", path, e));
+
+            return;
+        }
+
+        match stream {
+            Stream::Stdout => println!("{}", rendered),
+            Stream::Stderr => eprintln!("{}", rendered),
+        }
     }
 
-    /// Helper function to push `str` into `row` only if output for this column/field is enabled
-    ///
-    /// # Arguments
-    ///
-    /// * `row` - Row to append column value to
-    /// * `output_kind` - Kind of output (sections/segments/etc)
-    /// * `field` - Column/field bitmask
-    /// * `str` - Column value
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// let table: Table = ...;
-    /// let mut row = Row::default();
-    ///
-    /// self.push_into_row(
-    ///     &mut row,
-    ///     Symbols, Name as u8,
-    ///     format!("{} ", sym.name).as_str()
-    /// );
-    ///
-    /// table.push_row(row).unwrap();
-    /// ```
-    fn push_into_row(
-        &self,
-        row: &mut Row,
-        output_kind: OutputKind,
-        field: u8,
-        str: &str
-    ) {
-        if !self.output.field_enabled(output_kind, field) {
+    /// Dump JSON reports, collected in `batch` mode, as a single array into `stream`
+    fn dump_json_reports(&mut self, stream: Stream) {
+        if self.json_reports.is_empty() {
             return;
+        }
+
+        let rendered = json::JsonValue::Array(std::mem::take(&mut self.json_reports)).pretty(2);
+
+        match stream {
+            Stream::Stdout => println!("{}", rendered),
+            Stream::Stderr => eprintln!("{}", rendered),
+        }
+    }
+
+    /// Check all `budgets` and dump results into a table. Sets `budget_exceeded` on violation
+    fn dump_budgets(&mut self) {
+        use budget::Target;
+
+        hprintln!();
+
+        let regions = if self.budgets.iter().any(|b| matches!(b.target, Target::Region(_))) {
+            if self.ld_file.is_empty() {
+                panic!("Region budgets require a linker script (--ld-memory-map)");
+            }
+
+            self.load_regions()
+        } else {
+            Vec::new()
         };
 
-        row.push(AttributeString::from(str));
+        let crates = self.exe.crate_sizes();
+
+        // All budgets are shown regardless of `max_rows`, as any of them can fail
+        let mut table = self.new_table(self.plain_header(&["Budget ", "Actual ", "Limit ", "Status "]));
+
+        table.set_max_rows(0);
+
+        for budget in self.budgets.clone() {
+            let (actual, capacity) = match &budget.target {
+                Target::Total => (self.exe.symbols.iter().fold(0, |r, s| r + s.size), None),
+                Target::Crate(name) => (
+                    crates.iter().find(|(n, _)| n == name).map(|(_, size)| *size).unwrap_or(0),
+                    None
+                ),
+                Target::Section(name) => (
+                    self.exe.sections.iter().filter(|s| &s.name == name).fold(0, |r, s| r + s.size),
+                    None
+                ),
+                Target::Region(name) => {
+                    let reg = regions.iter()
+                        .find(|r| &r.name == name)
+                        .unwrap_or_else(|| panic!("Budget for unknown memory region '{}'", name));
+
+                    (reg.used, Some(reg.length))
+                }
+            };
+
+            let passed = budget.check(actual, capacity);
+
+            if !passed {
+                self.budget_exceeded = true;
+            }
+
+            let mut row = Row::default();
+
+            row.push(AttributeString::from(format!("{} ", budget.target).as_str()));
+            row.push(AttributeString::from(format!("{} ", util::format_size(actual)).as_str()));
+            row.push(AttributeString::from(format!("{} ", budget.limit).as_str()));
+            row.push(self.colored_str(
+                if passed { "PASS " } else { "FAIL " }.to_string(),
+                |s| s.push_attr(if passed { Attribute::ColorFgGreen } else { Attribute::ColorFgRed })
+            ));
+
+            if !passed {
+                self.highlight_row(&mut row);
+            }
+
+            table.push_row(row).unwrap();
+        }
+
+        table.print();
     }
 
-    /// Dump symbols into a table
-    fn dump_symbols(&mut self) {
+    /// Disables crate-related columns and tables, if no symbol has a crate name
+    fn hide_missing_crates(&mut self) {
         use OutputKind::*;
         use SymbolTableFields::*;
-        
-        if let Some(order) = &self.symbols_sorting_order {
-            self.exe.sort_symbols(*order);
-        }
 
         // Check if at least one symbol has a crate name
         let has_crate_names = self.exe.symbols.iter()
@@ -740,437 +5713,526 @@ impl Binsize {
 
         // If no symbols have a crate name
         if !has_crate_names {
-            // Disable `Crate` column in `Symbols` table
-            self.output.field_disable(Symbols, Crate as u8);
+            // Disable `Crate` column in tables with symbols
+            self.output.field_disable(Symbols, Crate as u32);
+            self.output.field_disable(Regions, Crate as u32);
+            self.output.field_disable(Ram, Crate as u32);
 
             // Disable `Crates` table
             self.output.disable(Crates);
         }
+    }
 
-        let total = self.exe.symbols.iter()
-            .filter(|s| s.filter(&self.filter))
-            .fold(0, |r, s| r + s.size);
+    /// Loads firmware image (Intel HEX or raw binary) instead of executable
+    fn load_image(&mut self, path: &std::path::Path, kind: image::ImageKind) {
+        // Image has only occupied address ranges, so linker script is required to make sense of it
+        if self.ld_file.is_empty() {
+            eprintln!(
+                "Error: '{}' is a firmware image without symbols, pass --ld-memory-map to see its memory region usage",
+                path.display()
+            );
+            std::process::exit(1);
+        }
 
-        let mut header = Row::default();
-        let mut paddings = Vec::new();
+        let regions = link::MemoryRegion::from_file(&self.ld_file.clone().into())
+            .expect("Failed to open LD file");
 
-        self.push_into_header_and_padding_color(
-            &mut header, &mut paddings,
-            Symbols, Size as u8,
-            "Size ", Padding::Right,
-            attr_apply_bold
-        );
+        // Raw binary is flashed to the start of first region (usually FLASH)
+        let base = regions.first().map(|r| r.origin).unwrap_or(0);
 
-        self.push_into_header_and_padding_color(
-            &mut header, &mut paddings,
-            Symbols, Percent as u8,
-            "Percentage ", Padding::Right,
-            attr_apply_bold
-        );
+        self.exe = image::parse(path, kind, base)
+            .unwrap_or_else(|e| panic!("Failed to parse image '{}': {}", path.display(), e));
 
-        self.push_into_header_and_padding_color(
-            &mut header, &mut paddings,
-            Symbols, Kind as u8,
-            "Symbol Kind ", Padding::Right,
-            attr_apply_bold
-        );
+        // Only memory regions can be reported, as there are no symbols or sections
+        self.output = Output::new();
+        self.output.apply_pattern("segments");
+    }
 
-        self.push_into_header_and_padding_color(
-            &mut header, &mut paddings,
-            Symbols, Crate as u8,
-            "Crate Name ", Padding::Right,
-            attr_apply_bold
-        );
+    /// Explains why executable at `path` has no symbols. If it was `built` by `binsize`, cargo
+    /// profile is checked for settings, that strip symbols, and overrides for them are suggested
+    fn explain_stripped(&self, path: &std::path::Path, built: bool) {
+        eprintln!("Error: '{}' has no symbol table (it was stripped), so there is nothing to analyze", path.display());
 
-        self.push_into_header_and_padding_color(
-            &mut header, &mut paddings,
-            Symbols, Name as u8,
-            "Symbol Name ", Padding::Left,
-            attr_apply_bold
-        );
+        if !built {
+            eprintln!("Rebuild it without stripping symbols (e.g. `strip = false` in cargo profile), or pass an unstripped binary");
+            return;
+        }
 
-        let mut table = Table::with_header_and_padding(header, paddings.as_slice());
+        let profile = self.build_options.profile.as_str();
+        let issues = cargo::profile_issues(profile);
 
-        table.set_max_rows(self.max_rows);
+        if issues.is_empty() || self.fix_profile {
+            eprintln!(
+                "Profile '{}' doesn't strip symbols{}, check RUSTFLAGS (e.g. `-C strip=symbols`) and linker arguments (e.g. `-s`)",
+                profile,
+                if self.fix_profile { " (or was overridden by --fix-profile)" } else { "" }
+            );
+            return;
+        }
 
-        for sym in &self.exe.symbols {
-            if sym.size == 0 {
-                continue;
-            }
+        eprintln!("Profile '{}' has:", profile);
 
-            if !sym.filter(&self.filter) {
-                continue;
-            }
+        for issue in issues.iter() {
+            eprintln!("    {} (in {})", issue.setting, issue.source);
+        }
 
-            let mut row = Row::default();
+        eprintln!("Change these settings, or override them for binsize run with:");
+        eprintln!(
+            "    {} binsize",
+            issues.iter().map(|i| format!("{}={}", i.env, i.fix)).collect::<Vec<_>>().join(" ")
+        );
+        eprintln!("Or run binsize with --fix-profile to apply these overrides automatically");
+    }
 
-            self.push_into_row_color(
-                &mut row,
-                Symbols, Size as u8,
-                format!("{} ", sym.size).as_str(),
-                |s| {
-                    if sym.size >= self.size_threshold_red {
-                        s.push_attr(Attribute::ColorFgRed);
-                    } else if sym.size >= self.size_threshold_yellow {
-                        s.push_attr(Attribute::ColorFgYellow);
-                    } else {
-                        s.push_attr(Attribute::ColorFgGreen);
-                    }
-                }
-            );
+    /// Handles `binsize init` command - writes a starter config into `CONFIG`
+    fn init(&mut self) {
+        let argp = args::ArgumentParser::new(
+            vec![
+                args::Argument::new_flag(
+                    "help",
+                    &["--help", "-h"],
+                    "Display help message"
+                ),
+                args::Argument::new_flag(
+                    "force",
+                    &["--force"],
+                    "Overwrite existing config"
+                ),
+            ],
+            args::UnexpectedArgumentPolicy::Crash
+        );
 
-            let percentage = sym.size as f32 / (total as f32 / 100.0);
+        let mut force = false;
 
-            self.push_into_row_color(
-                &mut row,
-                Symbols, Percent as u8,
-                format!("{:.02}% ", percentage).as_str(),
-                |s| {
-                    if percentage >= self.percentage_threshold_red {
-                        s.push_attr(Attribute::ColorFgRed);
-                    } else if percentage >= self.percentage_threshold_yellow {
-                        s.push_attr(Attribute::ColorFgYellow);
-                    } else {
-                        s.push_attr(Attribute::ColorFgGreen);
-                    }
+        for arg in argp.parse(std::env::args().skip(2)).args {
+            match arg.name.as_str() {
+                "help" => {
+                    println!("binsize init - write a starter config into {}", CONFIG);
+                    println!("Options:");
+                    argp.print_help();
+                    std::process::exit(0);
                 }
-            );
-
-            self.push_into_row_color(
-                &mut row,
-                Symbols, Kind as u8,
-                format!("{} ", sym.kind).as_str(),
-                |s| {
-                    match sym.kind {
-                        SymbolKind::Function => s.push_attr(Attribute::ColorFgMagenta),
-                        SymbolKind::Data     => s.push_attr(Attribute::ColorFgCyan),
-                        SymbolKind::Unknown  => {},
-                    }
+                "force" => {
+                    force = true;
                 }
-            );
-
-            self.push_into_row(
-                &mut row,
-                Symbols, Crate as u8,
-                format!("{} ", sym.crate_name).as_str()
-            );
-
-            self.push_into_row_color(
-                &mut row,
-                Symbols, Name as u8,
-                format!("{} ", sym.name).as_str(),
-                |s| {
-                    s.push_attr(Attribute::TextBold)
+                arg => {
+                    panic!("Unexpected argument: {}", arg);
                 }
-            );
+            }
+        }
 
-            table.push_row(row).unwrap();
+        match init::write_config(std::path::Path::new(CONFIG), force) {
+            Ok(_) => {
+                println!("Config written to {}", CONFIG);
+            }
+            Err(e) => {
+                eprintln!("binsize init: {}", e);
+                std::process::exit(1);
+            }
         }
+    }
 
-        table.print();
+    /// Output all configured tables for currently loaded executable
+    fn report(&mut self, tables: bool, machine_formats: &[OutputFormat], machine_stream: Stream) {
+        self.hide_missing_crates();
 
-        println!();
+        self.symbol_deltas = self.previous.as_ref()
+            .map(|previous| previous.symbol_deltas(&self.exe))
+            .unwrap_or_default();
 
-        let mut fn_count = 0;
-        let mut fn_total = 0;
+        if !self.sort_keys.is_empty() {
+            self.exe.sort_symbols_by(&self.sort_keys);
+        } else if let Some(order) = &self.symbols_sorting_order {
+            self.exe.sort_symbols(*order);
+        }
 
-        let mut data_count = 0;
-        let mut data_total = 0;
+        if tables {
+            if self.output.enabled(OutputKind::Symbols) {
+                self.dump_symbols();
+            }
 
-        for sym in &self.exe.symbols {
-            match sym.kind {
-                SymbolKind::Function => {
-                    fn_count += 1;
-                    fn_total += sym.size;
-                }
-                SymbolKind::Data => {
-                    data_count += 1;
-                    data_total += sym.size;
-                }
-                _ => {}
+            if self.output.enabled(OutputKind::Crates) {
+                self.dump_crates();
             }
-        }
 
-        let mut totals_table = Table::with_empty_header_and_padding(vec![
-            Padding::Left, Padding::Right, Padding::Left, Padding::Right, Padding::Right,
-        ]);
+            if self.output.enabled(OutputKind::Categories) {
+                self.dump_categories();
+            }
 
-        let mut row = Row::default();
+            if self.output.enabled(OutputKind::Generated) {
+                self.dump_generated();
+            }
 
-        row.push("Functions: ".into());
-        row.push(self.colored_str(format!("{} ", fn_count), attr_apply_bold));
-        row.push("symbols, ".into());
-        row.push(self.colored_str(format!("{} ", fn_total), attr_apply_bold));
-        row.push("bytes".into());
+            if self.output.enabled(OutputKind::Stats) {
+                self.dump_stats();
+            }
+
+            if self.output.enabled(OutputKind::Imports) {
+                self.dump_imports();
+            }
 
-        totals_table.push_row(row).unwrap();
+            if self.output.enabled(OutputKind::Exports) {
+                self.dump_exports();
+            }
 
-        row = Row::default();
+            if self.output.enabled(OutputKind::CAbi) {
+                self.dump_c_abi();
+            }
 
-        row.push("Data: ".into());
-        row.push(self.colored_str(format!("{} ", data_count), attr_apply_bold));
-        row.push("symbols, ".into());
-        row.push(self.colored_str(format!("{} ", data_total), attr_apply_bold));
-        row.push("bytes".into());
+            if self.output.enabled(OutputKind::Libraries) {
+                self.dump_libraries();
+            }
 
-        totals_table.push_row(row).unwrap();
+            if self.output.enabled(OutputKind::Tls) {
+                self.dump_tls();
+            }
 
-        row = Row::default();
+            if self.output.enabled(OutputKind::Dominators) {
+                self.dump_dominators();
+            }
 
-        row.push("Total: ".into());
-        row.push(self.colored_str(format!("{} ", self.exe.symbols.len()), attr_apply_bold));
-        row.push("symbols, ".into());
-        row.push(self.colored_str(format!("{} ", total), attr_apply_bold));
-        row.push("bytes".into());
+            if self.output.enabled(OutputKind::Unreachable) {
+                self.dump_unreachable();
+            }
 
-        totals_table.push_row(row).unwrap();
+            if self.output.enabled(OutputKind::Sections) {
+                self.dump_sections();
+            }
 
-        totals_table.print();
-    }
+            if self.output.enabled(OutputKind::Debug) {
+                self.dump_debug();
+            }
 
-    /// Dump crate sizes into a table
-    fn dump_crates(&mut self) {
-        use OutputKind::*;
-        use CrateTableFields::*;
+            if self.output.enabled(OutputKind::Compression) {
+                self.dump_compression();
+            }
 
-        println!();
+            if self.output.enabled(OutputKind::Relocations) {
+                self.dump_relocations();
+            }
 
-        let mut crates = HashMap::new();
+            // Only archives have members, other files would just show an empty table
+            if self.output.enabled(OutputKind::Members) && !self.exe.members.is_empty() {
+                self.dump_members();
+            }
 
-        for sym in self.exe.symbols.iter() {
-            if crates.contains_key(&sym.crate_name) {
-                *crates.get_mut(&sym.crate_name).unwrap() += sym.size;
-            } else {
-                crates.insert(&sym.crate_name, sym.size);
+            if self.output.enabled(OutputKind::Segments) {
+                self.dump_segments();
             }
-        }
 
-        let mut crates = crates.iter().collect::<Vec<_>>();
+            if self.output.enabled(OutputKind::Regions) {
+                self.dump_region_symbols();
+            }
 
-        if let Some(order) = self.symbols_sorting_order {
-            crates.sort_by(|s1, s2|
-                if match order {
-                    SortOrder::Ascending  => s1.1 < s2.1,
-                    SortOrder::Descending => s1.1 > s2.1
-                } {
-                    core::cmp::Ordering::Less
-                } else {
-                    core::cmp::Ordering::Greater
-                }
-            );
-        }
+            if self.output.enabled(OutputKind::Ram) {
+                self.dump_ram();
+            }
 
-        let mut header = Row::default();
-        let mut paddings = Vec::new();
+            if !self.map_file.is_empty() {
+                self.dump_map_check();
+            }
 
-        self.push_into_header_and_padding_color(
-            &mut header, &mut paddings,
-            Crates, Size as u8,
-            "Crate Name ", Padding::Left,
-            attr_apply_bold
-        );
+            if self.estimate_stripped {
+                self.dump_strip_estimate();
+            }
 
-        self.push_into_header_and_padding_color(
-            &mut header, &mut paddings,
-            Crates, Size as u8,
-            "Size ", Padding::Right,
-            attr_apply_bold
-        );
+            if self.suggest {
+                self.dump_suggestions();
+            }
+        }
 
-        let mut table = Table::with_header_and_padding(header, paddings.as_slice());
+        for format in machine_formats {
+            self.dump_machine(*format, machine_stream);
+        }
 
-        table.set_max_rows(self.max_rows);
+        if !self.budgets.is_empty() {
+            self.dump_budgets();
+        }
 
-        for (name, size) in crates {
-            let mut row = Row::default();
+        if self.record {
+            self.record_history();
+        }
 
-            self.push_into_row(
-                &mut row,
-                Crates, Name as u8,
-                ((*name).clone() + " ").as_str()
-            );
+        self.save_last_run();
+    }
 
-            self.push_into_row(
-                &mut row,
-                Crates, Size as u8,
-                format!("{} ", size).as_str()
-            );
-            
-            table.push_row(row).unwrap();
+    /// Save totals of currently loaded executable, so the next run can show `Δ` against them.
+    /// Like cache, it's only kept inside a cargo project, and isn't touched with `--no-delta`
+    fn save_last_run(&self) {
+        if !self.delta || self.exe_path.is_empty() || !self.target_root().is_dir() {
+            return;
         }
 
-        table.print();
+        let path = self.target_root().join(delta::LAST_FILE);
+
+        delta::save(&path, &self.exe_path, &delta::Totals::new(&self.exe))
+            .unwrap_or_else(|e| eprintln!("Warning: Failed to save totals into '{}': {}", path.display(), e));
     }
 
-    /// Dump sections into a table
-    fn dump_sections(&mut self) {
-        use OutputKind::*;
-        use SectionTableFields::*;
+    /// Returns target directory of the project, which also holds `binsize` own files (cache,
+    /// totals of the last run, etc.)
+    fn target_root(&self) -> std::path::PathBuf {
+        cargo::target_root(&self.build_options)
+    }
 
-        println!();
+    /// Returns path to history file - `history_file`, or default one in target directory
+    fn history_path(&self) -> std::path::PathBuf {
+        match self.history_file.is_empty() {
+            true  => self.target_root().join(history::DEFAULT_HISTORY_FILE),
+            false => std::path::PathBuf::from(&self.history_file),
+        }
+    }
 
-        let mut header = Row::default();
-        let mut paddings = Vec::new();
+    /// Append totals of currently loaded executable to `history_file`
+    fn record_history(&self) {
+        let regions = if self.ld_file.is_empty() {
+            Vec::new()
+        } else {
+            self.load_regions()
+                .into_iter()
+                .map(|reg| (reg.name, reg.used))
+                .collect()
+        };
 
-        self.push_into_header_and_padding_color(
-            &mut header, &mut paddings,
-            Sections, Name as u8,
-            "Name ", Padding::Left,
-            attr_apply_bold
+        let record = history::Record::new(
+            if self.batch { self.exe_path.clone() } else { String::new() },
+            self.exe.symbols.iter().fold(0, |r, s| r + s.size),
+            self.exe.crate_sizes(),
+            self.exe.sections.iter().map(|s| (s.name.clone(), s.size)).collect(),
+            regions
         );
 
-        self.push_into_header_and_padding_color(
-            &mut header, &mut paddings,
-            Sections, Addr as u8,
-            "Address ", Padding::Left,
-            attr_apply_bold
-        );
+        let path = self.history_path();
 
-        self.push_into_header_and_padding_color(
-            &mut header, &mut paddings,
-            Sections, Size as u8,
-            "Size ", Padding::Right,
-            attr_apply_bold
+        history::append(&path, &record)
+            .unwrap_or_else(|e| panic!("Failed to record history into '{}': {}", path.display(), e));
+    }
+
+    /// Handles `binsize history` command - prints trend of totals, recorded with `--record`
+    fn history(&mut self) {
+        let argp = args::ArgumentParser::new(
+            vec![
+                args::Argument::new_flag(
+                    "help",
+                    &["--help", "-h"],
+                    "Display help message"
+                ),
+                args::Argument::new_flag(
+                    "chart",
+                    &["--chart"],
+                    "Print an ASCII chart of total size, instead of a table"
+                ),
+                args::Argument::new_value(
+                    "history-file",
+                    &["--history-file"],
+                    &["PATH"],
+                    "History file location (default target/binsize/history.jsonl)"
+                ),
+                args::Argument::new_value(
+                    "max-rows",
+                    &["-n", "--max-rows"],
+                    &["ROWS"],
+                    "Show only last ROWS records"
+                ),
+                args::Argument::new_value(
+                    "width",
+                    &["--width"],
+                    &["COLS"],
+                    "Width of table/chart, instead of terminal width"
+                ),
+                args::Argument::new_flag(
+                    "color",
+                    &["--color", "-c"],
+                    "Add coloring to output"
+                ),
+                args::Argument::new_value(
+                    "config",
+                    &["--config"],
+                    &["PATH"],
+                    "Config file to load instead of .cargo/binsize.toml"
+                ),
+            ],
+            args::UnexpectedArgumentPolicy::Crash
         );
 
-        let mut table = Table::with_header_and_padding(header, paddings.as_slice());
+        // User config goes first, so project config overrides it
+        for config in Self::user_config_path().into_iter().chain(self.config_path()) {
+            self.parse_config(config.as_str());
+        }
 
-        table.set_max_rows(self.max_rows);
+        let mut chart = false;
 
-        for section in self.exe.sections.iter() {
-            let mut row = Row::default();
+        for arg in argp.parse(std::env::args().skip(2)).args {
+            match arg.name.as_str() {
+                "help" => {
+                    println!("binsize history - print size trend from runs, recorded with --record");
+                    println!("Options:");
+                    argp.print_help();
+                    std::process::exit(0);
+                }
+                "chart" => {
+                    chart = true;
+                }
+                "history-file" => {
+                    self.history_file = util::rebase_path(
+                        arg.values.first().expect("Missing value for --history-file"),
+                        &self.invocation_dir
+                    );
+                }
+                "max-rows" => {
+                    self.max_rows = arg.values.first()
+                        .expect("Missing value ROWS for --max-rows")
+                        .parse::<usize>()
+                        .expect("max rows must be a number");
+                }
+                "width" => {
+                    self.width = arg.values.first()
+                        .expect("Missing value COLS for --width")
+                        .parse::<usize>()
+                        .expect("width must be a number");
+                }
+                "color" => {
+                    self.color = true;
+                }
+                "config" => {
+                    // Already loaded by `parse_config()`
+                }
+                arg => {
+                    panic!("Unexpected argument: {}", arg);
+                }
+            }
+        }
 
-            self.push_into_row(
-                &mut row,
-                Sections, Name as u8,
-                (section.name.clone() + " ").as_str()
-            );
+        util::set_term_width(self.width);
+        util::set_number_format(self.group_digits, self.precision, self.hex_sizes);
 
-            self.push_into_row(
-                &mut row,
-                Sections, Addr as u8,
-                format!("0x{:08x} ", section.addr).as_str()
-            );
+        let path = self.history_path();
 
-            self.push_into_row(
-                &mut row,
-                Sections, Size as u8,
-                format!("{} ", section.size).as_str()
-            );
+        let mut records = history::load(&path)
+            .unwrap_or_else(|e| panic!("Failed to load history from '{}': {}", path.display(), e));
 
-            table.push_row(row).unwrap();
+        // Only the latest records are interesting
+        if self.max_rows != 0 && records.len() > self.max_rows {
+            records.drain(..records.len() - self.max_rows);
         }
 
-        table.print();
-    }
-
-    /// Dump segments into a table, if `ld_file` is set
-    fn dump_segments(&mut self) {
-        use OutputKind::*;
-        use SegmentTableFields::*;
-        
-        if self.ld_file.is_empty() {
+        if records.is_empty() {
+            hprintln!("No records in {}", path.display());
             return;
         }
 
-        println!();
+        if chart {
+            self.dump_history_chart(&records);
+        } else {
+            self.dump_history_table(&records);
+        }
+    }
 
-        let mut header = Row::default();
-        let mut paddings = Vec::new();
+    /// Dump recorded totals into a table, with difference against previous record (of the same
+    /// file, if multiple files were recorded)
+    fn dump_history_table(&self, records: &[history::Record]) {
+        let files = records.iter().any(|r| !r.file.is_empty());
 
-        self.push_into_header_and_padding_color(
-            &mut header, &mut paddings,
-            Segments, Name as u8,
-            "Name ", Padding::Left,
-            attr_apply_bold
-        );
+        // Regions in the order they first appear
+        let mut regions = Vec::<String>::new();
 
-        self.push_into_header_and_padding_color(
-            &mut header, &mut paddings,
-            Segments, Addr as u8,
-            "Address ", Padding::Left,
-            attr_apply_bold
-        );
+        for record in records {
+            for (name, _) in record.regions.iter() {
+                if !regions.contains(name) {
+                    regions.push(name.clone());
+                }
+            }
+        }
 
-        self.push_into_header_and_padding_color(
-            &mut header, &mut paddings,
-            Segments, Used as u8,
-            "Used ", Padding::Right,
-            attr_apply_bold
-        );
+        let mut names = vec!["Date ".to_string(), "Commit ".to_string()];
 
-        self.push_into_header_and_padding_color(
-            &mut header, &mut paddings,
-            Segments, Size as u8,
-            "Size ", Padding::Right,
-            attr_apply_bold
-        );
+        if files {
+            names.push("File ".to_string());
+        }
 
-        self.push_into_header_and_padding_color(
-            &mut header, &mut paddings,
-            Segments, Percent as u8,
-            "Percentage ", Padding::Right,
-            attr_apply_bold
-        );
+        names.extend(["Total ".to_string(), "Diff ".to_string()]);
+        names.extend(regions.iter().map(|name| format!("{} ", name)));
 
-        let mut table = Table::with_header_and_padding(header, paddings.as_slice());
+        let mut table = Table::with_header(
+            self.plain_header(&names.iter().map(|n| n.as_str()).collect::<Vec<_>>())
+        );
 
-        table.set_max_rows(self.max_rows);
+        table.set_truncate(self.truncate);
 
-        // TODO: Shouldn't clone() ld_file
-        let mut regions = link::MemoryRegion::from_file(&self.ld_file.clone().into())
-            .expect("Failed to open LD file");
+        let mut previous = HashMap::<&str, usize>::new();
 
-        link::MemoryRegion::use_segments_data(&mut regions, &self.exe.segments);
+        for record in records {
+            let diff = record.total as i64 - previous.get(record.file.as_str()).copied().unwrap_or(record.total) as i64;
 
-        for reg in regions.iter_mut() {
             let mut row = Row::default();
 
-            self.push_into_row(
-                &mut row,
-                Segments, Name as u8,
-                (reg.name.clone() + " ").as_str()
-            );
-
-            self.push_into_row(
-                &mut row,
-                Segments, Addr as u8,
-                format!("0x{:08x} ", reg.origin).as_str()
-            );
-
-            self.push_into_row(
-                &mut row,
-                Segments, Used as u8,
-                format!("{} ", reg.used).as_str()
-            );
+            row.push(AttributeString::from(format!("{} ", record.date()).as_str()));
+            row.push(AttributeString::from(format!("{} ", record.commit).as_str()));
 
-            self.push_into_row(
-                &mut row,
-                Segments, Size as u8,
-                format!("{} ", reg.length).as_str()
-            );
+            if files {
+                row.push(AttributeString::from(format!("{} ", record.file).as_str()));
+            }
 
-            self.push_into_row_color(
-                &mut row,
-                Segments, Percent as u8,
-                format!("{:.02}% ", reg.used_percentage).as_str(),
+            row.push(AttributeString::from(format!("{} ", util::format_size(record.total)).as_str()));
+            row.push(self.colored_str(
+                format!("{} ", util::format_int(format!("{:+}", diff))),
                 |s| {
-                    if reg.used_percentage > 75.0 {
+                    if diff > 0 {
                         s.push_attr(Attribute::ColorFgRed);
-                    } else if reg.used_percentage > 50.0 {
-                        s.push_attr(Attribute::ColorFgYellow);
-                    } else {
+                    } else if diff < 0 {
                         s.push_attr(Attribute::ColorFgGreen);
                     }
                 }
-            );
+            ));
 
-            table.push_row(row).unwrap()
+            for name in regions.iter() {
+                row.push(AttributeString::from(
+                    record.region(name).map(|used| format!("{} ", util::format_size(used))).unwrap_or("- ".to_string()).as_str()
+                ));
+            }
+
+            table.push_row(row).unwrap();
+
+            previous.insert(record.file.as_str(), record.total);
         }
 
         table.print();
     }
 
+    /// Dump recorded totals as a horizontal bar chart. Bars are scaled between the smallest and
+    /// the largest total, so small changes are still visible
+    fn dump_history_chart(&self, records: &[history::Record]) {
+        let min = records.iter().map(|r| r.total).min().unwrap_or(0);
+        let max = records.iter().map(|r| r.total).max().unwrap_or(0);
+
+        let labels = records.iter()
+            .map(|r| match r.file.is_empty() {
+                true  => format!("{} {} {} ", r.date(), r.commit, r.total),
+                false => format!("{} {} {} {} ", r.date(), r.commit, r.file, r.total),
+            })
+            .collect::<Vec<_>>();
+
+        let label_width = labels.iter().map(|l| util::str_width(l)).max().unwrap_or(0);
+
+        let width = util::term_width().saturating_sub(label_width + 1).max(10);
+
+        for (record, label) in records.iter().zip(labels) {
+            // Smallest total still gets a bit of a bar, so it's visible
+            let fraction = if max == min {
+                1.0
+            } else {
+                0.05 + 0.95 * (record.total - min) as f32 / (max - min) as f32
+            };
+
+            hprintln!(
+                "{:<label_width$}{}",
+                label,
+                self.colored_str(util::bar(fraction, width), |s| s.push_attr(Attribute::ColorFgGreen)),
+                label_width = label_width
+            );
+        }
+    }
+
     /// Run whole application
     /// Will parse cmdline arguments, config, and output all configured tables
     ///
@@ -1179,29 +6241,150 @@ impl Binsize {
     /// Binsize::new().run();
     /// ```
     fn run(&mut self) {
-        self.parse_config();
+        // Like cargo, work from project root, even if invoked from a subdirectory. This way config
+        // is found, and paths in it (as well as `target`) are resolved relative to the root
+        self.invocation_dir = std::env::current_dir().expect("Failed to get current directory");
+
+        if let Some(root) = cargo::find_root(&self.invocation_dir) {
+            std::env::set_current_dir(&root)
+                .unwrap_or_else(|e| panic!("Failed to change directory to '{}': {}", root.display(), e));
+        }
+
+        match std::env::args().nth(1).as_deref() {
+            Some("init") => {
+                self.init();
+                return;
+            }
+            Some("history") => {
+                self.history();
+                return;
+            }
+            Some("tree") => {
+                self.tree = true;
+            }
+            _ => {}
+        }
+
+        // User config goes first, so project config overrides it
+        for config in Self::user_config_path().into_iter().chain(self.config_path()) {
+            self.parse_config(config.as_str());
+        }
+
         self.parse_args();
 
+        let profile = self.build_options.profile.clone();
+        let issues = cargo::profile_issues(profile.as_str());
+
+        if self.fix_profile {
+            self.build_options.env.extend(issues.into_iter().map(|issue| (issue.env, issue.fix)));
+        } else if !issues.is_empty() && !self.no_build && (self.files.is_empty() || self.workspace) && !self.compare {
+            // Warned before building, as the build can take a while only to produce nothing to analyze
+            eprintln!(
+                "Warning: Profile '{}' has {}, which strips symbols or debug info needed for analysis. Use --override-profile-settings to override it for the build",
+                profile,
+                issues.iter().map(|i| format!("`{}` (in {})", i.setting, i.source)).collect::<Vec<_>>().join(", ")
+            );
+        }
+
         if !self.output.any_enabled() {
             self.output.enable(OutputKind::Symbols);
+            self.output.enable(OutputKind::Members);
+        }
+
+        if !self.template.is_empty() && !self.formats.contains(&OutputFormat::Template) {
+            self.formats.push(OutputFormat::Template);
+        }
+
+        let machine_formats = self.formats.iter()
+            .filter(|f| f.is_machine())
+            .copied()
+            .collect::<Vec<_>>();
+
+        let tables = self.formats.is_empty() || self.formats.contains(&OutputFormat::Table);
+
+        // Machine output and tables never share a stream, so machine output can be piped
+        // somewhere while tables are still visible in the terminal
+        let machine_stream = if self.machine_stderr { Stream::Stderr } else { Stream::Stdout };
+
+        util::set_term_width(self.width);
+        util::set_number_format(self.group_digits, self.precision, self.hex_sizes);
+
+        util::set_human_stream(
+            if machine_formats.is_empty() || machine_stream == Stream::Stderr {
+                Stream::Stdout
+            } else {
+                Stream::Stderr
+            }
+        );
+
+        if !self.feature_impact.is_empty() {
+            self.dump_feature_impact();
+            return;
+        }
+
+        if self.lto_impact {
+            self.dump_lto_impact();
+            return;
+        }
+
+        if self.panic_impact {
+            self.dump_panic_impact();
+            return;
+        }
+
+        if !self.opt_matrix.is_empty() {
+            self.dump_opt_matrix();
+            return;
         }
 
-        self.load_exe();
+        if self.tree {
+            let file = self.files.first().cloned();
 
-        if self.output.enabled(OutputKind::Symbols) {
-            self.dump_symbols();
+            self.load_exe(file.as_deref());
+            self.dump_tree();
+            return;
         }
 
-        if self.output.enabled(OutputKind::Crates) {
-            self.dump_crates();
+        // Metadata is the same for every analyzed file, so it's fetched once
+        if self.output.enabled(OutputKind::Categories) {
+            self.metadata = cargo::metadata(&self.build_options).unwrap_or_default();
         }
 
-        if self.output.enabled(OutputKind::Sections) {
-            self.dump_sections();
+        self.batch = self.workspace || self.compare || self.files.len() > 1;
+
+        if self.workspace {
+            let binaries = self.workspace_binaries();
+
+            self.dump_comparison(binaries, tables, &machine_formats, machine_stream);
+        } else if self.compare {
+            let files = self.files.iter().map(|f| (f.clone(), f.clone())).collect();
+
+            self.dump_comparison(files, tables, &machine_formats, machine_stream);
+        } else if self.files.is_empty() {
+            self.load_exe(None);
+            self.report(tables, &machine_formats, machine_stream);
+        } else {
+            // Output is adjusted for each file (e.g. by `hide_missing_crates()`), so each report
+            // starts with output configured by the user
+            let output = self.output.clone();
+
+            for file in self.files.clone() {
+                self.output = output.clone();
+
+                if self.batch && tables {
+                    hprintln!();
+                    hprintln!("{}", self.colored_str(format!("File: {}", file), attr_apply_bold));
+                }
+
+                self.load_exe(Some(file.as_str()));
+                self.report(tables, &machine_formats, machine_stream);
+            }
         }
 
-        if self.output.enabled(OutputKind::Segments) {
-            self.dump_segments();
+        self.dump_json_reports(machine_stream);
+
+        if self.budget_exceeded {
+            std::process::exit(1);
         }
     }
 }
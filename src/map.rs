@@ -0,0 +1,181 @@
+//! # `binsize::map`
+//!
+//! Implements linker map file parser (GNU ld & LLD formats). Only output sections, discarded
+//! input sections and fill bytes are extracted, which is enough to cross-check sizes, parsed
+//! from the executable
+//!
+
+use std::error::Error;
+use std::path::Path;
+
+/// Output section from linker map
+pub struct MapSection {
+    /// Section name
+    pub name: String,
+
+    /// Section size
+    pub size: usize,
+}
+
+/// Contents of linker map
+#[derive(Default)]
+pub struct LinkerMap {
+    /// Output sections in the order they are listed
+    pub sections: Vec<MapSection>,
+
+    /// Number of input sections, discarded by linker (e.g. by `--gc-sections`)
+    pub discarded_count: usize,
+
+    /// Total size of discarded input sections
+    pub discarded_size: usize,
+
+    /// Total size of padding, inserted between input sections
+    pub fill: usize,
+}
+
+impl LinkerMap {
+    /// Parses linker map at `path`. Format (GNU ld or LLD) is detected by contents
+    pub fn from_file(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let contents = std::fs::read_to_string(path)?;
+
+        let map = match contents.lines().find(|l| l.trim_start().starts_with("VMA")) {
+            Some(header) => Self::parse_lld(&contents, header),
+            None         => Self::parse_gnu(&contents),
+        };
+
+        if map.sections.is_empty() {
+            return Err(format!("No output sections found in '{}'", path.display()).into());
+        }
+
+        Ok(map)
+    }
+
+    /// Returns output section named `name`
+    pub fn section(&self, name: &str) -> Option<&MapSection> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+
+    /// Parses GNU ld map. Output sections start at first column, and are followed by address &
+    /// size (on the next line, if section name is too long). Discarded input sections are listed
+    /// in a separate block before memory map
+    fn parse_gnu(contents: &str) -> Self {
+        let mut map = LinkerMap::default();
+
+        let mut discarded = false;
+        let mut memory_map = false;
+
+        // Name of an output section, which address & size were moved to the next line
+        let mut pending: Option<String> = None;
+
+        // Same for discarded input section
+        let mut pending_discarded = false;
+
+        for line in contents.lines() {
+            if line.starts_with("Discarded input sections") {
+                discarded = true;
+                continue;
+            }
+
+            if line.starts_with("Memory Configuration") {
+                discarded = false;
+                continue;
+            }
+
+            if line.starts_with("Linker script and memory map") {
+                memory_map = true;
+                continue;
+            }
+
+            let tokens = line.split_whitespace().collect::<Vec<_>>();
+
+            if discarded {
+                // Input section lines are indented by a single space, continuation by many
+                let numbers = if line.starts_with("  ") && pending_discarded {
+                    &tokens[..]
+                } else if line.starts_with(' ') && !line.starts_with("  ") && !tokens.is_empty() {
+                    pending_discarded = tokens.len() == 1;
+                    &tokens[1..]
+                } else {
+                    continue;
+                };
+
+                if let Some(size) = numbers.get(1).and_then(|s| parse_hex(s)) {
+                    map.discarded_count += 1;
+                    map.discarded_size += size;
+                    pending_discarded = false;
+                }
+
+                continue;
+            }
+
+            if !memory_map {
+                continue;
+            }
+
+            if tokens.first() == Some(&"*fill*") {
+                map.fill += tokens.get(2).and_then(|s| parse_hex(s)).unwrap_or(0);
+                continue;
+            }
+
+            let (name, numbers) = if !line.starts_with(' ') && !tokens.is_empty() {
+                (tokens[0].to_string(), &tokens[1..])
+            } else if let Some(name) = pending.take() {
+                (name, &tokens[..])
+            } else {
+                continue;
+            };
+
+            // Address goes first, and is only checked to tell sections from other lines
+            match (numbers.first().and_then(|s| parse_hex(s)), numbers.get(1).and_then(|s| parse_hex(s))) {
+                (Some(_), Some(size))   => map.sections.push(MapSection { name, size }),
+                _ if numbers.is_empty() => pending = Some(name),
+                _                       => {}
+            }
+        }
+
+        map
+    }
+
+    /// Parses LLD map. It's a table with `VMA`, `LMA`, `Size`, `Align`, `Out`, `In` & `Symbol`
+    /// columns, output sections are the lines, which have name in `Out` column
+    fn parse_lld(contents: &str, header: &str) -> Self {
+        let mut map = LinkerMap::default();
+
+        let Some(out_column) = header.find("Out") else {
+            return map;
+        };
+
+        let columns = header[..out_column].split_whitespace().collect::<Vec<_>>();
+
+        let size_index = columns.iter().position(|c| *c == "Size");
+
+        for line in contents.lines().skip_while(|l| *l != header).skip(1) {
+            if line.len() <= out_column || !line.is_char_boundary(out_column) || !line[..out_column].ends_with(' ') {
+                continue;
+            }
+
+            let name = &line[out_column..];
+
+            if name.starts_with(' ') {
+                continue;
+            }
+
+            let numbers = line[..out_column].split_whitespace().collect::<Vec<_>>();
+
+            let size = size_index
+                .and_then(|i| numbers.get(i))
+                .and_then(|s| usize::from_str_radix(s, 16).ok());
+
+            if let Some(size) = size {
+                map.sections.push(MapSection { name: name.trim().to_string(), size });
+            }
+        }
+
+        map
+    }
+}
+
+/// Parses `0x`-prefixed hex number
+fn parse_hex(s: &str) -> Option<usize> {
+    usize::from_str_radix(s.strip_prefix("0x")?, 16).ok()
+}
@@ -6,7 +6,7 @@
 use std::collections::HashMap;
 
 /// Macro to update `field_mask` (bitmask of allowed fields) by using some type that
-/// implements `try_from` and returns a value that can be converted to `u8`
+/// implements `try_from` and returns a value that can be converted to `u32`
 ///
 /// # Arguments
 ///
@@ -27,18 +27,24 @@ macro_rules! update_field_mask_from {
         $field_mask |= $enum::try_from($field)
             .expect(
                 format!("Invalid value for {}: '{}'", stringify!($enum), $field).as_str()
-            ) as u8
+            ) as u32
     };
 }
 
 /// Bit fields of symbol table columns/fields
+#[repr(u32)]
 pub enum SymbolTableFields {
-    Size    = 1 << 0,
-    Percent = 1 << 1,
-    Kind    = 1 << 2,
-    Crate   = 1 << 3,
-    Name    = 1 << 4,
-    All     = 0xFF,
+    Size     = 1 << 0,
+    Percent  = 1 << 1,
+    Kind     = 1 << 2,
+    Crate    = 1 << 3,
+    Name     = 1 << 4,
+    Bar      = 1 << 5,
+    Lang     = 1 << 6,
+    Retained = 1 << 7,
+    Derived  = 1 << 8,
+    Delta    = 1 << 9,
+    All      = 0xFFFF_FFFF,
 }
 
 impl TryFrom<&str> for SymbolTableFields {
@@ -54,16 +60,28 @@ impl TryFrom<&str> for SymbolTableFields {
             "k" | "kind"          => Ok(Kind),
             "c" | "crate"         => Ok(Crate),
             "n" | "name"          => Ok(Name),
+            "b" | "bar"           => Ok(Bar),
+            "l" | "lang"          => Ok(Lang),
+            "r" | "retained"      => Ok(Retained),
+            "d" | "derived"       => Ok(Derived),
+            "delta" | "Δ"         => Ok(Delta),
             _                     => Err(format!("Unknown symbol table output field: '{}'", value)),
         }
     }
 }
 
 /// Bit fields of crate table columns/fields
+#[repr(u32)]
 pub enum CrateTableFields {
-    Name = 1 << 0,
-    Size = 1 << 1,
-    All  = 0xFF,
+    Name    = 1 << 0,
+    Size    = 1 << 1,
+    Bar     = 1 << 2,
+    Delta   = 1 << 3,
+    Count   = 1 << 4,
+    Average = 1 << 5,
+    Flash   = 1 << 6,
+    Ram     = 1 << 7,
+    All     = 0xFFFF_FFFF,
 }
 
 impl TryFrom<&str> for CrateTableFields {
@@ -73,20 +91,82 @@ impl TryFrom<&str> for CrateTableFields {
         use CrateTableFields::*;
 
         match value {
-            "*" | "all"  => Ok(All),
-            "n" | "name" => Ok(Name),
-            "s" | "size" => Ok(Size),
-            _            => Err(format!("Unknown crate table output field: '{}'", value)),
+            "*" | "all"             => Ok(All),
+            "n" | "name"            => Ok(Name),
+            "s" | "size"            => Ok(Size),
+            "b" | "bar"             => Ok(Bar),
+            "delta" | "Δ"           => Ok(Delta),
+            "c" | "count"           => Ok(Count),
+            "a" | "avg" | "average" => Ok(Average),
+            "f" | "flash"           => Ok(Flash),
+            "r" | "ram"             => Ok(Ram),
+            _                       => Err(format!("Unknown crate table output field: '{}'", value)),
+        }
+    }
+}
+
+/// Bit fields of crate category table columns/fields
+#[repr(u32)]
+pub enum CategoryTableFields {
+    Name    = 1 << 0,
+    Size    = 1 << 1,
+    Percent = 1 << 2,
+    All     = 0xFFFF_FFFF,
+}
+
+impl TryFrom<&str> for CategoryTableFields {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        use CategoryTableFields::*;
+
+        match value {
+            "*" | "all"           => Ok(All),
+            "n" | "name"          => Ok(Name),
+            "s" | "size"          => Ok(Size),
+            "p" | "percent" | "%" => Ok(Percent),
+            _                     => Err(format!("Unknown category table output field: '{}'", value)),
+        }
+    }
+}
+
+/// Bit fields of shared library table columns/fields
+#[repr(u32)]
+pub enum LibraryTableFields {
+    Name    = 1 << 0,
+    Imports = 1 << 1,
+    Stubs   = 1 << 2,
+    All     = 0xFFFF_FFFF,
+}
+
+impl TryFrom<&str> for LibraryTableFields {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        use LibraryTableFields::*;
+
+        match value {
+            "*" | "all"     => Ok(All),
+            "n" | "name"    => Ok(Name),
+            "i" | "imports" => Ok(Imports),
+            "s" | "stubs"   => Ok(Stubs),
+            _               => Err(format!("Unknown library table output field: '{}'", value)),
         }
     }
 }
 
 /// Bit fields of section table columns/fields
+#[repr(u32)]
 pub enum SectionTableFields {
-    Name = 1 << 0,
-    Addr = 1 << 1,
-    Size = 1 << 2,
-    All  = 0xFF,
+    Name        = 1 << 0,
+    Addr        = 1 << 1,
+    Size        = 1 << 2,
+    Coverage    = 1 << 3,
+    Offset      = 1 << 4,
+    Percent     = 1 << 5,
+    FilePercent = 1 << 6,
+    Categories  = 1 << 7,
+    All         = 0xFFFF_FFFF,
 }
 
 impl TryFrom<&str> for SectionTableFields {
@@ -96,23 +176,35 @@ impl TryFrom<&str> for SectionTableFields {
         use SectionTableFields::*;
 
         match value {
-            "*" | "all"  => Ok(All),
-            "n" | "name" => Ok(Name),
-            "a" | "addr" => Ok(Addr),
-            "s" | "size" => Ok(Size),
-            _            => Err(format!("Unknown section table output field: '{}'", value)),
+            "*" | "all"           => Ok(All),
+            "n" | "name"          => Ok(Name),
+            "a" | "addr"          => Ok(Addr),
+            "s" | "size"          => Ok(Size),
+            "c" | "coverage"      => Ok(Coverage),
+            "o" | "offset"        => Ok(Offset),
+            "%" | "p" | "percent" => Ok(Percent),
+            "fp" | "file-percent" => Ok(FilePercent),
+            "cat" | "categories"  => Ok(Categories),
+            _                     => Err(format!("Unknown section table output field: '{}'", value)),
         }
     }
 }
 
 /// Bit fields of segment table columns/fields
+///
+/// `Name`, `Used` & `Percent` are only used in memory regions table (with linker script), and
+/// `Type`, `FileSize` & `Flags` - only in raw program headers table (without linker script)
+#[repr(u32)]
 pub enum SegmentTableFields {
-    Name    = 1 << 0,
-    Addr    = 1 << 1,
-    Used    = 1 << 2,
-    Size    = 1 << 3,
-    Percent = 1 << 4,
-    All     = 0xFF,
+    Name     = 1 << 0,
+    Addr     = 1 << 1,
+    Used     = 1 << 2,
+    Size     = 1 << 3,
+    Percent  = 1 << 4,
+    Type     = 1 << 5,
+    FileSize = 1 << 6,
+    Flags    = 1 << 7,
+    All      = 0xFFFF_FFFF,
 }
 
 impl TryFrom<&str> for SegmentTableFields {
@@ -128,28 +220,121 @@ impl TryFrom<&str> for SegmentTableFields {
             "u" | "used"          => Ok(Used),
             "s" | "size"          => Ok(Size),
             "p" | "percent" | "%" => Ok(Percent),
+            "t" | "type"          => Ok(Type),
+            "fs" | "filesize"     => Ok(FileSize),
+            "f" | "flags"         => Ok(Flags),
             _                     => Err(format!("Unknown segment table output field: '{}'", value)),
         }
     }
 }
 
 
+/// Base, against which `Percentage` column of symbols table is calculated
+#[derive(Clone, Copy, PartialEq)]
+pub enum PercentBase {
+    /// Total size of filtered symbols
+    Symbols,
+
+    /// Size of the whole file
+    FileSize,
+
+    /// Size of the section, that contains the symbol
+    Section,
+}
+
+impl TryFrom<&str> for PercentBase {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        use PercentBase::*;
+
+        match value {
+            "symbols"  => Ok(Symbols),
+            "filesize" => Ok(FileSize),
+            "section"  => Ok(Section),
+            _          => Err(format!("Invalid percentage base '{}' (possible values: symbols, filesize, section)", value)),
+        }
+    }
+}
+
 /// Bitmask of possible output tables
 #[derive(Hash, PartialEq, Eq, Clone, Copy)]
+#[repr(u32)]
 pub enum OutputKind {
-    Symbols  = 1 << 0,
-    Sections = 1 << 1,
-    Segments = 1 << 2,
-    Crates   = 1 << 3,
-    None     = 0,
-    All      = 0xff,
+    Symbols     = 1 << 0,
+    Sections    = 1 << 1,
+    Segments    = 1 << 2,
+    Crates      = 1 << 3,
+    Regions     = 1 << 4,
+    Ram         = 1 << 5,
+    Categories  = 1 << 6,
+    Stats       = 1 << 7,
+    Imports     = 1 << 8,
+    Exports     = 1 << 9,
+    Libraries   = 1 << 10,
+    Tls         = 1 << 11,
+    Dominators  = 1 << 12,
+    Unreachable = 1 << 13,
+    Debug       = 1 << 14,
+    Compression = 1 << 15,
+    Members     = 1 << 16,
+    Relocations = 1 << 17,
+    Generated   = 1 << 18,
+    CAbi        = 1 << 19,
+    None        = 0,
+    All         = 0xFFFF_FFFF,
 }
 
 impl OutputKind {
+    /// Returns columns/fields, that are shown, if output is enabled without a field list.
+    /// Optional columns (like `bar`) are only shown, if requested explicitly (or using `all`)
+    fn default_fields(&self) -> u32 {
+        match self {
+            OutputKind::Symbols | OutputKind::Regions | OutputKind::Ram | OutputKind::Tls => {
+                !(SymbolTableFields::Bar as u32 | SymbolTableFields::Lang as u32 | SymbolTableFields::Retained as u32)
+            }
+            OutputKind::Dominators => {
+                !(SymbolTableFields::Bar as u32 | SymbolTableFields::Lang as u32)
+            }
+            OutputKind::Unreachable => {
+                !(SymbolTableFields::Bar as u32 | SymbolTableFields::Lang as u32 | SymbolTableFields::Retained as u32)
+            }
+            OutputKind::Crates => {
+                !(CrateTableFields::Bar as u32 | CrateTableFields::Average as u32 | CrateTableFields::Flash as u32 | CrateTableFields::Ram as u32)
+            }
+            OutputKind::Sections => {
+                !(SectionTableFields::Coverage as u32 | SectionTableFields::Offset as u32 | SectionTableFields::FilePercent as u32 | SectionTableFields::Categories as u32)
+            }
+            OutputKind::Libraries => !(LibraryTableFields::Stubs as u32),
+            _ => 0xFFFF_FFFF,
+        }
+    }
+
     /// Returns all valid `OutputKind` values (all without `None` & `All`,
     /// which are for internal use)
     fn all() -> Vec<OutputKind> {
-        vec![OutputKind::Symbols, OutputKind::Sections, OutputKind::Segments, OutputKind::Crates]
+        vec![
+            OutputKind::Symbols,
+            OutputKind::Sections,
+            OutputKind::Segments,
+            OutputKind::Crates,
+            OutputKind::Regions,
+            OutputKind::Ram,
+            OutputKind::Categories,
+            OutputKind::Stats,
+            OutputKind::Imports,
+            OutputKind::Exports,
+            OutputKind::Libraries,
+            OutputKind::Tls,
+            OutputKind::Dominators,
+            OutputKind::Unreachable,
+            OutputKind::Debug,
+            OutputKind::Compression,
+            OutputKind::Members,
+            OutputKind::Relocations,
+            OutputKind::Generated,
+            OutputKind::CAbi,
+        ]
     }
 }
 
@@ -160,20 +345,37 @@ impl TryFrom<&str> for OutputKind {
         use OutputKind::*;
 
         match value {
-            "*"   | "all"      => Ok(All),
-            "sym" | "symbols"  => Ok(Symbols),
-            "sec" | "sections" => Ok(Sections),
-            "seg" | "segments" => Ok(Segments),
-            "cr"  | "crates"   => Ok(Crates),
-            _                  => Err(format!("Invalid output type '{}'", value)),
+            "*"   | "all"         => Ok(All),
+            "sym" | "symbols"     => Ok(Symbols),
+            "sec" | "sections"    => Ok(Sections),
+            "seg" | "segments"    => Ok(Segments),
+            "cr"  | "crates"      => Ok(Crates),
+            "reg" | "regions"     => Ok(Regions),
+            "ram"                 => Ok(Ram),
+            "cat" | "categories"  => Ok(Categories),
+            "st"  | "stats"       => Ok(Stats),
+            "imp" | "imports"     => Ok(Imports),
+            "exp" | "exports"     => Ok(Exports),
+            "lib" | "libraries"   => Ok(Libraries),
+            "tls"                 => Ok(Tls),
+            "dom" | "dominators"  => Ok(Dominators),
+            "unr" | "unreachable" => Ok(Unreachable),
+            "dbg" | "debug"       => Ok(Debug),
+            "z"   | "compression" => Ok(Compression),
+            "mem" | "members"     => Ok(Members),
+            "rel" | "relocations" => Ok(Relocations),
+            "gen" | "generated"   => Ok(Generated),
+            "abi" | "c-abi"       => Ok(CAbi),
+            _                     => Err(format!("Invalid output type '{}'", value)),
         }
     }
 }
 
 /// Stores allowed output tables, and their fields
+#[derive(Clone)]
 pub struct Output {
     /// Bitmask of `OutputKind`
-    outputs: u8,
+    outputs: u32,
 
     /// For each valid `OutputKind` store table-dependant bitmask
     ///
@@ -182,8 +384,19 @@ pub struct Output {
     /// * `Sections` - `SectionTableFields`
     /// * `Segments` - `SegmentTableFields`
     /// * `Crates` - `CrateTableFields`
+    /// * `Regions` - `SymbolTableFields`
+    /// * `Ram` - `SymbolTableFields` (for symbols part of the report)
+    /// * `Categories` - `CategoryTableFields`
+    /// * `Libraries` - `LibraryTableFields`
+    /// * `Tls` - `SymbolTableFields`
+    /// * `Dominators` - `SymbolTableFields`
+    /// * `Unreachable` - `SymbolTableFields`
     ///
-    fields: HashMap<OutputKind, u8>,
+    fields: HashMap<OutputKind, u32>,
+
+    /// Columns/fields, that are enabled, if output is enabled without a field list. Initially
+    /// `OutputKind::default_fields()`, can be overridden from config
+    defaults: HashMap<OutputKind, u32>,
 }
 
 impl Output {
@@ -194,17 +407,17 @@ impl Output {
 
     /// Enable an output for table denoted with `kind`
     pub fn enable(&mut self, kind: OutputKind) {
-        self.outputs |= kind as u8;
+        self.outputs |= kind as u32;
     }
 
     /// Disable an output for table denoted with `kind`
     pub fn disable(&mut self, kind: OutputKind) {
-        self.outputs &= !(kind as u8);
+        self.outputs &= !(kind as u32);
     }
 
     /// Returns true if table denoted by `kind` is enabled for output
     pub fn enabled(&self, kind: OutputKind) -> bool {
-        self.outputs & (kind as u8) != 0
+        self.outputs & (kind as u32) != 0
     }
 
     /// Returns `true` if any output is enabled
@@ -213,14 +426,14 @@ impl Output {
     }
 
     /// Disables a column `field` in table denoted by `kind`
-    pub fn field_disable(&mut self, kind: OutputKind, field: u8) {
+    pub fn field_disable(&mut self, kind: OutputKind, field: u32) {
         if let Some(value) = self.fields.get_mut(&kind) {
             *value &= !field;
         }
     }
 
     /// Returns true if column `field` in table denoted by `kind` is enabled for output
-    pub fn field_enabled(&self, kind: OutputKind, field: u8) -> bool {
+    pub fn field_enabled(&self, kind: OutputKind, field: u32) -> bool {
         if let Some(value) = self.fields.get(&kind) {
             value & field != 0
         } else {
@@ -241,7 +454,7 @@ impl Output {
     pub fn apply_pattern(&mut self, pattern: &str) {
         let mut enable = true;
         let output_kind: OutputKind;
-        let mut field_mask = 0;
+        let field_mask: u32;
 
         // If pattern start with `!` - it's a disable/disallow pattern, so invert `enable` and skip
         // first symbol (`!`)
@@ -259,23 +472,13 @@ impl Output {
             output_kind = OutputKind::try_from(kind)
                 .expect(format!("Unknown output kind: '{}'", kind).as_str());
 
-            // By parsing `OutputKind` first, we now know which `*TableFields` to use for
-            // column/fields parsing
-            for field in fields.split(',') {
-                match output_kind {
-                    OutputKind::Symbols  => update_field_mask_from!(field_mask, field, SymbolTableFields),
-                    OutputKind::Sections => update_field_mask_from!(field_mask, field, SectionTableFields),
-                    OutputKind::Segments => update_field_mask_from!(field_mask, field, SegmentTableFields),
-                    OutputKind::Crates   => update_field_mask_from!(field_mask, field, CrateTableFields),
-                    _                    => panic!("Can't specify output fields for '{}'", kind)
-            }
-            }
+            field_mask = Self::parse_fields(output_kind, kind, fields);
         } else {
             output_kind = OutputKind::try_from(pattern)
                 .expect(format!("Invalid output kind: '{}'", pattern).as_str());
 
-            // No column list, so enable all
-            field_mask = 0xFF;
+            // No column list, so enable defaults
+            field_mask = self.defaults.get(&output_kind).copied().unwrap_or(output_kind.default_fields());
         }
 
         if enable {
@@ -292,19 +495,67 @@ impl Output {
             }
         }
     }
+
+    /// Overrides default columns/fields of table `kind` (used when the output is enabled without
+    /// a field list). `fields` uses the same syntax, as in `--output KIND=FIELDS`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut output = Output::default();
+    /// output.set_default_fields("symbols", "size,percent,name");
+    /// ```
+    ///
+    pub fn set_default_fields(&mut self, kind: &str, fields: &str) {
+        let output_kind = OutputKind::try_from(kind)
+            .unwrap_or_else(|_| panic!("Unknown output kind: '{}'", kind));
+
+        let field_mask = Self::parse_fields(output_kind, kind, fields);
+
+        self.defaults.insert(output_kind, field_mask);
+        self.fields.insert(output_kind, field_mask);
+    }
+
+    /// Parses comma-separated list of `fields` of table `output_kind` (named `kind` by user)
+    /// into a bitmask
+    fn parse_fields(output_kind: OutputKind, kind: &str, fields: &str) -> u32 {
+        let mut field_mask = 0;
+
+        // By parsing `OutputKind` first, we now know which `*TableFields` to use for
+        // column/fields parsing
+        for field in fields.split(',').map(|f| f.trim()) {
+            match output_kind {
+                OutputKind::Symbols     => update_field_mask_from!(field_mask, field, SymbolTableFields),
+                OutputKind::Sections    => update_field_mask_from!(field_mask, field, SectionTableFields),
+                OutputKind::Segments    => update_field_mask_from!(field_mask, field, SegmentTableFields),
+                OutputKind::Crates      => update_field_mask_from!(field_mask, field, CrateTableFields),
+                OutputKind::Regions     => update_field_mask_from!(field_mask, field, SymbolTableFields),
+                OutputKind::Ram         => update_field_mask_from!(field_mask, field, SymbolTableFields),
+                OutputKind::Categories  => update_field_mask_from!(field_mask, field, CategoryTableFields),
+                OutputKind::Libraries   => update_field_mask_from!(field_mask, field, LibraryTableFields),
+                OutputKind::Tls         => update_field_mask_from!(field_mask, field, SymbolTableFields),
+                OutputKind::Dominators  => update_field_mask_from!(field_mask, field, SymbolTableFields),
+                OutputKind::Unreachable => update_field_mask_from!(field_mask, field, SymbolTableFields),
+                _                       => panic!("Can't specify output fields for '{}'", kind)
+            }
+        }
+
+        field_mask
+    }
 }
 
 impl Default for Output {
     fn default() -> Self {
         let mut out = Self {
             // By default, disallow all output
-            outputs: OutputKind::None as u8,
-            fields:  HashMap::new(),
+            outputs: OutputKind::None as u32,
+            fields:   HashMap::new(),
+            defaults: HashMap::new(),
         };
 
-        // By default, allow all columns to be printed
+        // By default, allow all non-optional columns to be printed
         for kind in OutputKind::all() {
-            out.fields.insert(kind, 0xFF);
+            out.fields.insert(kind, kind.default_fields());
         }
 
         out
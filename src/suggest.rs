@@ -0,0 +1,112 @@
+//! # `binsize::suggest`
+//!
+//! Implements size-reduction suggestions - simple heuristics over analyzed executable, that
+//! point at common sources of bloat and the usual ways to get rid of them
+//!
+
+use std::collections::HashMap;
+use crate::exe::ExecutableInfo;
+use crate::demangle;
+use crate::util;
+
+/// Formatting machinery is worth mentioning, if it takes this much of total symbol size (%)
+const FMT_THRESHOLD: f64 = 5.0;
+
+/// Generic function is reported, if it has at least this many instantiations
+const GENERIC_MIN_INSTANCES: usize = 5;
+
+/// ... and they take at least this many bytes together
+const GENERIC_MIN_SIZE: usize = 2048;
+
+/// Max number of generic functions to report
+const GENERIC_MAX_REPORTED: usize = 3;
+
+/// Unwinding tables are worth mentioning, if they take this much of file size (%)
+const UNWIND_THRESHOLD: f64 = 3.0;
+
+/// Debug info is worth mentioning, if it takes this much of file size (%)
+const DEBUG_THRESHOLD: f64 = 20.0;
+
+/// Single suggestion
+pub struct Suggestion {
+    /// What was found
+    pub finding: String,
+
+    /// What can be done about it
+    pub hint: String,
+
+    /// Size, that the finding is about
+    pub size: usize,
+}
+
+/// Inspects executable and returns suggestions, largest first
+pub fn suggest(exe: &ExecutableInfo) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+
+    let total = exe.symbols.iter().fold(0, |r, s| r + s.size);
+
+    let percent = |size: usize, of: usize| if of == 0 { 0.0 } else { size as f64 / of as f64 * 100.0 };
+
+    let fmt = exe.symbols.iter()
+        .filter(|s| s.name.starts_with("core::fmt::") || s.name.contains(" as core::fmt::"))
+        .fold(0, |r, s| r + s.size);
+
+    if percent(fmt, total) >= FMT_THRESHOLD {
+        suggestions.push(Suggestion {
+            finding: format!("Formatting machinery (core::fmt) takes {} bytes ({} of symbols)", util::format_size(fmt), util::format_percent(percent(fmt, total))),
+            hint: "Use `panic = \"abort\"` & `opt-level = \"z\"`, avoid `{:?}` and formatted panic messages, or try `ufmt`/`defmt`".to_string(),
+            size: fmt,
+        });
+    }
+
+    let mut generics: HashMap<String, (usize, usize)> = HashMap::new();
+
+    // Legacy mangling doesn't encode generic arguments of functions, so their instances only
+    // differ by hash, which is removed by demangler, and have the same name
+    for sym in exe.symbols.iter() {
+        let entry = generics.entry(demangle::fold_generics(&sym.name)).or_insert((0, 0));
+
+        entry.0 += 1;
+        entry.1 += sym.size;
+    }
+
+    let mut generics = generics.into_iter()
+        .filter(|(_, (count, size))| *count >= GENERIC_MIN_INSTANCES && *size >= GENERIC_MIN_SIZE)
+        .collect::<Vec<_>>();
+
+    generics.sort_by_key(|(_, (_, size))| std::cmp::Reverse(*size));
+
+    for (name, (count, size)) in generics.into_iter().take(GENERIC_MAX_REPORTED) {
+        suggestions.push(Suggestion {
+            finding: format!("`{}` is instantiated {} times, taking {} bytes", name, count, util::format_size(size)),
+            hint: "Accept `&dyn Trait` instead of a generic, or make it a thin generic shim over `#[inline(never)]` non-generic function".to_string(),
+            size,
+        });
+    }
+
+    let unwind = exe.unwind_size();
+
+    if percent(unwind, exe.file_size) >= UNWIND_THRESHOLD {
+        suggestions.push(Suggestion {
+            finding: format!("Unwinding tables take {} bytes ({} of file)", util::format_size(unwind), util::format_percent(percent(unwind, exe.file_size))),
+            hint: "Use `panic = \"abort\"`, if unwinding isn't needed".to_string(),
+            size: unwind,
+        });
+    }
+
+    let debug = exe.sections.iter()
+        .filter(|s| s.kind == crate::exe::SectionKind::Debug)
+        .fold(0, |r, s| r + s.size);
+
+    if percent(debug, exe.file_size) >= DEBUG_THRESHOLD {
+        suggestions.push(Suggestion {
+            finding: format!("Debug info takes {} bytes ({} of file)", util::format_size(debug), util::format_percent(percent(debug, exe.file_size))),
+            hint: "Use `strip = \"debuginfo\"` or `debug = false`, if file size matters (doesn't affect flashed size)".to_string(),
+            size: debug,
+        });
+    }
+
+    suggestions.sort_by_key(|s| std::cmp::Reverse(s.size));
+
+    suggestions
+}
@@ -3,11 +3,12 @@
 //! Implements simple `Table` for pretty-printing data
 //!
 
-use std::fmt::{Debug, Formatter};
+use std::fmt::{Debug, Display, Formatter, Write};
 use std::ops::{Index, IndexMut};
 
-use crate::attr_str::{AttributeString};
+use crate::attr_str::{self, Attribute, AttributeString};
 use crate::util;
+use crate::util::Stream;
 
 /// Represents left/right padding
 #[derive(Clone, Copy)]
@@ -27,18 +28,144 @@ impl Debug for Padding {
     }
 }
 
+/// Represents the way a value, that doesn't fit into a row, is shortened
+#[derive(Clone, Copy, PartialEq)]
+pub enum Truncate {
+    /// Cut the beginning of the value (`…::reserve_rehash`)
+    Start,
+
+    /// Cut the middle of the value (`hashbrown::raw…::reserve_rehash`)
+    Middle,
+
+    /// Cut the end of the value (`hashbrown::raw::RawTable<…`)
+    End,
+
+    /// Wrap the rest of the value onto the next lines
+    Wrap,
+}
+
+impl TryFrom<&str> for Truncate {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "start"  => Ok(Truncate::Start),
+            "middle" => Ok(Truncate::Middle),
+            "end"    => Ok(Truncate::End),
+            "wrap"   => Ok(Truncate::Wrap),
+            _        => Err(format!("Unknown truncation strategy: '{}' (possible values: start, middle, end, wrap)", value)),
+        }
+    }
+}
+
+impl Truncate {
+    /// Ellipsis, that replaces truncated part of the value
+    const ELLIPSIS: char = '…';
+
+    /// Shortens `value` to `width` terminal columns (including ellipsis), keeping attributes of
+    /// the remaining parts. Shouldn't be called with `Truncate::Wrap`, as wrapping is done while
+    /// printing
+    pub fn apply(&self, value: &AttributeString, width: usize) -> AttributeString {
+        let str = value.string().as_str();
+        let len = str.len();
+
+        if util::str_width(str) <= width {
+            return value.clone();
+        }
+
+        if width == 0 {
+            return value.excerpt(&[], "");
+        }
+
+        // Columns, that are left after placing ellipsis
+        let keep = width - util::char_width(Self::ELLIPSIS);
+        let ellipsis = Self::ELLIPSIS.to_string();
+
+        match self {
+            Truncate::Start => {
+                let tail = Self::take_end(str, keep);
+
+                value.excerpt(&[0..0, len - tail.len()..len], &ellipsis)
+            }
+            Truncate::Middle => {
+                // Suffix gets the bigger half, as it usually contains the most interesting part
+                // (function name after all the generics)
+                let head = keep / 2;
+                let (head, _) = util::split_at_width(str, head);
+                let tail = Self::take_end(str, keep - util::str_width(head));
+
+                value.excerpt(&[0..head.len(), len - tail.len()..len], &ellipsis)
+            }
+            Truncate::End | Truncate::Wrap => {
+                let mut head = value.clone();
+
+                head.truncate_display(keep);
+                head.push_str(&ellipsis);
+
+                head
+            }
+        }
+    }
+
+    /// Returns the longest suffix of `value`, that takes at most `width` terminal columns.
+    /// Embedded escape sequences are never split
+    fn take_end(value: &str, width: usize) -> &str {
+        let total = util::str_width(value);
+
+        if total <= width {
+            return value;
+        }
+
+        let (_, mut tail) = util::split_at_width(value, total - width);
+
+        // Wide character on the boundary doesn't fit into `width`
+        while util::str_width(tail) > width {
+            let (_, rest) = util::split_at_width(tail, 0);
+            let skip = rest.chars().next().map_or(rest.len(), |c| c.len_utf8());
+
+            tail = &rest[skip..];
+        }
+
+        tail
+    }
+}
+
+/// Width constraints of a table column, used to distribute `Table::max_width` between columns
+#[derive(Clone, Copy)]
+pub struct Constraint {
+    /// Min width of the column. If `None` - numeric columns are never shrunk, and other columns
+    /// are not shrunk below their header
+    pub min: Option<usize>,
+
+    /// Max width of the column. Longer values are shortened (or wrapped), even if there is space
+    pub max: usize,
+
+    /// If the row doesn't fit into `max_width`, columns with lower priority are shrunk first
+    /// (widest first, among columns with the same priority)
+    pub priority: u8,
+}
+
+impl Default for Constraint {
+    fn default() -> Self {
+        Self { min: None, max: usize::MAX, priority: 1 }
+    }
+}
+
 /// Represents Row of data in a table
 #[derive(Clone)]
 pub struct Row {
     /// Pack of values (column data)
     /// `AttributeString` is used here to enable multicolored rows to be printed
-    values: Vec<AttributeString>
+    values: Vec<AttributeString>,
+
+    /// Attributes of the whole row (e.g. background color), applied before attributes of values
+    attrs: Vec<Attribute>,
 }
 
 impl Row {
     /// Creates new Row from AttributeString slice
     pub fn new(values: &[AttributeString]) -> Self {
-        Self { values: Vec::from(values) }
+        Self { values: Vec::from(values), attrs: Vec::new() }
     }
 
     /// Returns length of value pack
@@ -50,12 +177,18 @@ impl Row {
     pub fn push(&mut self, value: AttributeString) {
         self.values.push(value);
     }
+
+    /// Pushes new attribute, applied to the whole row (including padding between values)
+    pub fn push_attr(&mut self, attr: Attribute) {
+        self.attrs.push(attr);
+    }
 }
 
 impl Default for Row {
     fn default() -> Self {
         Self {
-            values: Vec::new()
+            values: Vec::new(),
+            attrs:  Vec::new(),
         }
     }
 }
@@ -86,9 +219,9 @@ impl Debug for Row {
 /// Example:
 /// ```
 ///
-/// let mut table = Table::with_header_and_padding(
+/// // Numeric columns (`Address`, `Used`, `Size` and `Percentage`) are right-aligned automatically
+/// let mut table = Table::with_header(
 ///     Row::from(["Name ", "Address ", "Used ", "Size ", "Percentage "]),
-///     &[Padding::Left, Padding::Left, Padding::Right, Padding::Right, Padding::Right],
 /// );
 ///
 /// table.push_row(
@@ -108,20 +241,37 @@ pub struct Table {
     /// Table Header
     header: Row,
 
-    /// Padding for each column
+    /// Padding for each column. Columns without explicit padding are right-aligned, if all of
+    /// their values are numeric, and left-aligned otherwise
     padding: Vec<Padding>,
 
+    /// Whether each column contains only numeric values, updated on push
+    numeric: Vec<bool>,
+
     /// Row data
     rows: Vec<Row>,
 
-    /// Maximal width of each column, updated on push
+    /// Row, printed after a separator below all rows (e.g. totals)
+    footer: Option<Row>,
+
+    /// Maximal width of each column's values, updated on push. Actual widths are calculated by
+    /// `layout()` from these and `constraints`
     widths: Vec<usize>,
 
+    /// Width constraints of columns. Columns without explicit constraint use the default one
+    constraints: Vec<Constraint>,
+
     /// Max width of single row. If 0 - will be initialized from `util::term_width()`
     max_width: usize,
 
     /// Max rows to output
     max_rows: usize,
+
+    /// How values, that don't fit into `max_width`, are shortened
+    truncate: Truncate,
+
+    /// Attributes of every second row (zebra striping). Empty - no striping
+    stripe: Vec<Attribute>,
 }
 
 impl Table {
@@ -130,30 +280,22 @@ impl Table {
         let mut table = Self {
             header,
             padding:   padding.to_vec(),
+            numeric:   vec![],
             rows:      vec![],
+            footer:    None,
             widths:    vec![],
+            constraints: vec![],
             max_width: if max_width == 0 { util::term_width() } else { max_width },
-            max_rows:  if max_rows == 0 { usize::MAX } else { max_rows }
+            max_rows:  if max_rows == 0 { usize::MAX } else { max_rows },
+            truncate:  Truncate::Wrap,
+            stripe:    vec![],
         };
 
-        // Total size of header row in symbols
-        let mut size = 0;
-
         for val in table.header.values.iter() {
-            // Current column size
-            let mut col_size = val.len();
-
-            // If size of already processed columns and size of current column exceeds `max_width`
-            if size + col_size > table.max_width {
-                // Trim `col_size` to space, that's left (`max_width` - `size`)
-                col_size = table.max_width - size;
-            }
+            table.widths.push(val.len());
 
-            // Push `col_size` to cached widths
-            table.widths.push(col_size);
-
-            // Update header row size
-            size += col_size;
+            // Column is considered numeric until a non-numeric value is pushed into it
+            table.numeric.push(true);
         }
 
         for row in rows {
@@ -169,11 +311,6 @@ impl Table {
         Self::new(header, &[], &[], 0, 0)
     }
 
-    /// Creates new table, given only the header and padding for each column
-    pub fn with_header_and_padding(header: Row, padding: &[Padding]) -> Self {
-        Self::new(header, padding, &[], 0, 0)
-    }
-
     /// Creates new table with empty header, from number of columns
     pub fn with_empty_header(values: usize) -> Self {
         let mut header = Row::default();
@@ -199,6 +336,25 @@ impl Table {
         self.max_rows = if max_rows == 0 { usize::MAX } else { max_rows };
     }
 
+    /// Set `truncate` value
+    pub fn set_truncate(&mut self, truncate: Truncate) {
+        self.truncate = truncate;
+    }
+
+    /// Set attributes of every second row (e.g. background color for zebra striping)
+    pub fn set_stripe(&mut self, attrs: Vec<Attribute>) {
+        self.stripe = attrs;
+    }
+
+    /// Set width constraint of `column`
+    pub fn set_constraint(&mut self, column: usize, constraint: Constraint) {
+        if self.constraints.len() <= column {
+            self.constraints.resize(column + 1, Constraint::default());
+        }
+
+        self.constraints[column] = constraint;
+    }
+
     /// Checks that row has same number of elements as the header
     fn check_row(&self, data: &[AttributeString]) -> Result<(), String> {
         if !self.header.values.is_empty() && data.len() != self.header.len() {
@@ -212,150 +368,294 @@ impl Table {
     pub fn push_row(&mut self, row: Row) -> Result<(), String> {
         self.check_row(&row.values)?;
 
-        // Total size of row in symbols
-        let mut size: usize = 0;
-
         for (i, value) in row.values.iter().enumerate() {
-            // Size of column
-            let mut col_size = value.len();
-
-            // If size of already processed columns and size of current column exceeds `max_width`
-            if size + col_size > self.max_width {
-                // Trim `col_size` to space, that's left (`max_width` - `size`)
-                col_size = self.max_width - size - 1;
-            }
-
-            // If `col_size` is bigger than cached max width for current column
-            if col_size > self.widths[i] {
-                // Update cached value
-                self.widths[i] = col_size;
-            } else {
-                // Check if cached value doesn't already exceed `max_width`
-                if size + self.widths[i] > self.max_width {
-                    // Reduce cached value to actual max value for this column
-                    self.widths[i] = col_size;
-                }
-                // Set `col_size` to relevant value from cache
-                col_size = self.widths[i];
+            if !Self::is_numeric(value.string()) {
+                self.numeric[i] = false;
             }
-
-            // Update row size
-            size += col_size + 1;
         }
 
+        self.update_widths(&row);
+
         // Save row
         self.rows.push(row);
 
         Ok(())
     }
 
-    /// Prints overflowed part of the column
-    fn print_overflow(val: &AttributeString, overflowed: &str, size: usize) {
-        // If overflowed text is present - remove attributes (so that, for example BG
-        // color isn't printed to the end on the line)
-        val.attrs_reset();
+    /// Set footer row, which is printed after a separator below all rows, and isn't affected
+    /// by `max_rows` (e.g. totals, or collapsed rows). Footer values count towards column
+    /// widths, but don't affect whether column is numeric (right-aligned)
+    pub fn set_footer(&mut self, row: Row) -> Result<(), String> {
+        self.check_row(&row.values)?;
+
+        self.update_widths(&row);
+
+        self.footer = Some(row);
+
+        Ok(())
+    }
+
+    /// Updates cached widths of columns with values from `row`
+    fn update_widths(&mut self, row: &Row) {
+        for (i, value) in row.values.iter().enumerate() {
+            self.widths[i] = self.widths[i].max(value.len());
+        }
+    }
+
+    /// Calculates actual width of each column, so that the row fits into `max_width`. Columns
+    /// are shrunk according to their constraints: lowest priority first, widest first among
+    /// columns with the same priority, but not below min width
+    fn layout(&self) -> Vec<usize> {
+        let constraint = |i: usize| self.constraints.get(i).copied().unwrap_or_default();
+
+        let mut widths = Vec::with_capacity(self.widths.len());
+        let mut mins = Vec::with_capacity(self.widths.len());
+
+        for (i, width) in self.widths.iter().enumerate() {
+            let width = (*width).min(constraint(i).max);
+
+            let min = constraint(i).min.unwrap_or(if self.numeric[i] { width } else { self.header[i].len() });
+
+            widths.push(width);
+            mins.push(min.min(width));
+        }
+
+        let mut excess = widths.iter().sum::<usize>().saturating_sub(self.max_width);
+
+        while excess > 0 {
+            let column = (0..widths.len())
+                .filter(|i| widths[*i] > mins[*i])
+                .min_by_key(|i| (constraint(*i).priority, std::cmp::Reverse(widths[*i]), std::cmp::Reverse(*i)));
+
+            // Every column is at its min width - row will overflow `max_width`
+            let Some(column) = column else {
+                break;
+            };
+
+            widths[column] -= 1;
+            excess -= 1;
+        }
+
+        widths
+    }
+
+    /// Returns `true` if value looks like a number - integer, float, hex (`0x...`) or percentage.
+    /// Empty values don't affect column alignment, so they're considered numeric too
+    fn is_numeric(value: &str) -> bool {
+        let value = util::strip_escapes(value);
+        let value = value.trim();
+        let value = value.strip_suffix('%').unwrap_or(value);
+
+        // Digits may be grouped (`--group-digits`). Separators alone aren't a number though
+        let ungrouped = value.replace(['_', ','], "");
+        let value = if ungrouped.chars().any(|c| c.is_ascii_digit()) { ungrouped.as_str() } else { value };
 
-        // Print newline
-        println!();
+        if value.is_empty() {
+            return true;
+        }
+
+        if let Some(hex) = value.strip_prefix("0x") {
+            return usize::from_str_radix(hex, 16).is_ok();
+        }
+
+        // Digit check filters out `inf`/`NaN`, which are parsed by `f64`, but aren't numbers
+        value.chars().any(|c| c.is_ascii_digit()) && value.parse::<f64>().is_ok()
+    }
+
+    /// Returns padding of `i`-th column - either explicit one, or one derived from column
+    /// contents (numeric columns are right-aligned)
+    fn column_padding(&self, i: usize) -> Padding {
+        if i < self.padding.len() {
+            self.padding[i]
+        } else if self.numeric[i] {
+            Padding::Right
+        } else {
+            Padding::Left
+        }
+    }
+
+    /// Returns amount of spaces needed to pad `str` to `width` terminal columns
+    ///
+    /// Note: `{:width$}` can't be used for this, as it counts characters and not their display
+    /// width, so any wide character would break the alignment
+    fn fill(str: &str, width: usize) -> String {
+        " ".repeat(width.saturating_sub(util::str_width(str)))
+    }
+
+    /// Splits `value` into lines of at most `width` terminal columns
+    fn wrap(value: &AttributeString, width: usize) -> Vec<AttributeString> {
+        let str = value.string().as_str();
+
+        let mut lines = Vec::new();
+        let mut start = 0;
+
+        while util::str_width(&str[start..]) > width {
+            let (current, _) = util::split_at_width(&str[start..], width);
+
+            // Single character can't fit into the width - nothing more can be done
+            if current.is_empty() {
+                break;
+            }
+
+            lines.push(value.slice(start..start + current.len()));
+            start += current.len();
+        }
 
-        // Reapply attributes
-        val.attrs_apply();
+        lines.push(value.slice(start..str.len()));
 
-        // Print overflowed text in the next line, left-padded with spaces to the start
-        // of original column
-        print!("{:width$}{}", "", overflowed, width = size);
+        lines
     }
 
-    /// Prints single row
+    /// Writes single row
     ///
     /// Will use
     ///  - `Self::padding` to correctly pad the value in each column and
     ///  - `AttributeString::attrs` to colorize the string
+    ///  - `row_attrs` (row's own attributes and stripe) to colorize the whole row
+    ///
+    /// Values, that can't fit into their column `widths` (see `layout()`), are either shortened
+    /// (see `Truncate`), or wrapped onto continuation lines, which are aligned under their own
+    /// column, while other columns are left blank
     ///
     /// `ignore_empty` - will not print, if at least one of the values is empty
     ///
-    fn print_row(&self,row: &Vec<AttributeString>, ignore_empty: bool) {
-        // Total size of row in symbols
-        let mut size = 0;
+    fn write_row(
+        &self,
+        w:            &mut impl Write,
+        row:          &[AttributeString],
+        row_attrs:    &[&[Attribute]],
+        widths:       &[usize],
+        ignore_empty: bool
+    ) -> std::fmt::Result {
+        if ignore_empty && row.iter().any(|val| val.len() == 0) {
+            return Ok(());
+        }
+
+        // Lines of each column value
+        let mut cells = Vec::with_capacity(row.len());
 
         for (i, val) in row.iter().enumerate() {
-            if ignore_empty && val.len() == 0 {
-                return;
-            }
+            // Shortened value keeps a space before the next column
+            let space = widths[i].saturating_sub(1).max(1);
 
-            // Creates `str` - column value, trimmed to `max_width` (if needed), and `overflowed` -
-            // leftover/trimmed part of the column, which can't fit into original row
-            let (str, overflowed) =  if size + val.len() > self.max_width {
-                // If current column can't fit - split it into 2 parts - first is printed in
-                // current column (and fits into `max_width` along with everything that was already
-                // printed), and second - which is padded, and printed in the next row
-                let (part1, part2) = val.string().split_at(self.max_width - size - 1);
-                (part1, Some(part2))
+            cells.push(if val.len() <= widths[i] {
+                vec![val.clone()]
+            } else if self.truncate != Truncate::Wrap {
+                vec![self.truncate.apply(val, space)]
             } else {
-                // If current column fits - return it as-is
-                (val.string().as_str(), None)
-            };
+                Self::wrap(val, space)
+            });
+        }
 
-            // Applies any text/color modifications
-            val.attrs_apply();
+        let height = cells.iter().map(|lines| lines.len()).max().unwrap_or(1);
 
-            match if i >= self.padding.len() {
-                Padding::None
-            } else {
-                self.padding[i]
-            } {
-                Padding::None => {
-                    print!("{}", str);
-                }
-                Padding::Left => {
-                    print!("{:width$}", str, width = self.widths[i]);
+        for line in 0..height {
+            for (i, (val, lines)) in row.iter().zip(cells.iter()).enumerate() {
+                let part = lines.get(line);
+                let str = part.map(|p| p.string().as_str()).unwrap_or("");
+
+                // Row attributes cover the whole line, while blank parts of continuation lines
+                // are left without value's own attributes (so that, for example BG color of the
+                // value isn't printed there)
+                for attrs in row_attrs {
+                    attr_str::write_attrs(w, attrs)?;
                 }
-                Padding::Right => {
-                    print!("{:>width$}", str, width = self.widths[i]);
+
+                if line == 0 || !str.is_empty() {
+                    val.write_attrs(w)?;
                 }
-            }
 
-            // If overflowed text is present
-            if let Some(overflowed) = overflowed {
-                // Redeclare for mutability
-                let mut overflowed = overflowed;
+                let fill = Self::fill(str, widths[i]);
 
-                // While can split at max width (in other words - while overflowed text is present)
-                while let Some((current, next)) = overflowed.split_at_checked(self.max_width - size) {
-                    // Print first part of overflowed text (sliced at `max_width`, so it can fit)
-                    Self::print_overflow(val, current, size);
+                let (before, after) = match self.column_padding(i) {
+                    // Continuation lines have to keep other columns aligned
+                    Padding::None if line == 0    => ("", ""),
+                    Padding::None | Padding::Left => ("", fill.as_str()),
+                    Padding::Right                => (fill.as_str(), ""),
+                };
 
-                    // Set overflowed to the rest of overflowed text, which wasn't printed
-                    overflowed = next;
+                w.write_str(before)?;
+
+                if let Some(part) = part {
+                    part.write_str_within(w, row_attrs)?;
                 }
 
-                // Print last overflowed part
-                Self::print_overflow(val, overflowed, size);
-            }
+                w.write_str(after)?;
 
-            // Resets all text modifications
-            val.attrs_reset();
+                // Resets all text modifications
+                if line == 0 || !str.is_empty() || row_attrs.iter().any(|attrs| !attrs.is_empty()) {
+                    val.write_reset(w)?;
+                }
+            }
 
-            // Update size with max width of current column
-            size += self.widths[i];
+            writeln!(w)?;
         }
 
-        println!();
+        Ok(())
     }
 
-    /// Prints whole table
-    pub fn print(&self) {
+    /// Renders whole table into `w`
+    pub fn render(&self, w: &mut impl Write) -> std::fmt::Result {
         // `ignore_empty` is used to print tables without the header
         // For example in `ArgumentParser::print_help()`
-        self.print_row(&self.header.values, true);
+        let widths = self.layout();
+
+        self.write_row(w, &self.header.values, &[&self.header.attrs], &widths, true)?;
 
         for (i, row) in self.rows.iter().enumerate() {
-            if i > self.max_rows {
+            if i >= self.max_rows {
                 break;
             }
 
-            self.print_row(&row.values, false);
+            let stripe: &[Attribute] = if i % 2 == 1 { &self.stripe } else { &[] };
+
+            self.write_row(w, &row.values, &[stripe, &row.attrs], &widths, false)?;
         }
+
+        self.write_footer(w, &widths, self.rows.len().saturating_sub(self.max_rows))
+    }
+
+    /// Renders footer into `w` - a separator, followed by the number of rows, cut by `max_rows`
+    /// (if any), and the footer row (if set)
+    fn write_footer(&self, w: &mut impl Write, widths: &[usize], hidden: usize) -> std::fmt::Result {
+        if hidden == 0 && self.footer.is_none() {
+            return Ok(());
+        }
+
+        writeln!(w, "{}", "-".repeat(widths.iter().sum::<usize>()))?;
+
+        if hidden != 0 {
+            writeln!(w, "… {} more rows", hidden)?;
+        }
+
+        if let Some(footer) = &self.footer {
+            self.write_row(w, &footer.values, &[&footer.attrs], widths, false)?;
+        }
+
+        Ok(())
+    }
+
+    /// Renders whole table into `w` (e.g. a file)
+    pub fn write_to(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        w.write_all(self.to_string().as_bytes())
+    }
+
+    /// Prints whole table into the stream, selected by `util::set_human_stream()`
+    pub fn print(&self) {
+        let result = match util::human_stream() {
+            Stream::Stdout => self.write_to(&mut std::io::stdout().lock()),
+            Stream::Stderr => self.write_to(&mut std::io::stderr().lock()),
+        };
+
+        // Same as `print!`, which panics, if output can't be written
+        result.expect("Failed to print table");
+    }
+}
+
+/// Renders the table, so it can be formatted into a string
+impl Display for Table {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.render(f)
     }
 }
 
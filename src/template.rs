@@ -0,0 +1,264 @@
+//! # `binsize::template`
+//!
+//! Implements a small template engine for custom reports (`--template`). Syntax is a subset of
+//! Handlebars: `{{path}}` values, `{{#each}}`, `{{#if}}` & `{{#unless}}` blocks with optional
+//! `{{else}}`, `{{! comments }}` and `~` whitespace control. Templates are rendered over the same
+//! data as `--format json`
+//!
+
+use std::borrow::Cow;
+use json::JsonValue;
+
+/// Part of a parsed template
+enum Node {
+    /// Literal text
+    Text(String),
+
+    /// Value at path (`{{stats.symbols}}`)
+    Value(String),
+
+    /// `{{#each path}}` block - body is rendered for each element of an array (or each value of
+    /// an object), `{{else}}` branch - if there are none
+    Each(String, Vec<Node>, Vec<Node>),
+
+    /// `{{#if path}}` (or `{{#unless path}}` if `bool` is `true`) block - body & `{{else}}` branch
+    If(String, bool, Vec<Node>, Vec<Node>),
+}
+
+/// Piece of template source - either literal text, or contents of a `{{...}}` tag
+enum Token {
+    Text(String),
+    Tag(String),
+}
+
+/// Context of rendering - value of current `{{#each}}` element (or the root) & its position
+struct Scope<'a> {
+    value: &'a JsonValue,
+    index: usize,
+    len:   usize,
+    key:   Option<&'a str>,
+}
+
+/// Parsed template
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    /// Parses template `source`
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut tokens = tokenize(source)?.into_iter();
+
+        let (nodes, _) = parse_nodes(&mut tokens, None)?;
+
+        Ok(Self { nodes })
+    }
+
+    /// Renders template over `data`. Missing values are rendered as empty strings & are false
+    /// in conditions, so templates work regardless of which tables are enabled
+    pub fn render(&self, data: &JsonValue) -> String {
+        let mut out = String::new();
+        let mut scopes = vec![Scope { value: data, index: 0, len: 1, key: None }];
+
+        render_nodes(&self.nodes, &mut scopes, &mut out);
+
+        out
+    }
+}
+
+/// Splits `source` into text & tags. `~` at the start (end) of a tag trims whitespace of the
+/// text before (after) it, so block tags can be placed on their own lines
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    let mut trim_next = false;
+
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+
+        let Some(end) = after.find("}}") else {
+            return Err(format!("Unclosed tag at line {}", source[..source.len() - rest.len() + start].lines().count().max(1)));
+        };
+
+        let mut text = &rest[..start];
+        let mut tag = &after[..end];
+
+        if trim_next {
+            text = text.trim_start();
+        }
+
+        if let Some(stripped) = tag.strip_prefix('~') {
+            tag = stripped;
+            text = text.trim_end();
+        }
+
+        trim_next = false;
+
+        if let Some(stripped) = tag.strip_suffix('~') {
+            tag = stripped;
+            trim_next = true;
+        }
+
+        tokens.push(Token::Text(text.to_string()));
+        tokens.push(Token::Tag(tag.trim().to_string()));
+
+        rest = &after[end + 2..];
+    }
+
+    tokens.push(Token::Text(if trim_next { rest.trim_start() } else { rest }.to_string()));
+
+    Ok(tokens)
+}
+
+/// Parses `tokens` into nodes, until the end of block `block` (`None` for top level). Returns
+/// nodes & `true` if block body ended with `{{else}}`
+fn parse_nodes(tokens: &mut impl Iterator<Item = Token>, block: Option<&str>) -> Result<(Vec<Node>, bool), String> {
+    let mut nodes = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        let tag = match token {
+            Token::Text(text) => {
+                if !text.is_empty() {
+                    nodes.push(Node::Text(text));
+                }
+
+                continue;
+            }
+            Token::Tag(tag) => tag,
+        };
+
+        if tag.starts_with('!') {
+            continue;
+        }
+
+        if tag == "else" {
+            return match block {
+                Some(_) => Ok((nodes, true)),
+                None    => Err("'{{else}}' outside of a block".to_string()),
+            };
+        }
+
+        if let Some(name) = tag.strip_prefix('/') {
+            return match block {
+                Some(block) if block == name.trim() => Ok((nodes, false)),
+                Some(block) => Err(format!("'{{{{/{}}}}}' closes '{{{{#{}}}}}' block", name.trim(), block)),
+                None        => Err(format!("'{{{{/{}}}}}' without opening tag", name.trim())),
+            };
+        }
+
+        let Some(open) = tag.strip_prefix('#') else {
+            nodes.push(Node::Value(tag));
+            continue;
+        };
+
+        let (name, path) = open.split_once(char::is_whitespace).unwrap_or((open, ""));
+        let path = path.trim().to_string();
+
+        if !matches!(name, "each" | "if" | "unless") {
+            return Err(format!("Unknown block '{}' (possible values: each, if, unless)", name));
+        }
+
+        if path.is_empty() {
+            return Err(format!("Missing path in '{{{{#{}}}}}'", name));
+        }
+
+        let (body, has_else) = parse_nodes(tokens, Some(name))?;
+
+        let otherwise = match has_else {
+            true => match parse_nodes(tokens, Some(name))? {
+                (_, true)       => return Err(format!("Duplicate '{{{{else}}}}' in '{{{{#{}}}}}' block", name)),
+                (nodes, false)  => nodes,
+            },
+            false => Vec::new(),
+        };
+
+        nodes.push(match name {
+            "each" => Node::Each(path, body, otherwise),
+            _      => Node::If(path, name == "unless", body, otherwise),
+        });
+    }
+
+    match block {
+        Some(block) => Err(format!("Unclosed '{{{{#{}}}}}' block", block)),
+        None        => Ok((nodes, false)),
+    }
+}
+
+/// Renders `nodes` into `out`
+fn render_nodes<'a>(nodes: &[Node], scopes: &mut Vec<Scope<'a>>, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Value(path) => {
+                let value = lookup(scopes, path);
+
+                if !value.is_null() {
+                    out.push_str(&value.to_string());
+                }
+            }
+            Node::If(path, negate, body, otherwise) => {
+                // `is_empty()` is true for null, false, 0, "" and empty arrays & objects
+                match lookup(scopes, path).is_empty() == *negate {
+                    true  => render_nodes(body, scopes, out),
+                    false => render_nodes(otherwise, scopes, out),
+                }
+            }
+            Node::Each(path, body, otherwise) => {
+                let items = match lookup(scopes, path) {
+                    Cow::Borrowed(value) if value.is_array() => value.members().map(|v| (None, v)).collect(),
+                    Cow::Borrowed(value) => value.entries().map(|(k, v)| (Some(k), v)).collect(),
+                    Cow::Owned(_) => Vec::new(),
+                };
+
+                if items.is_empty() {
+                    render_nodes(otherwise, scopes, out);
+                }
+
+                let len = items.len();
+
+                for (index, (key, value)) in items.into_iter().enumerate() {
+                    scopes.push(Scope { value, index, len, key });
+                    render_nodes(body, scopes, out);
+                    scopes.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Resolves `path` - either `@index`/`@first`/`@last`/`@key` of current `{{#each}}` element, or
+/// dot-separated keys (array indices), starting at `this` (current element), or at the innermost
+/// scope, that has the first key
+fn lookup<'a>(scopes: &[Scope<'a>], path: &str) -> Cow<'a, JsonValue> {
+    let scope = scopes.last().unwrap();
+
+    match path {
+        "@index" => return Cow::Owned(scope.index.into()),
+        "@first" => return Cow::Owned((scope.index == 0).into()),
+        "@last"  => return Cow::Owned((scope.index + 1 == scope.len).into()),
+        "@key"   => return Cow::Owned(scope.key.map_or(JsonValue::Null, |key| key.into())),
+        _        => {}
+    }
+
+    let mut segments = path.split('.');
+    let first = segments.next().unwrap_or_default();
+
+    let mut value = match first {
+        "this" => scope.value,
+        _      => scopes.iter().rev().map(|s| child(s.value, first)).find(|v| !v.is_null()).unwrap_or(&JsonValue::Null),
+    };
+
+    for segment in segments {
+        value = child(value, segment);
+    }
+
+    Cow::Borrowed(value)
+}
+
+/// Returns element of array `value` by index, or value of object `value` by key
+fn child<'a>(value: &'a JsonValue, segment: &str) -> &'a JsonValue {
+    match segment.parse::<usize>() {
+        Ok(index) if value.is_array() => &value[index],
+        _                             => &value[segment],
+    }
+}
@@ -5,6 +5,8 @@
 
 use std::io;
 use std::mem;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 const DEFAULT_MAX_TERM_COLS: usize = 80;
 
@@ -15,7 +17,71 @@ pub enum SortOrder {
     Descending,
 }
 
-/// Unix (Linux/Mac) version of `terminal_size` - returns `(cols, rows)` if available
+impl SortOrder {
+    /// Applies order to an ordering, which was calculated in ascending order
+    pub fn apply(&self, ordering: std::cmp::Ordering) -> std::cmp::Ordering {
+        match self {
+            SortOrder::Ascending  => ordering,
+            SortOrder::Descending => ordering.reverse(),
+        }
+    }
+}
+
+impl TryFrom<&str> for SortOrder {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "asc"  => Ok(SortOrder::Ascending),
+            "desc" => Ok(SortOrder::Descending),
+            _      => Err(format!("Invalid sorting order: '{}' (possible values: asc, desc)", value)),
+        }
+    }
+}
+
+/// Single sorting key - field name and an order, e.g. `size:desc`
+#[derive(Clone)]
+pub struct SortKey {
+    /// Field name, meaning of which depends on what is being sorted
+    pub field: String,
+
+    /// Sorting order for this field
+    pub order: SortOrder,
+}
+
+impl SortKey {
+    /// Parses comma separated list of keys, like `size:desc,name:asc`. If order is omitted,
+    /// ascending is used
+    pub fn parse_list(value: &str) -> Result<Vec<SortKey>, String> {
+        value.split(',')
+            .map(|key| {
+                let (field, order) = key.split_once(':').unwrap_or((key, "asc"));
+
+                Ok(SortKey {
+                    field: field.trim().to_string(),
+                    order: SortOrder::try_from(order.trim())?,
+                })
+            })
+            .collect()
+    }
+
+    /// Compares two values using a list of keys. Each key is tried in order, until one of them
+    /// distinguishes the values. `cmp` compares values by a single field in ascending order
+    pub fn compare<T>(
+        keys: &[SortKey],
+        a: &T,
+        b: &T,
+        cmp: impl Fn(&str, &T, &T) -> std::cmp::Ordering
+    ) -> std::cmp::Ordering {
+        keys.iter()
+            .map(|key| key.order.apply(cmp(key.field.as_str(), a, b)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+/// Unix (Linux/Mac) version of `terminal_size` - returns `(cols, rows)` of the terminal, that
+/// human-readable output goes to (see `human_stream()`), if available
 #[cfg(unix)]
 pub fn terminal_size() -> io::Result<(u16, u16)> {
     use std::os::unix::io::AsRawFd;
@@ -28,7 +94,10 @@ pub fn terminal_size() -> io::Result<(u16, u16)> {
         ws_ypixel: u16,
     }
 
-    let fd = std::io::stdout().as_raw_fd();
+    let fd = match human_stream() {
+        Stream::Stdout => std::io::stdout().as_raw_fd(),
+        Stream::Stderr => std::io::stderr().as_raw_fd(),
+    };
     let mut ws: WinSize = unsafe { mem::zeroed() };
     let result = unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut ws) };
 
@@ -39,15 +108,21 @@ pub fn terminal_size() -> io::Result<(u16, u16)> {
     }
 }
 
-/// Windows version of `terminal_size` - returns `(cols, rows)` if available
+/// Windows version of `terminal_size` - returns `(cols, rows)` of the console, that
+/// human-readable output goes to (see `human_stream()`), if available
 #[cfg(windows)]
 pub fn terminal_size() -> io::Result<(u16, u16)> {
     use winapi::um::wincon::{GetConsoleScreenBufferInfo, CONSOLE_SCREEN_BUFFER_INFO};
     use winapi::um::processenv::GetStdHandle;
-    use winapi::um::winbase::STD_OUTPUT_HANDLE;
+    use winapi::um::winbase::{STD_ERROR_HANDLE, STD_OUTPUT_HANDLE};
+
+    let handle = match human_stream() {
+        Stream::Stdout => STD_OUTPUT_HANDLE,
+        Stream::Stderr => STD_ERROR_HANDLE,
+    };
 
     unsafe {
-        let h = GetStdHandle(STD_OUTPUT_HANDLE);
+        let h = GetStdHandle(handle);
         let mut csbi: CONSOLE_SCREEN_BUFFER_INFO = mem::zeroed();
 
         if GetConsoleScreenBufferInfo(h, &mut csbi) == 0 {
@@ -61,11 +136,313 @@ pub fn terminal_size() -> io::Result<(u16, u16)> {
 }
 
 
-/// Shortcut to `terminal_size().cols`, if available, otherwise returns default max cols
+/// Terminal width, set by `set_term_width()`. 0 - not set
+static TERM_WIDTH: AtomicUsize = AtomicUsize::new(0);
+
+/// Overrides terminal width, returned by `term_width()`. 0 - detect it
+pub fn set_term_width(width: usize) {
+    TERM_WIDTH.store(width, Ordering::Relaxed);
+}
+
+/// Returns terminal width: either the one set by `set_term_width()`, or `terminal_size().cols`,
+/// if available. If output isn't a terminal (CI, pipes), `COLUMNS` environment variable is
+/// used, and if it's not set either - default max cols
 pub fn term_width() -> usize {
+    let width = TERM_WIDTH.load(Ordering::Relaxed);
+
+    if width != 0 {
+        return width;
+    }
+
     match terminal_size() {
-        Ok((cols, _)) => (cols - 1) as usize,
-        Err(_)        => DEFAULT_MAX_TERM_COLS,
+        Ok((cols, _)) if cols > 0 => (cols - 1) as usize,
+        _ => std::env::var("COLUMNS").ok()
+            .and_then(|cols| cols.trim().parse::<usize>().ok())
+            .filter(|cols| *cols > 0)
+            .map_or(DEFAULT_MAX_TERM_COLS, |cols| cols - 1),
+    }
+}
+
+
+/// Separator of digit groups, set by `set_number_format()`. 0 - digits aren't grouped
+static DIGIT_SEPARATOR: AtomicU32 = AtomicU32::new(0);
+
+/// Number of decimal places in percentages, set by `set_number_format()`
+static PERCENT_PRECISION: AtomicUsize = AtomicUsize::new(2);
+
+/// If `true` - sizes are formatted in hexadecimal, set by `set_number_format()`
+static HEX_SIZES: AtomicBool = AtomicBool::new(false);
+
+/// Sets how `format_int()`, `format_size()` & `format_percent()` format numbers in tables
+pub fn set_number_format(separator: Option<char>, precision: usize, hex_sizes: bool) {
+    DIGIT_SEPARATOR.store(separator.map_or(0, |c| c as u32), Ordering::Relaxed);
+    PERCENT_PRECISION.store(precision, Ordering::Relaxed);
+    HEX_SIZES.store(hex_sizes, Ordering::Relaxed);
+}
+
+/// Formats integer `value` (possibly with a sign, e.g. `format!("{:+}", delta)`), splitting
+/// its digits into groups of three, if separator is set
+pub fn format_int(value: impl std::fmt::Display) -> String {
+    let str = value.to_string();
+
+    let Some(separator) = char::from_u32(DIGIT_SEPARATOR.load(Ordering::Relaxed)).filter(|c| *c != '\0') else {
+        return str;
+    };
+
+    let (sign, digits) = str.split_at(str.len() - str.trim_start_matches(['+', '-']).len());
+
+    let mut grouped = sign.to_string();
+
+    for (i, c) in digits.chars().enumerate() {
+        if i != 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(separator);
+        }
+
+        grouped.push(c);
+    }
+
+    grouped
+}
+
+/// Formats size in bytes - in hexadecimal (`0x1f40`), if enabled, or like `format_int()`
+pub fn format_size(size: usize) -> String {
+    match HEX_SIZES.load(Ordering::Relaxed) {
+        true  => format!("0x{:x}", size),
+        false => format_int(size),
+    }
+}
+
+/// Formats percentage `value` with precision, set by `set_number_format()` (2 by default)
+pub fn format_percent(value: impl Into<f64>) -> String {
+    format!("{:.*}%", PERCENT_PRECISION.load(Ordering::Relaxed), value.into())
+}
+
+
+/// Returns display width of `s` in terminal columns (wide CJK characters take 2 columns,
+/// combining characters & embedded escape sequences take none)
+pub fn str_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut rest = s;
+
+    while let Some(pos) = rest.find('\x1b') {
+        width += rest[..pos].width();
+        rest = &rest[pos + escape_len(&rest[pos..])..];
+    }
+
+    width + rest.width()
+}
+
+/// Returns length in bytes of escape sequence at the start of `s` (which must start with ESC):
+/// CSI (`ESC [ ... m`), OSC (`ESC ] ... ST`, e.g. hyperlinks) or two-byte `ESC X`. Unterminated
+/// sequence takes the rest of `s`
+fn escape_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+
+    match bytes.get(1) {
+        // Parameter & intermediate bytes, followed by a final byte in `@`..`~`
+        Some(b'[') => bytes[2..].iter()
+            .position(|b| (0x40..=0x7e).contains(b))
+            .map_or(s.len(), |pos| pos + 3),
+        // Terminated by BEL or ST (`ESC \`)
+        Some(b']') => (2..bytes.len())
+            .find_map(|i| match bytes[i] {
+                0x07                                     => Some(i + 1),
+                0x1b if bytes.get(i + 1) == Some(&b'\\') => Some(i + 2),
+                _                                        => None,
+            })
+            .unwrap_or(s.len()),
+        Some(c) if c.is_ascii() => 2,
+        _                       => 1,
+    }
+}
+
+/// Returns `s` without embedded escape sequences
+pub fn strip_escapes(s: &str) -> String {
+    let mut stripped = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(pos) = rest.find('\x1b') {
+        stripped.push_str(&rest[..pos]);
+        rest = &rest[pos + escape_len(&rest[pos..])..];
+    }
+
+    stripped + rest
+}
+
+/// Returns display width of a single character in terminal columns
+pub fn char_width(c: char) -> usize {
+    c.width().unwrap_or(0)
+}
+
+/// Splits `s` into two parts, first of which takes at most `width` terminal columns. Embedded
+/// escape sequences are never split
+pub fn split_at_width(s: &str, width: usize) -> (&str, &str) {
+    let mut taken = 0;
+    let mut i = 0;
+
+    while let Some(c) = s[i..].chars().next() {
+        if c == '\x1b' {
+            i += escape_len(&s[i..]);
+            continue;
+        }
+
+        taken += char_width(c);
+
+        if taken > width {
+            return s.split_at(i);
+        }
+
+        i += c.len_utf8();
+    }
+
+    (s, "")
+}
+
+/// Resolves `path`, given relative to directory `base`, against current directory. Paths,
+/// that end up inside current directory are kept relative
+pub fn rebase_path(path: &str, base: &std::path::Path) -> String {
+    // Normalize `..` lexically, so paths stay readable
+    let path = base.join(path).components().fold(std::path::PathBuf::new(), |mut path, c| {
+        match c {
+            std::path::Component::ParentDir if path.file_name().is_some() => { path.pop(); }
+            std::path::Component::CurDir => {}
+            c => path.push(c),
+        }
+
+        path
+    });
+
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok().map(|p| p.to_path_buf()));
+
+    relative.unwrap_or(path).to_string_lossy().to_string()
+}
+
+/// Expands a glob pattern (`*` & `?` wildcards in the last path component) into a sorted list
+/// of matching files. Pattern without wildcards is returned as is
+pub fn expand_glob(pattern: &str) -> Result<Vec<String>, String> {
+    if !pattern.contains(['*', '?']) {
+        return Ok(vec![pattern.to_string()]);
+    }
+
+    let path = std::path::Path::new(pattern);
+
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => std::path::Path::new("."),
+    };
+
+    if dir.to_string_lossy().contains(['*', '?']) {
+        return Err(format!("Wildcards are only supported in file name: '{}'", pattern));
+    }
+
+    let name = path.file_name()
+        .ok_or_else(|| format!("Invalid glob pattern: '{}'", pattern))?
+        .to_string_lossy();
+
+    // Translate wildcards into regex, escaping everything else
+    let re = name.chars()
+        .map(|c| match c {
+            '*' => ".*".to_string(),
+            '?' => ".".to_string(),
+            c   => regex::escape(c.to_string().as_str()),
+        })
+        .collect::<String>();
+
+    let re = regex::Regex::new(format!("^{}$", re).as_str()).unwrap();
+
+    let mut files = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read '{}': {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter(|entry| re.is_match(entry.file_name().to_string_lossy().as_ref()))
+        .map(|entry| {
+            if path.parent().is_some_and(|p| p.as_os_str().is_empty()) {
+                entry.file_name().to_string_lossy().to_string()
+            } else {
+                entry.path().to_string_lossy().to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if files.is_empty() {
+        return Err(format!("No files match '{}'", pattern));
     }
+
+    files.sort();
+
+    Ok(files)
+}
+
+/// Renders a horizontal bar of Unicode blocks, `fraction` (0.0 - 1.0) of which is filled.
+/// Bar takes `width` terminal columns, and uses partial blocks for sub-column precision
+pub fn bar(fraction: f32, width: usize) -> String {
+    const PARTIAL: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+    let eighths = (fraction.clamp(0.0, 1.0) * (width * 8) as f32).round() as usize;
+
+    let mut bar = "█".repeat(eighths / 8);
+
+    let partial = eighths % 8;
+
+    if partial > 0 {
+        bar.push(PARTIAL[partial]);
+    }
+
+    let filled = str_width(bar.as_str());
+
+    bar + " ".repeat(width - filled).as_str()
+}
+
+/// Percent-encodes `s` for use in URL. Only unreserved characters (alphanumerics and `-._~`)
+/// are kept as is
+pub fn url_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", b));
+        }
+    }
+
+    encoded
+}
+
+/// Stream, that output is written to
+#[derive(Copy, Clone, PartialEq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Where human-readable output (tables) goes. `false` - stdout, `true` - stderr
+static HUMAN_STREAM_IS_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Selects a stream for human-readable output (tables)
+pub fn set_human_stream(stream: Stream) {
+    HUMAN_STREAM_IS_STDERR.store(stream == Stream::Stderr, Ordering::Relaxed);
+}
+
+/// Returns a stream, that human-readable output (tables) should be written to
+pub fn human_stream() -> Stream {
+    if HUMAN_STREAM_IS_STDERR.load(Ordering::Relaxed) {
+        Stream::Stderr
+    } else {
+        Stream::Stdout
+    }
+}
+
+/// `println!` that writes into the stream, selected by `set_human_stream()`
+macro_rules! hprintln {
+    ($($arg:tt)*) => {
+        match $crate::util::human_stream() {
+            $crate::util::Stream::Stdout => println!($($arg)*),
+            $crate::util::Stream::Stderr => eprintln!($($arg)*),
+        }
+    };
 }
 
+pub(crate) use hprintln;